@@ -0,0 +1,45 @@
+//! A minimal game driven only through the library: the engine plays White against a
+//! player that always takes the first valid move, and the game is printed as an archive.
+//!
+//! Run it with `cargo run --example console_min`.
+
+use reversi_game::{
+    archive,
+    engine::{Engine, Search, SearchLimits},
+    Board, Color, Field, Game, GameStatus,
+};
+
+/// A player picks a move for a color, `None` to pass.
+type Player<'a> = Box<dyn Fn(&Board, Color) -> Option<Field> + 'a>;
+
+fn main() {
+    let engine = Engine::new();
+    let engine_player: Player =
+        Box::new(|board, color| engine.best_move(board, color, SearchLimits::depth(3)).0);
+    let first_move: Player = Box::new(|board, color| board.valid_moves(color).first().copied());
+
+    let mut game = Game::new();
+    while game.status() == GameStatus::InProgress {
+        let color = game.current_player();
+        let player = match color {
+            Color::White => &engine_player,
+            Color::Black => &first_move,
+        };
+        match player(game.board(), color) {
+            Some(field) => {
+                game.play(field).expect("players only choose valid moves");
+            }
+            None => game.pass().expect("the game goes on"),
+        }
+    }
+
+    let mut record = archive::Game::new();
+    record.set_header("White", "Engine (depth 3)");
+    record.set_header("Black", "First valid move");
+    record.set_header("Result", archive::Game::result_of(game.board()));
+    record.moves = game.history().to_vec();
+    assert_eq!(record.replay().as_ref(), Ok(game.board()));
+
+    println!("{}", game.board());
+    print!("{}", archive::write(&[record]));
+}
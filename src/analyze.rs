@@ -0,0 +1,600 @@
+//! An interactive position lab: set up positions and let the engine analyze them.
+
+use reversi_game::{
+    engine::{Engine, Search, SearchLimits},
+    reversi::*,
+    rules::Position,
+};
+
+use crate::play::input;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::ArgMatches;
+
+/// A named position to start analyzing from.
+pub struct TestPosition {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The moves leading to the position from the initial board, White moving first.
+    pub moves: &'static [&'static str],
+}
+
+pub const TEST_POSITIONS: [TestPosition; 5] = [
+    TestPosition {
+        name: "start",
+        description: "The initial position",
+        moves: &[],
+    },
+    TestPosition {
+        name: "empty",
+        description: "A board without any discs",
+        moves: &[],
+    },
+    TestPosition {
+        name: "parallel",
+        description: "The parallel opening",
+        moves: &["d3", "e3"],
+    },
+    TestPosition {
+        name: "diagonal",
+        description: "The diagonal opening",
+        moves: &["d3", "c3"],
+    },
+    TestPosition {
+        name: "perpendicular",
+        description: "The perpendicular opening",
+        moves: &["d3", "c5"],
+    },
+];
+
+impl TestPosition {
+    /// Set up the position, returning the board and the color to move.
+    pub fn setup(&self) -> (Board, Color) {
+        if self.name == "empty" {
+            return (Board::empty(), Color::White);
+        }
+
+        let mut board = Board::new();
+        let mut color = Color::White;
+        for field in self.moves {
+            board
+                .add_piece(Notation::Standard.parse(field).unwrap(), color)
+                .unwrap();
+            color = color.other();
+        }
+        (board, color)
+    }
+}
+
+/// A command of the analysis REPL.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Show,
+    Add(Field, Color),
+    Remove(Field),
+    Play(Field),
+    Turn(Option<Color>),
+    Clear,
+    Setup(String),
+    Load(String),
+    Transcript(String),
+    Go(Option<u8>),
+    Deeper(Option<u8>),
+    Tree(String, Option<u8>),
+    Regions,
+    Help,
+    Quit,
+}
+
+fn parse_color(input: &str) -> Result<Color, String> {
+    match input.to_lowercase().as_str() {
+        "w" | "white" => Ok(Color::White),
+        "b" | "black" => Ok(Color::Black),
+        _ => Err(format!("Unknown color `{input}`, use `white` or `black`")),
+    }
+}
+
+fn parse_field(input: Option<&str>) -> Result<Field, String> {
+    let input = input.ok_or("Missing field")?;
+    input
+        .parse()
+        .map_err(|error| format!("Invalid field `{input}`: {error}"))
+}
+
+/// A depth of at least one ply, searches of depth 0 don't find a move.
+fn parse_depth(input: &str) -> Result<u8, String> {
+    input
+        .parse()
+        .ok()
+        .filter(|&depth| depth > 0)
+        .ok_or_else(|| format!("Invalid depth `{input}`, use a number from 1 to 255"))
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Command::Show);
+        };
+
+        let command = match command {
+            "show" | "board" => Command::Show,
+            "add" | "set" => {
+                let field = parse_field(words.next())?;
+                let color = parse_color(words.next().ok_or("Missing color")?)?;
+                Command::Add(field, color)
+            }
+            "remove" | "rm" => Command::Remove(parse_field(words.next())?),
+            "play" => Command::Play(parse_field(words.next())?),
+            "turn" => Command::Turn(words.next().map(parse_color).transpose()?),
+            "clear" => Command::Clear,
+            "setup" => Command::Setup(words.next().ok_or("Missing position name")?.to_owned()),
+            "load" => Command::Load(words.next().ok_or("Missing file name")?.to_owned()),
+            "transcript" => {
+                let transcript: String = words.by_ref().collect();
+                if transcript.is_empty() {
+                    return Err("Missing transcript".to_owned());
+                }
+                Command::Transcript(transcript)
+            }
+            "go" | "analyze" => Command::Go(words.next().map(parse_depth).transpose()?),
+            "deeper" => Command::Deeper(
+                words
+                    .next()
+                    .map(|plies| {
+                        plies
+                            .parse()
+                            .ok()
+                            .filter(|&plies| plies > 0)
+                            .ok_or_else(|| format!("Invalid number of plies `{plies}`"))
+                    })
+                    .transpose()?,
+            ),
+            "tree" => Command::Tree(
+                words.next().ok_or("Missing file name")?.to_owned(),
+                words.next().map(parse_depth).transpose()?,
+            ),
+            "regions" => Command::Regions,
+            "help" | "?" => Command::Help,
+            "quit" | "exit" => Command::Quit,
+            other => return Err(format!("Unknown command `{other}`, try `help`")),
+        };
+
+        if words.next().is_some() {
+            Err("Too many arguments".to_owned())
+        } else {
+            Ok(command)
+        }
+    }
+}
+
+const HELP: &str = "\
+Commands:
+  show                  Redraw the board
+  add <field> <color>   Put a disc on a field
+  remove <field>        Remove the disc from a field
+  play <field>          Make a move for the side to move
+  turn [color]          Switch the side to move, or set it
+  clear                 Remove all discs
+  setup <name>          Set up a test position (see below)
+  load <file>           Set up a board drawn in a file, with rows of . X O
+  transcript <moves>    Set up the position after an Othello transcript like F5D6C3
+  go [depth]            Let the engine analyze the position, at most to the end of the game
+  deeper [plies]        Search one (or more) plies deeper than the last analysis, one by one
+  tree <file> [depth]   Save the search tree as DOT (*.dot) or JSON
+  regions               Show or hide the size of the empty region on each empty field
+  help                  Show this help
+  quit                  Leave the analysis";
+
+/// The state of an analysis session.
+pub struct Analysis {
+    pub board: Board,
+    pub to_move: Color,
+    pub depth: u8,
+    pub hints: Hints,
+    pub theme: Theme,
+    /// Whether the empty fields show the size of their region.
+    pub show_regions: bool,
+    /// The file the analysis is kept in after each search, see `resume`.
+    pub file: Option<PathBuf>,
+    /// The deepest search of the position and its evaluation, forgotten when it changes.
+    analyzed: Option<(Position, u8, i32)>,
+    engine: Engine,
+}
+
+/// The maximum number of positions written by the `tree` command.
+const TREE_LIMIT: usize = 2000;
+
+impl Analysis {
+    pub fn new(depth: u8) -> Self {
+        Analysis {
+            board: Board::new(),
+            to_move: Color::White,
+            depth,
+            hints: Hints::Color,
+            theme: Theme::default(),
+            show_regions: false,
+            file: None,
+            analyzed: None,
+            engine: Engine::new(),
+        }
+    }
+
+    fn position(&self) -> Position {
+        Position::from_board(self.board.clone(), self.to_move)
+    }
+
+    /// The number of empty fields, the deepest a search can go before the game ends.
+    fn empty_fields(&self) -> u8 {
+        let discs = self.board.count_pieces(Color::White) + self.board.count_pieces(Color::Black);
+        u8::try_from(64 - discs).unwrap()
+    }
+
+    /// Search the position `depth` plies deep, but no deeper than the end of the game, keeping
+    /// the result if it is the deepest yet.
+    fn search(&mut self, depth: u8) -> String {
+        let depth = depth.min(self.empty_fields());
+        let (best_move, evaluation) =
+            self.engine
+                .best_move(&self.board, self.to_move, SearchLimits::depth(depth));
+        let position = self.position();
+        if self
+            .analyzed
+            .as_ref()
+            .is_none_or(|(analyzed, deepest, _)| *analyzed != position || depth >= *deepest)
+        {
+            self.analyzed = Some((position, depth, evaluation));
+        }
+
+        let line: Vec<String> = self
+            .engine
+            .best_line(&self.board, self.to_move)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut message = match best_move {
+            Some(field) => format!(
+                "Depth {depth}: best move {field} ({evaluation:+}), line {}",
+                line.join(" ")
+            ),
+            None => format!("Depth {depth}: no valid moves ({evaluation:+})"),
+        };
+        if let Some(path) = &self.file {
+            if let Err(error) = self.save(path) {
+                message += &format!("\n{error}");
+            }
+        }
+        message
+    }
+
+    /// Write the position, the deepest search with its best line and the engine's cached
+    /// search results to `path`, see `resume`.
+    fn save(&self, path: &Path) -> Result<(), String> {
+        let mut contents = vec![
+            "version = 1".to_owned(),
+            format!(
+                "position = {}",
+                self.position().to_string().replace('\n', "/")
+            ),
+        ];
+        if let Some((_, depth, evaluation)) = &self.analyzed {
+            let line: Vec<String> = self
+                .engine
+                .best_line(&self.board, self.to_move)
+                .into_iter()
+                .map(|field| Notation::Standard.format(field))
+                .collect();
+            contents.push(format!("depth = {depth}"));
+            contents.push(format!("evaluation = {evaluation}"));
+            contents.push(format!("line = {}", line.join(" ")));
+        }
+        contents.push("hash".to_owned());
+        contents.push(self.engine.export_hash());
+        fs::write(path, contents.join("\n"))
+            .map_err(|error| format!("Failed to save the analysis to {}: {error}", path.display()))
+    }
+
+    /// Continue the analysis saved in `path` by `save`, or start a new one there if there is
+    /// no such file yet. Returns what was resumed.
+    pub fn resume(&mut self, path: &Path) -> Result<String, String> {
+        self.file = Some(path.to_owned());
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Ok(format!("Saving the analysis to {}", path.display()));
+        };
+        let invalid = |error: String| format!("{}: {error}", path.display());
+
+        let (header, hash) = contents
+            .split_once("\nhash\n")
+            .or_else(|| contents.strip_suffix("\nhash").map(|header| (header, "")))
+            .ok_or_else(|| invalid("Missing the cached search results".to_owned()))?;
+        let mut values = header.lines().map(|line| {
+            line.split_once(" = ")
+                .ok_or_else(|| invalid(format!("Expected `key = value`, got `{line}`")))
+        });
+        if values.next().transpose()? != Some(("version", "1")) {
+            return Err(invalid("Unsupported analysis file version".to_owned()));
+        }
+        let (mut position, mut depth, mut evaluation) = (None, None, None);
+        for value in values {
+            match value? {
+                ("position", value) => position = Some(value.parse::<Position>().map_err(invalid)?),
+                ("depth", value) => depth = value.parse().ok(),
+                ("evaluation", value) => evaluation = value.parse().ok(),
+                // The line is only there to be read, it is found again in the hash.
+                _ => {}
+            }
+        }
+        let position = position.ok_or_else(|| invalid("Missing the position".to_owned()))?;
+        let positions = self.engine.import_hash(hash).map_err(invalid)?;
+
+        self.set_position(position.clone());
+        Ok(match depth.zip(evaluation) {
+            Some((depth, evaluation)) => {
+                self.analyzed = Some((position, depth, evaluation));
+                let line: Vec<String> = self
+                    .engine
+                    .best_line(&self.board, self.to_move)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                format!(
+                    "Resumed the analysis of {} at depth {depth} ({evaluation:+}), line {}, with {positions} cached positions. Type `deeper` to go on.",
+                    path.display(),
+                    line.join(" ")
+                )
+            }
+            None => format!("Resumed {}", path.display()),
+        })
+    }
+
+    /// Analyze `position` from now on.
+    pub fn set_position(&mut self, position: Position) {
+        self.board = position.board().clone();
+        self.to_move = position.to_move();
+    }
+
+    fn redraw(&self) {
+        redraw_board(
+            &self.board,
+            &DisplayOptions {
+                color: Some(self.to_move),
+                hints: self.hints,
+                theme: self.theme.clone(),
+                marks: if self.show_regions {
+                    region_marks(&self.board)
+                } else {
+                    Vec::new()
+                },
+                title: Some("Analysis".to_owned()),
+                ..Default::default()
+            },
+        );
+        println!(
+            "{} to move | ⚪ {} ⚫ {}",
+            self.to_move,
+            self.board.count_pieces(Color::White),
+            self.board.count_pieces(Color::Black)
+        );
+    }
+
+    /// Execute a command, returning the message to show to the user.
+    /// Returns `None` if the session should end.
+    pub fn execute(&mut self, command: Command) -> Option<String> {
+        let message = match command {
+            Command::Show | Command::Help => String::new(),
+            Command::Add(field, color) => {
+                self.board.set(field, Some(color));
+                format!("Added {color} on {field}")
+            }
+            Command::Remove(field) => {
+                self.board.set(field, None);
+                format!("Removed the disc on {field}")
+            }
+            Command::Play(field) => match self.board.add_piece(field, self.to_move) {
+                Ok(captures) => {
+                    let message = format!(
+                        "{} played {field}, capturing {}",
+                        self.to_move,
+                        captures.len()
+                    );
+                    self.to_move = self.to_move.other();
+                    message
+                }
+                Err(error) => format!("Invalid move {field}: {error}"),
+            },
+            Command::Turn(color) => {
+                self.to_move = color.unwrap_or_else(|| self.to_move.other());
+                format!("{} to move", self.to_move)
+            }
+            Command::Clear => {
+                self.board = Board::empty();
+                "Cleared the board".to_owned()
+            }
+            Command::Setup(name) => {
+                match TEST_POSITIONS.iter().find(|position| position.name == name) {
+                    Some(position) => {
+                        (self.board, self.to_move) = position.setup();
+                        format!("Set up {}", position.description.to_lowercase())
+                    }
+                    None => format!("Unknown position `{name}`"),
+                }
+            }
+            Command::Load(path) => match load_position(&path) {
+                Ok(position) => {
+                    self.set_position(position);
+                    format!("Loaded {path}")
+                }
+                Err(error) => error,
+            },
+            // The cached results of earlier searches stay valid and make deeper ones faster.
+            Command::Transcript(transcript) => match Game::from_transcript(&transcript) {
+                Ok(game) => {
+                    self.set_position(game.position().clone());
+                    format!("Set up the position after {} moves", game.history().len())
+                }
+                Err(error) => format!("Invalid transcript: {error}"),
+            },
+            Command::Go(depth) => self.search(depth.unwrap_or(self.depth)),
+            Command::Deeper(plies) => {
+                let position = self.position();
+                let deepest = match &self.analyzed {
+                    Some((analyzed, depth, _)) if *analyzed == position => *depth,
+                    _ => 0,
+                };
+                let end = self.empty_fields();
+                if deepest >= end {
+                    format!("Depth {deepest} already reaches the end of the game")
+                } else {
+                    let target = deepest.saturating_add(plies.unwrap_or(1)).min(end);
+                    for depth in deepest + 1..target {
+                        // Show the progress of long runs, the last search is shown with the
+                        // board.
+                        println!("{}", self.search(depth));
+                    }
+                    self.search(target)
+                }
+            }
+            Command::Tree(path, depth) => {
+                let depth = depth.unwrap_or(self.depth).min(self.empty_fields());
+                // A fresh engine traces the whole tree, without cutoffs from the cached
+                // results, which are kept for the analysis.
+                let (_, tree) = Engine::new().traced_best_move(
+                    &self.board,
+                    self.to_move,
+                    SearchLimits::depth(depth),
+                    TREE_LIMIT,
+                );
+                let contents = if path.ends_with(".dot") {
+                    tree.to_dot()
+                } else {
+                    tree.to_json()
+                };
+                match fs::write(&path, contents) {
+                    Ok(()) => format!(
+                        "Saved {} positions to {path} ({} more not saved)",
+                        tree.nodes.len(),
+                        tree.truncated
+                    ),
+                    Err(error) => format!("Failed to write {path}: {error}"),
+                }
+            }
+            Command::Regions => {
+                self.show_regions = !self.show_regions;
+                let odd = self
+                    .board
+                    .empty_regions()
+                    .iter()
+                    .filter(|region| region.len() % 2 == 1)
+                    .count();
+                if self.show_regions {
+                    format!("{odd} of the empty regions have an odd number of fields")
+                } else {
+                    "Hid the empty regions".to_owned()
+                }
+            }
+            Command::Quit => return None,
+        };
+        Some(message)
+    }
+}
+
+impl Analysis {
+    /// The commands to complete with tab, with the fields and names they can take here.
+    fn completions(&self) -> Vec<String> {
+        let fields = Field::all().map(|field| match self.board[field] {
+            Some(_) => format!("remove {field}"),
+            None => format!("add {field}"),
+        });
+        [
+            "show",
+            "clear",
+            "go",
+            "deeper",
+            "tree",
+            "load ",
+            "transcript ",
+            "regions",
+            "help",
+            "quit",
+            "turn white",
+            "turn black",
+        ]
+        .into_iter()
+        .map(str::to_owned)
+        .chain(
+            self.board
+                .valid_moves(self.to_move)
+                .into_iter()
+                .map(|field| format!("play {field}")),
+        )
+        .chain(fields)
+        .chain(
+            TEST_POSITIONS
+                .iter()
+                .map(|position| format!("setup {}", position.name)),
+        )
+        .collect()
+    }
+}
+
+/// Read a board drawn in a file, see `Board::from_ascii`.
+pub fn load_position(path: &str) -> Result<Position, String> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {path}: {error}"))?
+        .parse()
+        .map_err(|error| format!("{path}: {error}"))
+}
+
+fn help() -> String {
+    let positions = TEST_POSITIONS
+        .iter()
+        .map(|position| format!("  {:<22}{}", position.name, position.description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{HELP}\n\nTest positions:\n{positions}")
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let mut analysis = Analysis::new(*matches.get_one::<u8>("depth").unwrap());
+    if matches.get_flag("always-hints") {
+        analysis.hints = Hints::Both;
+    } else if matches.get_flag("no-hints") {
+        analysis.hints = Hints::Off;
+    }
+    if let Some(theme) = matches.get_one::<Theme>("theme") {
+        analysis.theme = theme.clone();
+    }
+    if let Some(position) = matches.get_one::<Position>("position") {
+        analysis.set_position(position.clone());
+    }
+    let mut message = "Type `help` for a list of commands.".to_owned();
+    if let Some(path) = matches.get_one::<PathBuf>("resume-analysis") {
+        message = analysis.resume(path)?;
+    }
+
+    loop {
+        analysis.redraw();
+        println!("{message}");
+
+        let Ok(input) = input::edit_line("> ", &analysis.completions(), None) else {
+            break;
+        };
+
+        message = match input.parse::<Command>() {
+            Ok(Command::Help) => help(),
+            Ok(command) => match analysis.execute(command) {
+                Some(message) => message,
+                None => break,
+            },
+            Err(error) => error,
+        };
+    }
+    Ok(())
+}
@@ -0,0 +1,343 @@
+//! Endgame solving benchmarks over suites of test positions.
+
+use reversi_game::{engine::Engine, reversi::*};
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    time::{Duration, Instant},
+};
+
+use clap::ArgMatches;
+
+/// The positions solved by `reversi bench` if no suite is given.
+const DEFAULT_SUITE: &str = include_str!("../data/endgame.obf");
+
+/// The positions of the FFO endgame test suite solved by `reversi bench --ffo`.
+const FFO_SUITE: &str = include_str!("../data/ffo.obf");
+
+/// A position with its known exact result.
+#[derive(Debug, Clone)]
+pub struct SuitePosition {
+    pub board: Board,
+    pub to_move: Color,
+    /// The best moves and the final disc difference for the side to move.
+    /// Several moves may be equally good.
+    pub best_moves: Vec<Field>,
+    pub score: i32,
+}
+
+impl SuitePosition {
+    /// Parse a position in the OBF format used by common endgame suites (e.g. FFO):
+    /// 64 fields from a1 to h8 (`X` black, `O` white, `-` empty), the side to move,
+    /// and the best moves with their scores, e.g. `...XO--- X; b8:+24; a2:+24;`.
+    pub fn from_obf(line: &str) -> Result<Self, String> {
+        let (position, solutions) = line.split_once(';').ok_or("Missing `;`")?;
+        let mut parts = position.split_whitespace();
+        let fields = parts.next().ok_or("Missing board")?;
+        let side = parts.next().ok_or("Missing side to move")?;
+
+        if fields.chars().count() != 64 {
+            return Err(format!(
+                "Expected 64 fields, got {}",
+                fields.chars().count()
+            ));
+        }
+
+        let mut board = Board::empty();
+        for (index, piece) in fields.chars().enumerate() {
+            // The first field is a1, which is in the lowest row of this board.
+            let field = Field(index % 8, 7 - index / 8);
+            board.set(
+                field,
+                match piece {
+                    'X' | 'x' | '*' => Some(Color::Black),
+                    'O' | 'o' => Some(Color::White),
+                    '-' | '.' => None,
+                    other => return Err(format!("Invalid field `{other}`")),
+                },
+            );
+        }
+
+        let to_move = match side {
+            "X" | "x" | "*" => Color::Black,
+            "O" | "o" => Color::White,
+            other => return Err(format!("Invalid side to move `{other}`")),
+        };
+
+        let mut best_moves = Vec::new();
+        let mut score = None;
+        for solution in solutions
+            .split(';')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let (field, field_score) = solution
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid solution `{solution}`"))?;
+            let field_score: i32 = field_score
+                .trim_start_matches('+')
+                .parse()
+                .map_err(|_| format!("Invalid score `{field_score}`"))?;
+
+            match score {
+                Some(score) if field_score < score => break,
+                _ => score = Some(field_score),
+            }
+            best_moves.push(
+                Notation::Standard
+                    .parse(&field.to_lowercase())
+                    .map_err(|error| format!("Invalid move `{field}`: {error}"))?,
+            );
+        }
+
+        Ok(SuitePosition {
+            board,
+            to_move,
+            best_moves,
+            score: score.ok_or("Missing solution")?,
+        })
+    }
+
+    /// The number of empty fields.
+    pub fn empties(&self) -> usize {
+        64 - self.board.count_pieces(Color::White) - self.board.count_pieces(Color::Black)
+    }
+}
+
+/// Parse a suite of OBF positions, one per line. Empty lines and `%` comments are skipped.
+pub fn parse_suite(suite: &str) -> Result<Vec<SuitePosition>, String> {
+    suite
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('%'))
+        .map(|(index, line)| {
+            SuitePosition::from_obf(line).map_err(|error| format!("line {}: {error}", index + 1))
+        })
+        .collect()
+}
+
+/// The outcome of solving one suite position.
+#[derive(Debug, Clone)]
+pub struct SolveResult {
+    pub best_move: Option<Field>,
+    pub score: i32,
+    pub correct: bool,
+    pub nodes: u64,
+    pub time: Duration,
+}
+
+pub fn solve(position: &SuitePosition, engine: &Engine) -> SolveResult {
+    engine.reset_counters();
+    let start = Instant::now();
+    let (best_move, score) = engine.solve(&position.board, position.to_move);
+    let time = start.elapsed();
+
+    SolveResult {
+        best_move,
+        score,
+        correct: score == position.score
+            && best_move.map_or(position.best_moves.is_empty(), |field| {
+                position.best_moves.contains(&field)
+            }),
+        nodes: engine.nodes(),
+        time,
+    }
+}
+
+/// Changes of more than this many percent in time are reported as regressions.
+const REGRESSION_PERCENT: f64 = 10.0;
+
+/// Write the results as JSON with one position per line, to be read back by `parse_baseline`,
+/// and the memory used by the transposition table and its limit in bytes, see
+/// `Engine::hash_usage`.
+pub fn to_json(
+    positions: &[SuitePosition],
+    results: &[SolveResult],
+    (hash_used, hash_limit): (usize, usize),
+) -> String {
+    let lines = positions
+        .iter()
+        .zip(results)
+        .enumerate()
+        .map(|(index, (position, result))| {
+            format!(
+                "{{\"number\": {}, \"empties\": {}, \"correct\": {}, \"nodes\": {}, \"time\": {:.6}}}",
+                index + 1,
+                position.empties(),
+                result.correct,
+                result.nodes,
+                result.time.as_secs_f64()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n    ");
+
+    format!(
+        "{{\n  \"version\": \"{}\",\n  \"hash\": {{\"used\": {hash_used}, \"limit\": {hash_limit}}},\n  \"positions\": [\n    {lines}\n  ]\n}}\n",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// The value of `key` in a line of `to_json`.
+fn json_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let key = format!("\"{key}\":");
+    let rest = &line[line.find(&key)? + key.len()..];
+    Some(rest[..rest.find([',', '}']).unwrap_or(rest.len())].trim())
+}
+
+/// Read the nodes and seconds of each position, by its number, from the output of `to_json`.
+pub fn parse_baseline(json: &str) -> Result<BTreeMap<usize, (u64, f64)>, String> {
+    json.lines()
+        .filter(|line| line.contains("\"number\":"))
+        .map(|line| {
+            let value = |key| {
+                json_value(line, key).ok_or_else(|| format!("Missing `{key}` in `{}`", line.trim()))
+            };
+            let invalid = |key| format!("Invalid `{key}` in `{}`", line.trim());
+            Ok((
+                value("number")?.parse().map_err(|_| invalid("number"))?,
+                (
+                    value("nodes")?.parse().map_err(|_| invalid("nodes"))?,
+                    value("time")?.parse().map_err(|_| invalid("time"))?,
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// The change from `before` to `after` in percent, e.g. `+12.5%`.
+fn change(before: f64, after: f64) -> String {
+    if before == 0.0 {
+        "--".to_owned()
+    } else {
+        format!("{:+.1}%", (after - before) / before * 100.0)
+    }
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let suite = match matches.get_one::<String>("suite") {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|error| format!("Failed to read `{path}`: {error}"))?
+        }
+        None if matches.get_flag("ffo") => FFO_SUITE.to_owned(),
+        None => DEFAULT_SUITE.to_owned(),
+    };
+
+    let positions = parse_suite(&suite).map_err(|error| format!("Invalid suite: {error}"))?;
+
+    let baseline = match matches.get_one::<String>("baseline") {
+        Some(path) => Some(
+            fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|json| parse_baseline(&json))
+                .map_err(|error| format!("Failed to read the baseline `{path}`: {error}"))?,
+        ),
+        None => None,
+    };
+    let json = matches.get_flag("json");
+
+    let engine = Engine::new();
+    let mut results = Vec::new();
+    let mut total_nodes = 0;
+    let mut total_time = Duration::ZERO;
+    let mut solved = 0;
+    let mut regressions = 0;
+    // The totals of the baseline over the positions it has.
+    let mut baseline_total = (0, 0.0);
+
+    if !json {
+        print!(
+            "{:>3} {:>7} {:>8} {:>8} {:>7} {:>12} {:>10}",
+            "#", "empties", "expected", "result", "correct", "nodes", "time"
+        );
+        if baseline.is_some() {
+            print!(" {:>8} {:>8}", "nodes Δ", "time Δ");
+        }
+        println!();
+    }
+
+    for (index, position) in positions.iter().enumerate() {
+        let result = solve(position, &engine);
+        total_nodes += result.nodes;
+        total_time += result.time;
+        solved += usize::from(result.correct);
+        if json {
+            results.push(result);
+            continue;
+        }
+
+        let expected = format!(
+            "{}{:+}",
+            position
+                .best_moves
+                .first()
+                .map_or("--".to_owned(), ToString::to_string),
+            position.score
+        );
+        let got = format!(
+            "{}{:+}",
+            result
+                .best_move
+                .map_or("--".to_owned(), |field| field.to_string()),
+            result.score
+        );
+
+        print!(
+            "{:>3} {:>7} {:>8} {:>8} {:>7} {:>12} {:>9.3}s",
+            index + 1,
+            position.empties(),
+            expected,
+            got,
+            if result.correct { "yes" } else { "NO" },
+            result.nodes,
+            result.time.as_secs_f64()
+        );
+        if let Some(&(nodes, time)) = baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(&(index + 1)))
+        {
+            #[allow(clippy::cast_precision_loss)]
+            let nodes_change = change(nodes as f64, result.nodes as f64);
+            let time_change = change(time, result.time.as_secs_f64());
+            print!(" {nodes_change:>8} {time_change:>8}");
+            if result.time.as_secs_f64() > time * (1.0 + REGRESSION_PERCENT / 100.0) {
+                print!(" slower");
+                regressions += 1;
+            }
+            baseline_total.0 += nodes;
+            baseline_total.1 += time;
+        }
+        println!();
+    }
+
+    if json {
+        print!("{}", to_json(&positions, &results, engine.hash_usage()));
+        return Ok(());
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let nodes_per_second = total_nodes as f64 / total_time.as_secs_f64().max(f64::EPSILON);
+    println!(
+        "\n{solved}/{} correct, {total_nodes} nodes in {:.3}s ({nodes_per_second:.0} nodes/s)",
+        positions.len(),
+        total_time.as_secs_f64()
+    );
+    let (used, limit) = engine.hash_usage();
+    #[allow(clippy::cast_precision_loss)]
+    let to_megabytes = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "Hash: {:.1} / {:.0} MB",
+        to_megabytes(used),
+        to_megabytes(limit)
+    );
+    if baseline.is_some() {
+        #[allow(clippy::cast_precision_loss)]
+        let nodes_change = change(baseline_total.0 as f64, total_nodes as f64);
+        println!(
+            "Compared to the baseline: nodes {nodes_change}, time {}, {regressions} positions more than {REGRESSION_PERCENT}% slower",
+            change(baseline_total.1, total_time.as_secs_f64())
+        );
+    }
+    Ok(())
+}
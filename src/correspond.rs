@@ -0,0 +1,176 @@
+//! Correspondence games: the players take turns with one game file, e.g. by email or in a
+//! shared folder, each run of `reversi correspond` adding one move.
+
+use crate::{games, play::input};
+use reversi_game::{
+    archive,
+    reversi::*,
+    rules::{self, Variant},
+};
+
+use std::{fs, path::Path};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+/// The game of a correspondence file, checking that it wasn't changed by hand.
+fn load(path: &Path) -> Result<archive::Game, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let mut games =
+        archive::parse(&contents).map_err(|error| format!("{}: {error}", path.display()))?;
+    let game = match (games.pop(), games.is_empty()) {
+        (Some(game), true) => game,
+        _ => return Err(format!("{} must contain exactly one game", path.display())),
+    };
+    game.verify()
+        .map_err(|error| format!("{}: {error}", path.display()))?;
+    Ok(game)
+}
+
+/// A new correspondence game between the players named in `matches`.
+fn start(matches: &ArgMatches) -> archive::Game {
+    let mut saved = archive::Game::new();
+    saved.set_header("White", matches.get_one::<String>("white").unwrap());
+    saved.set_header("Black", matches.get_one::<String>("black").unwrap());
+    saved.set_header("Date", games::today());
+    saved.set_header("Result", "*");
+    saved.set_header("TimeControl", "-");
+    saved.set_header("Variant", Variant::Standard.to_string());
+    saved.add_tag("correspondence");
+    saved
+}
+
+/// Pass for the side to move as long as it has no valid moves, as the rules demand.
+fn forced_passes(game: &mut rules::Game, saved: &mut archive::Game) {
+    while game.position().must_pass() {
+        game.pass().unwrap();
+        saved.moves.push(None);
+    }
+}
+
+/// Show the game of the file in `matches`, let the side to move make one move and write the
+/// file back with a new checksum. Starts a new game if the file doesn't exist yet.
+fn correspond(matches: &ArgMatches) -> Result<String, String> {
+    let path = Path::new(matches.get_one::<String>("file").unwrap());
+    let mut saved = if path.exists() {
+        load(path)?
+    } else {
+        start(matches)
+    };
+
+    let mut game = rules::Game::from_position(saved.start()?);
+    for (index, &played) in saved.moves.iter().enumerate() {
+        game.replay_move(played)
+            .map_err(|error| format!("Move {}: {error}", index + 1))?;
+    }
+    let [white, black] =
+        ["White", "Black"].map(|header| saved.header(header).unwrap_or(header).to_owned());
+    let name = |color| match color {
+        Color::White => &white,
+        Color::Black => &black,
+    };
+
+    let color = game.current_player();
+    let last_move = match saved.moves.last() {
+        Some(Some(field)) => format!("Last move: {field}"),
+        Some(None) => "Last move: pass".to_owned(),
+        None => "The game starts.".to_owned(),
+    };
+    redraw_board(
+        game.board(),
+        &DisplayOptions {
+            title: Some(format!(
+                "{} {white} vs {} {black}",
+                Color::White,
+                Color::Black
+            )),
+            color: Some(color),
+            footer: Some(last_move),
+            ..Default::default()
+        },
+    );
+
+    if let Some(termination) = saved.header("Termination") {
+        return Ok(format!("The game is over by {termination}."));
+    }
+    if game.status() != GameStatus::InProgress {
+        return Ok(format!(
+            "The game is over: {}.",
+            saved.header("Result").unwrap_or("*")
+        ));
+    }
+
+    let completions: Vec<String> = game
+        .valid_moves()
+        .iter()
+        .map(ToString::to_string)
+        .chain(["resign", "quit"].map(str::to_owned))
+        .collect();
+    loop {
+        let Ok(input) = input::edit_line(
+            &format!("{color} {}, enter your move: ", name(color)),
+            &completions,
+            None,
+        ) else {
+            return Ok(format!("{} was left unchanged.", path.display()));
+        };
+        match input.trim().to_lowercase().as_str() {
+            "quit" => return Ok(format!("{} was left unchanged.", path.display())),
+            "resign" => {
+                if input::confirm("Really resign?") {
+                    saved.set_header("Termination", "resignation");
+                    break;
+                }
+            }
+            input => match input.parse::<Field>() {
+                Ok(field) => match game.play(field) {
+                    Ok(_) => {
+                        saved.moves.push(Some(field));
+                        forced_passes(&mut game, &mut saved);
+                        break;
+                    }
+                    Err(error) => println!("Invalid move {field}: {error}"),
+                },
+                Err(error) => println!("Invalid input: {error}, try a field like {}", Field(3, 5)),
+            },
+        }
+    }
+
+    saved.set_header("Result", archive::Game::result_of(game.board()));
+    saved.sign();
+    fs::write(path, archive::write(std::slice::from_ref(&saved)))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+
+    let next = game.current_player();
+    Ok(if saved.header("Termination").is_some() {
+        format!(
+            "You resigned. Send {} to {} to let them know.",
+            path.display(),
+            name(color.other())
+        )
+    } else if game.status() == GameStatus::InProgress {
+        format!(
+            "Saved. Send {} to {}, who plays {next} next.",
+            path.display(),
+            name(next)
+        )
+    } else {
+        format!(
+            "The game is over: {}. Send {} to {} and keep it with `reversi games import`.",
+            saved.header("Result").unwrap_or("*").bold(),
+            path.display(),
+            name(color.other())
+        )
+    })
+}
+
+pub fn run(matches: &ArgMatches) {
+    match correspond(matches) {
+        Ok(message) => println!("{message}"),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Crash reports: when the program panics, the state of the game being played is written to a
+//! directory that can be attached to a bug report.
+
+use reversi_game::{
+    archive::{self, Game},
+    reversi::*,
+};
+
+use std::{
+    backtrace::Backtrace,
+    env, fs, io,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    process,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The game being played and the board before its next move.
+static SNAPSHOT: Mutex<Option<(Game, Board, Color)>> = Mutex::new(None);
+
+/// Write a crash report on every panic, after the usual panic message.
+pub fn install() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        match write_report(info) {
+            Ok(directory) => eprintln!(
+                "\nA crash report was written to {}. Please attach it to a bug report.",
+                directory.display()
+            ),
+            Err(error) => eprintln!("\nFailed to write a crash report: {error}"),
+        }
+    }));
+}
+
+/// Remember the game so far, with `board` the position before `to_move`'s move.
+pub fn record(game: &Game, board: &Board, to_move: Color) {
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        *snapshot = Some((game.clone(), board.clone(), to_move));
+    }
+}
+
+/// Forget the recorded game once it is over.
+pub fn forget() {
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        *snapshot = None;
+    }
+}
+
+/// Write the report into a new directory in the temporary directory and return its path.
+fn write_report(info: &PanicHookInfo) -> io::Result<PathBuf> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let directory = env::temp_dir().join(format!("reversi-crash-{seconds}-{}", process::id()));
+    fs::create_dir_all(&directory)?;
+
+    let report = format!(
+        "reversi {} on {} ({})\narguments: {}\n\n{info}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        env::consts::OS,
+        env::consts::ARCH,
+        env::args().collect::<Vec<_>>().join(" "),
+        Backtrace::force_capture()
+    );
+    fs::write(directory.join("report.txt"), report)?;
+
+    // A panic while the snapshot was being recorded leaves it poisoned, but still readable.
+    let snapshot = SNAPSHOT
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    if let Some((game, board, to_move)) = snapshot {
+        fs::write(directory.join("game.rgn"), archive::write(&[game]))?;
+        let theme = Theme::named("letters").unwrap();
+        fs::write(
+            directory.join("position.txt"),
+            format!(
+                "{}\n{to_move:?} to move\n",
+                board.render_compact(&[to_move], &theme, None, &[])
+            ),
+        )?;
+    }
+
+    Ok(directory)
+}
@@ -0,0 +1,67 @@
+//! An attract mode for displays at events: bots play each other forever, without any input.
+
+use crate::play::{self, Opponent};
+use reversi_game::{board::display::clear_screen, reversi::*};
+
+use std::{thread, time::Duration};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+const BANNER: &str = r"
+ ____                          _
+|  _ \ _____   _____ _ __ ___(_)
+| |_) / _ \ \ / / _ \ '__/ __| |
+|  _ <  __/\ V /  __/ |  \__ \ |
+|_| \_\___| \_/ \___|_|  |___/_|
+";
+
+fn splash(game: usize, wins: (usize, usize, usize), pause: Duration) {
+    clear_screen();
+    println!("{}", BANNER.bold());
+    println!("Game {game} starts in {}s\n", pause.as_secs());
+    println!(
+        "{} {} wins   {} {} wins   {} draws",
+        Color::White,
+        wins.0,
+        Color::Black,
+        wins.1,
+        wins.2
+    );
+    thread::sleep(pause);
+}
+
+pub fn run(matches: &ArgMatches) {
+    let depth = matches.get_one::<u8>("depth").unwrap().to_string();
+    let delay = matches.get_one::<u64>("delay").unwrap().to_string();
+    let pause = Duration::from_secs(*matches.get_one::<u64>("pause").unwrap());
+    let games = matches.get_one::<usize>("games").copied();
+
+    // The game loop takes its settings from the top-level options.
+    let mut args = vec![
+        "reversi",
+        "--watch",
+        "--depth",
+        &depth,
+        "--watch-delay",
+        &delay,
+    ];
+    if let Some(address) = matches.get_one::<String>("broadcast") {
+        args.extend(["--broadcast", address]);
+    }
+    let play_matches = crate::cli().get_matches_from(args);
+
+    let mut wins = (0, 0, 0);
+    for game in (1..).take_while(|&game| games.is_none_or(|games| game <= games)) {
+        splash(game, wins, pause);
+
+        match play::run(&Opponent::Demo, &play_matches).map(|result| result.status) {
+            Ok(GameStatus::Win(Color::White)) => wins.0 += 1,
+            Ok(GameStatus::Win(Color::Black)) => wins.1 += 1,
+            Ok(_) => wins.2 += 1,
+            // Keep the show going.
+            Err(error) => eprintln!("{error}"),
+        }
+        thread::sleep(pause);
+    }
+}
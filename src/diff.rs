@@ -0,0 +1,123 @@
+//! `reversi diff`: one board showing how two positions differ, e.g. to find where an engine or
+//! a protocol went out of sync. Unchanged discs are dim, new discs green, removed discs red and
+//! flipped discs yellow, and each changed field is also marked with `+`, `-` or `~`.
+
+use reversi_game::{board::FieldChange, reversi::*, rules::Position};
+
+use std::{path::Path, process};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+/// A position from a file, or written on one line with `/` between the rows.
+pub fn parse_position(value: &str) -> Result<Position, String> {
+    if Path::new(value).is_file() {
+        crate::analyze::load_position(value)
+    } else {
+        value
+            .parse()
+            .map_err(|error| format!("not a readable file or a position: {error}"))
+    }
+}
+
+/// The letter of a disc like in `Board::to_ascii`.
+fn letter(color: Color) -> &'static str {
+    match color {
+        Color::Black => "X",
+        Color::White => "O",
+    }
+}
+
+/// The board `after` with the changes from `before` marked, mirrored if the `TextDirection` is
+/// right to left.
+fn render(after: &Board, changes: &[(Field, FieldChange)]) -> String {
+    let notation = Notation::current();
+    let direction = TextDirection::current();
+    let files: Vec<String> = direction
+        .columns()
+        .iter()
+        .map(|&x| format!("{:<2}", notation.file(x)))
+        .collect();
+    let mut out = match direction {
+        TextDirection::LeftToRight => format!("   {}\n", files.concat().trim_end()),
+        TextDirection::RightToLeft => format!("{}\n", files.concat().trim_end()),
+    };
+    for y in 0..8 {
+        let row: Vec<String> = direction
+            .columns()
+            .into_iter()
+            .map(|x| {
+                let field = Field(x, y);
+                match changes.iter().find(|(changed, _)| *changed == field) {
+                    Some((_, FieldChange::Added(color))) => {
+                        format!("{}+", letter(*color).green().bold())
+                    }
+                    Some((_, FieldChange::Removed(color))) => {
+                        format!("{}-", letter(*color).red().bold())
+                    }
+                    Some((_, FieldChange::Flipped(color))) => {
+                        format!("{}~", letter(*color).yellow().bold())
+                    }
+                    None => match after[field] {
+                        Some(color) => format!("{} ", letter(color).dimmed()),
+                        None => format!("{} ", ".".dimmed()),
+                    },
+                }
+            })
+            .collect();
+        out += &match direction {
+            TextDirection::LeftToRight => {
+                format!("{:>2} {}\n", notation.rank(y), row.concat().trim_end())
+            }
+            TextDirection::RightToLeft => format!("{}{}\n", row.concat(), notation.rank(y)),
+        };
+    }
+    direction.align(&out)
+}
+
+/// The changed fields of one kind, like `added: d3 (O), c4 (O)`.
+fn list(changes: &[(Field, FieldChange)], name: &str, kind: fn(FieldChange) -> Option<Color>) {
+    let fields: Vec<String> = changes
+        .iter()
+        .filter_map(|&(field, change)| {
+            kind(change).map(|color| format!("{field} ({})", letter(color)))
+        })
+        .collect();
+    if !fields.is_empty() {
+        println!("{name}: {}", fields.join(", "));
+    }
+}
+
+/// Show the difference and exit with 1 if the positions differ, like `diff`.
+pub fn run(matches: &ArgMatches) {
+    let before = matches.get_one::<Position>("before").unwrap();
+    let after = matches.get_one::<Position>("after").unwrap();
+    let changes = before.board().diff(after.board());
+
+    print!("{}", render(after.board(), &changes));
+    list(&changes, "added", |change| match change {
+        FieldChange::Added(color) => Some(color),
+        _ => None,
+    });
+    list(&changes, "removed", |change| match change {
+        FieldChange::Removed(color) => Some(color),
+        _ => None,
+    });
+    list(&changes, "flipped", |change| match change {
+        FieldChange::Flipped(color) => Some(color),
+        _ => None,
+    });
+    if before.to_move() != after.to_move() {
+        println!(
+            "to move: {} before, {} after",
+            letter(before.to_move()),
+            letter(after.to_move())
+        );
+    }
+
+    if changes.is_empty() && before.to_move() == after.to_move() {
+        println!("The positions are the same.");
+    } else {
+        process::exit(1);
+    }
+}
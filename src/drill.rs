@@ -0,0 +1,389 @@
+//! Tactics drills: positions from random games where a corner or a wedge can be won within a
+//! few plies, presented as puzzles with hints.
+
+use crate::{games, play::input};
+use reversi_game::{reversi::*, rules::Position};
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use clap::ArgMatches;
+use rand::seq::SliceRandom;
+use reversi_game::board::display::style::Colorize;
+
+/// The themes of the drills.
+pub const THEMES: [&str; 2] = ["corners", "edges"];
+
+/// The number of random games searched for drills before giving up.
+const MAX_GAMES: usize = 5000;
+/// Drills are taken from positions after at least this many plies.
+const MIN_PLIES: usize = 12;
+
+const CORNERS: [Field; 4] = [Field(0, 0), Field(7, 0), Field(0, 7), Field(7, 7)];
+
+/// The kind of tactic a drill is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrillTheme {
+    /// Force a corner: after the move, the opponent can't stop the player from taking one.
+    Corners,
+    /// Wedge in between two of the opponent's discs on an edge, where they can't retake it.
+    Edges,
+}
+
+impl FromStr for DrillTheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "corners" => Ok(DrillTheme::Corners),
+            "edges" => Ok(DrillTheme::Edges),
+            other => Err(format!("Unknown theme `{other}`")),
+        }
+    }
+}
+
+impl DrillTheme {
+    /// What to look for in a position of the theme.
+    fn task(self) -> &'static str {
+        match self {
+            DrillTheme::Corners => "Find the move that wins a corner by force.",
+            DrillTheme::Edges => "Find the wedge on an edge.",
+        }
+    }
+
+    /// The moves of `color` that solve the drill on `board`, with the fields they win:
+    /// the corners that can be taken next or the wedge itself. Empty if there is no drill.
+    fn solutions(self, board: &Board, color: Color) -> Vec<(Field, Vec<Field>)> {
+        let moves = board.valid_moves(color);
+        let solutions: Vec<(Field, Vec<Field>)> = match self {
+            DrillTheme::Corners if moves.iter().any(|field| CORNERS.contains(field)) => Vec::new(),
+            DrillTheme::Corners => moves
+                .iter()
+                .filter_map(|&field| Some((field, forced_corners(board, field, color)?)))
+                .collect(),
+            DrillTheme::Edges => moves
+                .iter()
+                .filter(|&&field| is_wedge(board, field, color))
+                .map(|&field| (field, vec![field]))
+                .collect(),
+        };
+
+        // Drills with many solutions are too easy.
+        if solutions.len() > 2 || solutions.len() == moves.len() {
+            return Vec::new();
+        }
+        solutions
+    }
+
+    /// The hints for a drill, from vague to almost the answer, with fields to mark.
+    fn hints(self, color: Color, solutions: &[(Field, Vec<Field>)]) -> Vec<(String, Vec<Field>)> {
+        let opponent = color.other();
+        let targets: Vec<Field> = solutions
+            .iter()
+            .flat_map(|(_, won)| won.iter().copied())
+            .collect();
+        match self {
+            DrillTheme::Corners => vec![
+                (
+                    format!(
+                        "Look for a move that threatens a corner in a way {opponent} can't parry."
+                    ),
+                    Vec::new(),
+                ),
+                (
+                    "These corners become yours in three moves.".to_owned(),
+                    targets,
+                ),
+            ],
+            DrillTheme::Edges => vec![
+                (
+                    format!("Is there a gap between two {opponent} discs on an edge?"),
+                    Vec::new(),
+                ),
+                (
+                    format!("Look at the {} edge.", edge_name(targets[0])),
+                    Vec::new(),
+                ),
+            ],
+        }
+    }
+
+    /// Why `field` solves the drill.
+    fn explanation(self, color: Color, field: Field, won: &[Field]) -> String {
+        let opponent = color.other();
+        let won = won
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" or ");
+        match self {
+            DrillTheme::Corners => {
+                format!("After {field}, whatever {opponent} plays, {color} can take {won}.")
+            }
+            DrillTheme::Edges => format!(
+                "{field} wedges in between two {opponent} discs, and {opponent} can't flip it back."
+            ),
+        }
+    }
+}
+
+/// The corners `color` can take after playing `field` whatever the opponent replies, if there
+/// are any and the opponent can't take a corner first.
+fn forced_corners(board: &Board, field: Field, color: Color) -> Option<Vec<Field>> {
+    let after = board.with_move(field, color).ok()?;
+
+    let replies = after.valid_moves(color.other());
+    if replies.iter().any(|reply| CORNERS.contains(reply)) {
+        return None;
+    }
+    let corners = |board: &Board| {
+        CORNERS
+            .into_iter()
+            .filter(|&corner| board.is_valid(corner, color))
+            .collect::<Vec<_>>()
+    };
+
+    let mut forced = corners(&after);
+    if !replies.is_empty() {
+        forced = replies
+            .iter()
+            .map(|&reply| corners(&after.with_move(reply, color.other()).unwrap()))
+            .reduce(|common, corners| {
+                common
+                    .into_iter()
+                    .filter(|corner| corners.contains(corner))
+                    .collect()
+            })
+            .unwrap();
+    }
+    (!forced.is_empty()).then_some(forced)
+}
+
+/// The neighbors of an edge field along its edge, for fields that aren't corners.
+fn edge_neighbors(field: Field) -> Option<[Field; 2]> {
+    let Field(x, y) = field;
+    match (x, y) {
+        _ if CORNERS.contains(&field) => None,
+        (0 | 7, _) => Some([Field(x, y - 1), Field(x, y + 1)]),
+        (_, 0 | 7) => Some([Field(x - 1, y), Field(x + 1, y)]),
+        _ => None,
+    }
+}
+
+fn edge_name(field: Field) -> &'static str {
+    match field {
+        Field(0, _) => "left",
+        Field(7, _) => "right",
+        Field(_, 0) => "top",
+        _ => "bottom",
+    }
+}
+
+/// Whether `color` playing `field` wedges a disc in between two of the opponent's discs on an
+/// edge, where the opponent can't flip it back right away.
+fn is_wedge(board: &Board, field: Field, color: Color) -> bool {
+    let Some(neighbors) = edge_neighbors(field) else {
+        return false;
+    };
+    if neighbors
+        .iter()
+        .any(|&neighbor| board[neighbor] != Some(color.other()))
+    {
+        return false;
+    }
+
+    let Ok(after) = board.with_move(field, color) else {
+        return false;
+    };
+    after
+        .valid_moves(color.other())
+        .into_iter()
+        .all(|reply| after.with_move(reply, color.other()).unwrap()[field] == Some(color))
+}
+
+/// A position of a drill with its solutions.
+struct Drill {
+    position: Position,
+    solutions: Vec<(Field, Vec<Field>)>,
+}
+
+/// Find `count` drills of `theme` in random games.
+fn generate(theme: DrillTheme, count: usize) -> Vec<Drill> {
+    let mut rng = rand::thread_rng();
+    let mut drills = Vec::new();
+
+    for _ in 0..MAX_GAMES {
+        let mut position = Position::new();
+        let mut ply = 0;
+        while position.status() == GameStatus::InProgress {
+            let color = position.to_move();
+            if ply >= MIN_PLIES {
+                let solutions = theme.solutions(position.board(), color);
+                if !solutions.is_empty() {
+                    drills.push(Drill {
+                        position: position.clone(),
+                        solutions,
+                    });
+                    // One drill per game keeps them varied.
+                    break;
+                }
+            }
+            let played = position
+                .board()
+                .valid_moves(color)
+                .choose(&mut rng)
+                .copied();
+            position.play(played).unwrap();
+            ply += 1;
+        }
+        if drills.len() == count {
+            break;
+        }
+    }
+
+    drills
+}
+
+/// The file the results of the drills are appended to, next to the saved games:
+/// one `date theme solved|failed` line per drill.
+pub fn results_path() -> PathBuf {
+    games::directory().join("drills")
+}
+
+/// Append the result of a drill to the results, see `results_path`.
+fn record(theme: &str, solved: bool) -> io::Result<()> {
+    let path = results_path();
+    fs::create_dir_all(path.parent().unwrap_or(&path))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let result = if solved { "solved" } else { "failed" };
+    writeln!(file, "{} {theme} {result}", games::today())
+}
+
+/// How a drill ended.
+enum Outcome {
+    Solved { hints: usize, attempts: usize },
+    Skipped,
+    Quit,
+}
+
+fn play_drill(drill: &Drill, theme: DrillTheme, title: &str) -> Outcome {
+    let board = drill.position.board();
+    let color = drill.position.to_move();
+    let hints = theme.hints(color, &drill.solutions);
+    let mut hints_shown = 0;
+    let mut attempts = 0;
+    let mut message = format!("{color} to move. {}", theme.task());
+
+    let completions: Vec<String> = board
+        .valid_moves(color)
+        .iter()
+        .map(ToString::to_string)
+        .chain(["hint", "solution", "quit"].map(str::to_owned))
+        .collect();
+
+    loop {
+        let marks = hints[..hints_shown]
+            .iter()
+            .flat_map(|(_, fields)| fields.iter().map(|&field| (field, "?".to_owned())))
+            .collect();
+        redraw_board(
+            board,
+            &DisplayOptions {
+                color: Some(color),
+                title: Some(title.to_owned()),
+                marks,
+                ..Default::default()
+            },
+        );
+        println!("{message}");
+
+        let Ok(input) = input::edit_line("> ", &completions, None) else {
+            return Outcome::Quit;
+        };
+        message = match input.trim().to_lowercase().as_str() {
+            "" => continue,
+            "quit" => return Outcome::Quit,
+            "hint" => match hints.get(hints_shown) {
+                Some((hint, _)) => {
+                    hints_shown += 1;
+                    hint.clone()
+                }
+                None => "No more hints, type `solution` to see it.".to_owned(),
+            },
+            "solution" => {
+                let (field, won) = &drill.solutions[0];
+                println!("{}", theme.explanation(color, *field, won));
+                let _ = input::edit_line("Press <Enter> for the next drill.", &[], None);
+                return Outcome::Skipped;
+            }
+            input => match input.parse::<Field>() {
+                Ok(field) => {
+                    attempts += 1;
+                    match drill
+                        .solutions
+                        .iter()
+                        .find(|(solution, _)| *solution == field)
+                    {
+                        Some((_, won)) => {
+                            println!(
+                                "{} {}",
+                                "Correct!".bold().green(),
+                                theme.explanation(color, field, won)
+                            );
+                            let _ =
+                                input::edit_line("Press <Enter> for the next drill.", &[], None);
+                            return Outcome::Solved {
+                                hints: hints_shown,
+                                attempts,
+                            };
+                        }
+                        None if board.is_valid(field, color) => {
+                            format!("{field} doesn't do it, try again or type `hint`.")
+                        }
+                        None => format!("{field} isn't a valid move."),
+                    }
+                }
+                Err(error) => format!("Invalid input: {error}"),
+            },
+        };
+    }
+}
+
+pub fn run(matches: &ArgMatches) {
+    let name = matches.get_one::<String>("theme").unwrap();
+    let theme: DrillTheme = name.parse().unwrap();
+    let count = *matches.get_one::<u64>("count").unwrap() as usize;
+
+    let drills = generate(theme, count);
+    if drills.len() < count {
+        eprintln!("Found only {} of {count} drills.", drills.len());
+    }
+
+    let mut solved = 0;
+    let mut clean = 0;
+    let mut played = 0;
+    for (index, drill) in drills.iter().enumerate() {
+        let title = format!("Drill {}/{}: {name}", index + 1, drills.len());
+        let outcome = play_drill(drill, theme, &title);
+        match outcome {
+            Outcome::Solved { hints, attempts } => {
+                solved += 1;
+                if hints == 0 && attempts == 1 {
+                    clean += 1;
+                }
+            }
+            Outcome::Skipped => {}
+            Outcome::Quit => break,
+        }
+        played += 1;
+        if let Err(error) = record(name, matches!(outcome, Outcome::Solved { .. })) {
+            eprintln!("Failed to save the result: {error}");
+        }
+    }
+
+    println!("Solved {solved} of {played} drills, {clean} at the first try without hints.");
+}
@@ -0,0 +1,263 @@
+//! Engine duels: two bots play several games at once, shown side by side while they think.
+
+use crate::games;
+use reversi_game::{
+    archive,
+    board::display::{clear_screen, side_by_side, terminal_width},
+    engine::{Engine, Search, SearchLimits},
+    reversi::*,
+    rules::{Game, Variant},
+};
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::ArgMatches;
+use rand::seq::SliceRandom;
+use reversi_game::board::display::style::Colorize;
+
+/// The short names of the two bots on the dashboard.
+const LABELS: [&str; 2] = ["A", "B"];
+
+/// How often the dashboard is redrawn.
+const REFRESH: Duration = Duration::from_millis(200);
+
+/// The number of random moves each pair of games starts with, as the bots would play the same
+/// game over and over otherwise.
+const OPENING_PLIES: usize = 4;
+
+/// One of the games of the duel, shared by the thread playing it and the dashboard.
+#[derive(Default)]
+struct Table {
+    game: Game,
+    /// Which bot plays White, an index into `LABELS`.
+    white: usize,
+    /// The time White and Black spent thinking.
+    clocks: [Duration; 2],
+    /// When the side to move started thinking, while it does.
+    thinking: Option<Instant>,
+}
+
+impl Table {
+    /// The bot playing `color`.
+    fn bot(&self, color: Color) -> usize {
+        match color {
+            Color::White => self.white,
+            Color::Black => 1 - self.white,
+        }
+    }
+
+    /// The time `color` spent thinking, including the current search.
+    fn clock(&self, color: Color) -> Duration {
+        let index = usize::from(color == Color::Black);
+        let running = match self.thinking {
+            Some(started) if self.game.current_player() == color => started.elapsed(),
+            _ => Duration::ZERO,
+        };
+        self.clocks[index] + running
+    }
+
+    /// The winning bot, `None` for a draw or a game in progress.
+    fn winner(&self) -> Option<usize> {
+        match self.game.status() {
+            GameStatus::Win(color) => Some(self.bot(color)),
+            _ => None,
+        }
+    }
+
+    /// The game's panel on the dashboard: the bots with their discs, the board and the clocks.
+    fn panel(&self, number: usize) -> String {
+        let board = self.game.board();
+        let status = match self.game.status() {
+            GameStatus::InProgress => format!("{} to move", self.game.current_player()),
+            GameStatus::Win(color) => format!("{} wins", LABELS[self.bot(color)]),
+            GameStatus::Draw => "draw".to_owned(),
+        };
+        let side = |color| {
+            format!(
+                "{} {color} {:2} {}",
+                LABELS[self.bot(color)],
+                board.count_pieces(color),
+                format_clock(self.clock(color))
+            )
+        };
+        format!(
+            "Game {number}: {status}\n{}\n{}\n{}",
+            board
+                .render_compact(&[], &Theme::default(), None, &[])
+                .trim_end(),
+            side(Color::White),
+            side(Color::Black),
+        )
+    }
+
+    /// The game for the games directory.
+    fn record(&self, names: &[String; 2]) -> archive::Game {
+        let mut saved = archive::Game::new();
+        saved.set_header("White", &names[self.white]);
+        saved.set_header("Black", &names[1 - self.white]);
+        saved.set_header("Date", games::today());
+        saved.set_header("Result", archive::Game::result_of(self.game.board()));
+        saved.set_header("TimeControl", "-");
+        saved.set_header("Variant", Variant::of(self.game.start()).to_string());
+        saved.add_tag("duel");
+        saved.moves = self.game.history().to_vec();
+        saved
+    }
+}
+
+/// A game after `OPENING_PLIES` random moves.
+fn random_opening() -> Game {
+    let mut game = Game::new();
+    for _ in 0..OPENING_PLIES {
+        let field = game.valid_moves().choose(&mut rand::thread_rng()).copied();
+        game.play_move(field).unwrap();
+    }
+    game
+}
+
+/// A clock like `1:05.3`.
+fn format_clock(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// Play the game at `index` to the end, the bots searching `depths` deep, pausing `delay`
+/// after each move so the game can be followed.
+fn play(tables: &Mutex<Vec<Table>>, index: usize, depths: [u8; 2], delay: Duration) {
+    let engines = [Engine::new(), Engine::new()];
+    loop {
+        let started = Instant::now();
+        let (board, color, bot) = {
+            let mut tables = tables.lock().unwrap();
+            let table = &mut tables[index];
+            if table.game.status() != GameStatus::InProgress {
+                return;
+            }
+            table.thinking = Some(started);
+            let color = table.game.current_player();
+            (table.game.board().clone(), color, table.bot(color))
+        };
+
+        let (best_move, _) =
+            engines[bot].best_move(&board, color, SearchLimits::depth(depths[bot]));
+
+        {
+            let mut tables = tables.lock().unwrap();
+            let table = &mut tables[index];
+            table.clocks[usize::from(color == Color::Black)] += started.elapsed();
+            table.thinking = None;
+            table.game.play_move(best_move).unwrap();
+        }
+        thread::sleep(delay);
+    }
+}
+
+/// Draw all games in a grid, as many side by side as the terminal is wide enough for.
+fn redraw(tables: &[Table], title: &str) {
+    // The width of a panel with its gap, see `Table::panel`.
+    const PANEL_WIDTH: usize = 21;
+
+    let score = |bot| {
+        tables
+            .iter()
+            .filter(|table| table.winner() == Some(bot))
+            .count()
+    };
+    let finished: Vec<&Table> = tables
+        .iter()
+        .filter(|table| table.game.status() != GameStatus::InProgress)
+        .collect();
+    let draws = finished
+        .iter()
+        .filter(|table| table.game.status() == GameStatus::Draw)
+        .count();
+
+    let panels: Vec<String> = tables
+        .iter()
+        .enumerate()
+        .map(|(index, table)| table.panel(index + 1))
+        .collect();
+    let columns = terminal_width().map_or(4, |width| width / PANEL_WIDTH);
+
+    clear_screen();
+    println!("{}\n", title.bold());
+    println!("{}\n", side_by_side(&panels, columns));
+    println!(
+        "{} {}  {} {}  draws {draws}  ({} of {} games over)",
+        LABELS[0],
+        score(0),
+        LABELS[1],
+        score(1),
+        finished.len(),
+        tables.len()
+    );
+}
+
+pub fn run(matches: &ArgMatches) {
+    let games = usize::from(*matches.get_one::<u8>("games").unwrap());
+    let depths: Vec<u8> = matches.get_many::<u8>("depths").unwrap().copied().collect();
+    let depths = [depths[0], depths[1]];
+    let delay = Duration::from_millis(*matches.get_one::<u64>("delay").unwrap());
+    let names = depths.map(|depth| format!("Minimax Bot (depth {depth})"));
+    let title = format!("{}: {} vs {}: {}", LABELS[0], names[0], LABELS[1], names[1]);
+
+    // Each bot plays both sides of every opening, so neither is favored by the openings.
+    let mut opening = random_opening();
+    let tables = Mutex::new(
+        (0..games)
+            .map(|index| {
+                if index % 2 == 0 {
+                    opening = random_opening();
+                }
+                Table {
+                    game: opening.clone(),
+                    white: index % 2,
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>(),
+    );
+    thread::scope(|scope| {
+        let threads: Vec<_> = (0..games)
+            .map(|index| {
+                let tables = &tables;
+                scope.spawn(move || play(tables, index, depths, delay))
+            })
+            .collect();
+        while !threads.iter().all(|thread| thread.is_finished()) {
+            redraw(&tables.lock().unwrap(), &title);
+            thread::sleep(REFRESH);
+        }
+    });
+
+    let tables = tables.into_inner().unwrap();
+    redraw(&tables, &title);
+    println!();
+    for (index, table) in tables.iter().enumerate() {
+        let board = table.game.board();
+        let result = match table.winner() {
+            Some(bot) => format!("{} won", LABELS[bot]),
+            None => "draw".to_owned(),
+        };
+        println!(
+            "Game {}: {} {} {:2} - {:2} {} {}  {result}",
+            index + 1,
+            Color::White,
+            LABELS[table.white],
+            board.count_pieces(Color::White),
+            board.count_pieces(Color::Black),
+            LABELS[1 - table.white],
+            Color::Black,
+        );
+
+        if matches.get_flag("save") {
+            if let Err(error) = games::save(&table.record(&names)) {
+                eprintln!("Failed to save game {}: {error}", index + 1);
+            }
+        }
+    }
+}
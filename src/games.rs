@@ -0,0 +1,485 @@
+//! The collection of saved games, and importing and exporting it as a single archive.
+
+pub mod batch;
+pub mod csv;
+pub mod replay;
+
+use reversi_game::{
+    archive::{self, Game},
+    engine::EndgameCache,
+    ggf,
+    rules::{self, Position},
+    wthor,
+};
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::ArgMatches;
+
+/// The directory the games are saved in:
+/// `$REVERSI_GAMES`, or `reversi/games` in the user's data directory.
+pub fn directory() -> PathBuf {
+    if let Some(directory) = env::var_os("REVERSI_GAMES") {
+        return directory.into();
+    }
+
+    let data = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .unwrap_or_default();
+    data.join("reversi").join("games")
+}
+
+/// The file the endgames solved while analyzing games are kept in, next to the saved games.
+fn endgames_path() -> PathBuf {
+    directory().join("endgames")
+}
+
+/// The endgames solved in earlier runs, none if they can't be read.
+pub fn load_endgames() -> EndgameCache {
+    fs::read_to_string(endgames_path())
+        .ok()
+        .and_then(|endgames| endgames.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Keep the solved endgames for the next runs, see `load_endgames`.
+pub fn save_endgames(endgames: &EndgameCache) -> io::Result<()> {
+    let path = endgames_path();
+    fs::create_dir_all(path.parent().unwrap_or(&path))?;
+    fs::write(path, endgames.to_string())
+}
+
+/// Today's date in the `YYYY.MM.DD` format of the `Date` header.
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs() / 86400);
+
+    // Convert days since 1970-01-01 to a civil date (Howard Hinnant's algorithm).
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year}.{month:02}.{day:02}")
+}
+
+/// All saved games with their files, oldest first.
+pub fn load_all() -> io::Result<Vec<(PathBuf, Game)>> {
+    load_dir(&directory())
+}
+
+/// All games of the archive files in `directory` with their files, sorted by file name.
+pub fn load_dir(directory: &Path) -> io::Result<Vec<(PathBuf, Game)>> {
+    let mut paths = match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "rgn"));
+    paths.sort();
+
+    let mut games = Vec::new();
+    for path in paths {
+        let contents = fs::read_to_string(&path)?;
+        let parsed = archive::parse(&contents).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })?;
+        games.extend(parsed.into_iter().map(|game| (path.clone(), game)));
+    }
+    Ok(games)
+}
+
+/// Whether `game` has all of `tags`.
+fn tagged(game: &Game, tags: &[&String]) -> bool {
+    tags.iter().all(|tag| game.has_tag(tag))
+}
+
+/// Save a game as a new file in the games directory.
+pub fn save(game: &Game) -> io::Result<PathBuf> {
+    let directory = directory();
+    fs::create_dir_all(&directory)?;
+
+    let date = game
+        .header("Date")
+        .map_or_else(today, str::to_owned)
+        .replace('.', "-");
+    let path = (1..)
+        .map(|number| directory.join(format!("{date}-{number:03}.rgn")))
+        .find(|path| !path.exists())
+        .unwrap();
+    fs::write(&path, archive::write(std::slice::from_ref(game)))?;
+    Ok(path)
+}
+
+/// Overwrite the file `path` with `game` alone, e.g. with an adjourned game that was resumed.
+pub fn replace(path: &Path, game: &Game) -> io::Result<()> {
+    fs::write(path, archive::write(std::slice::from_ref(game)))
+}
+
+/// The game of the file `path` if it is an adjourned game, see `reversi --resume`.
+pub fn load_adjourned(path: &Path) -> Result<Game, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let mut games =
+        archive::parse(&contents).map_err(|error| format!("{}: {error}", path.display()))?;
+    match (games.pop(), games.is_empty()) {
+        (Some(game), true) if game.header("Termination") == Some("adjourned") => Ok(game),
+        (Some(_), true) => Err(format!("{} is not an adjourned game", path.display())),
+        _ => Err(format!("{} must contain exactly one game", path.display())),
+    }
+}
+
+/// Whether `contents` are Othello transcripts, one game per line, rather than an archive.
+fn is_transcripts(contents: &str) -> bool {
+    contents
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
+}
+
+/// The games of transcripts, one per line, see `rules::Game::from_transcript`.
+fn parse_transcripts(contents: &str) -> Result<Vec<Game>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let game = rules::Game::from_transcript(line)
+                .map_err(|error| format!("Line {}: {error}", index + 1))?;
+            let mut saved = Game::new();
+            saved.set_header("Result", Game::result_of(game.board()));
+            saved.add_tag("transcript");
+            saved.moves = game.history().to_vec();
+            Ok(saved)
+        })
+        .collect()
+}
+
+/// Whether `path` is the games file of a WTHOR database, see `wthor`.
+fn is_wthor(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("wtb"))
+}
+
+/// The file called `name` in any case in the directory of `path`, e.g. `WTHOR.JOU`.
+fn next_to(path: &Path, name: &str) -> Result<PathBuf, String> {
+    let directory = path.parent().unwrap_or(Path::new("."));
+    let directory = if directory.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        directory
+    };
+    fs::read_dir(directory)
+        .map_err(|error| format!("Failed to read {}: {error}", directory.display()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|candidate| {
+            candidate
+                .file_name()
+                .is_some_and(|file_name| file_name.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| format!("{name} must be next to {}", path.display()))
+}
+
+/// The games of a WTHOR database with the names of `WTHOR.JOU` and `WTHOR.TRN` next to it.
+fn parse_wthor(path: &Path) -> Result<Vec<Game>, String> {
+    let read = |path: &Path| {
+        fs::read(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))
+    };
+    let in_file = |path: &Path, error: String| format!("{}: {error}", path.display());
+    let players_path = next_to(path, "WTHOR.JOU")?;
+    let players = wthor::parse_players(&read(&players_path)?)
+        .map_err(|error| in_file(&players_path, error))?;
+    let tournaments_path = next_to(path, "WTHOR.TRN")?;
+    let tournaments = wthor::parse_tournaments(&read(&tournaments_path)?)
+        .map_err(|error| in_file(&tournaments_path, error))?;
+
+    let (header, games) = wthor::parse_games(&read(path)?).map_err(|error| in_file(path, error))?;
+    games
+        .iter()
+        .enumerate()
+        .map(|(index, game)| {
+            game.to_archive(&header, &players, &tournaments)
+                .map_err(|error| format!("{}: Game {}: {error}", path.display(), index + 1))
+        })
+        .collect()
+}
+
+/// Import a WTHOR database into a single file of the games directory, named after it, as it
+/// has thousands of games.
+fn import_wthor(path: &Path) -> Result<usize, String> {
+    let games = parse_wthor(path)?;
+    let directory = directory();
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    let target = directory.join(format!("wthor-{name}.rgn"));
+    if target.exists() {
+        return Err(format!(
+            "{} was imported before, see {}",
+            path.display(),
+            target.display()
+        ));
+    }
+    fs::create_dir_all(&directory)
+        .and_then(|()| fs::write(&target, archive::write(&games)))
+        .map_err(|error| format!("Failed to save the games: {error}"))?;
+    Ok(games.len())
+}
+
+fn import(files: &[&String]) -> Result<String, String> {
+    let mut imported = 0;
+    for file in files {
+        if is_wthor(Path::new(file)) {
+            imported += import_wthor(Path::new(file))?;
+            continue;
+        }
+        let contents = fs::read_to_string(file)
+            .map_err(|error| format!("Failed to read `{file}`: {error}"))?;
+        let games = if is_transcripts(&contents) {
+            parse_transcripts(&contents)
+        } else if contents.trim_start().starts_with("(;") {
+            ggf::parse(&contents)
+        } else {
+            archive::parse(&contents)
+        }
+        .map_err(|error| format!("{file}: {error}"))?;
+        for game in &games {
+            save(game).map_err(|error| format!("Failed to save a game: {error}"))?;
+        }
+        imported += games.len();
+    }
+    Ok(format!(
+        "Imported {imported} games into {}",
+        directory().display()
+    ))
+}
+
+/// The formats games are exported in.
+enum Format {
+    Archive,
+    /// One row per move, see `csv::write`.
+    Csv,
+    /// One Othello transcript per game, see `rules::Game::to_transcript`.
+    Transcript,
+    /// One game per line in the format of GGS and `NBoard`, see `ggf::write`.
+    Ggf,
+}
+
+/// The game of `saved` as a transcript, if it starts from the initial position.
+fn transcript(number: usize, saved: &Game) -> Result<String, String> {
+    let invalid = |error| format!("Game {number}: {error}");
+    if saved.start().map_err(invalid)? != Position::new() {
+        return Err(invalid(
+            "Transcripts can only start from the initial position".to_owned(),
+        ));
+    }
+    let mut game = rules::Game::new();
+    for &played in &saved.moves {
+        game.replay_move(played)
+            .map_err(|error| invalid(error.to_string()))?;
+    }
+    Ok(game.to_transcript())
+}
+
+/// Export all saved games with `tags`, or only the `number`th one, as an archive, as CSV or
+/// as transcripts.
+fn export(
+    output: Option<&String>,
+    number: Option<usize>,
+    tags: &[&String],
+    format: Format,
+) -> Result<String, String> {
+    let games: Vec<(usize, Game)> = load_all()
+        .map_err(|error| format!("Failed to load the games: {error}"))?
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, game))| (index + 1, game))
+        .filter(|(index, game)| number.is_none_or(|number| number == *index) && tagged(game, tags))
+        .collect();
+    if let Some(number) = number.filter(|_| games.is_empty()) {
+        return Err(format!(
+            "There is no game {number}, see `reversi games list`"
+        ));
+    }
+
+    let contents = match format {
+        Format::Archive => {
+            let games: Vec<Game> = games.iter().map(|(_, game)| game.clone()).collect();
+            archive::write(&games)
+        }
+        Format::Csv => csv::write(&games)?,
+        Format::Transcript => games
+            .iter()
+            .map(|(number, game)| transcript(*number, game).map(|line| line + "\n"))
+            .collect::<Result<String, String>>()?,
+        Format::Ggf => {
+            let games: Vec<Game> = games.iter().map(|(_, game)| game.clone()).collect();
+            ggf::write(&games)?
+        }
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, contents)
+                .map_err(|error| format!("Failed to write `{path}`: {error}"))?;
+            Ok(format!("Exported {} games to {path}", games.len()))
+        }
+        None => {
+            print!("{contents}");
+            Ok(String::new())
+        }
+    }
+}
+
+/// List the saved games with `tags`, keeping the numbers of the full list.
+fn list(tags: &[&String]) -> Result<String, String> {
+    let games = load_all().map_err(|error| format!("Failed to load the games: {error}"))?;
+    Ok(games
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, game))| tagged(game, tags))
+        .map(|(index, (path, game))| {
+            let header = |name| game.header(name).unwrap_or("?");
+            let tags = game.tags();
+            format!(
+                "{:>3}  {:<24} {:<10} {} vs. {} ({}, {} moves){}",
+                index + 1,
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                header("Date"),
+                header("White"),
+                header("Black"),
+                header("Result"),
+                game.moves.len(),
+                if tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", tags.join(", "))
+                }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// The `number`th of the saved games, as numbered by `list`, with its file.
+fn numbered(games: &[(PathBuf, Game)], number: usize) -> Result<(PathBuf, Game), String> {
+    games
+        .get(number.wrapping_sub(1))
+        .cloned()
+        .ok_or_else(|| format!("There is no game {number}, see `reversi games list`"))
+}
+
+/// Replay the `number`th saved game, as numbered by `list`, and save new comments.
+fn replay(number: usize) -> Result<String, String> {
+    let games = load_all().map_err(|error| format!("Failed to load the games: {error}"))?;
+    let (path, mut game) = numbered(&games, number)?;
+
+    if !replay::run(&mut game)? {
+        return Ok(String::new());
+    }
+
+    write_back(games, &path, number, &game)?;
+    Ok(format!("Saved the comments to {}", path.display()))
+}
+
+/// Add `tags` to the `number`th saved game, as numbered by `list`.
+fn tag(number: usize, tags: &[&String]) -> Result<String, String> {
+    let games = load_all().map_err(|error| format!("Failed to load the games: {error}"))?;
+    let (path, mut game) = numbered(&games, number)?;
+    for tag in tags {
+        game.add_tag(tag);
+    }
+
+    write_back(games, &path, number, &game)?;
+    Ok(format!(
+        "Game {number} is tagged {}",
+        game.tags().join(", ")
+    ))
+}
+
+/// Rewrite the file `path` of the `number`th saved game with `game` in its place,
+/// keeping the previous file as a backup.
+fn write_back(
+    games: Vec<(PathBuf, Game)>,
+    path: &Path,
+    number: usize,
+    game: &Game,
+) -> Result<(), String> {
+    let file_games: Vec<Game> = games
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (file, _))| file == path)
+        .map(|(index, (_, other))| {
+            if index == number - 1 {
+                game.clone()
+            } else {
+                other
+            }
+        })
+        .collect();
+    let backup = path.with_extension("rgn.bak");
+    fs::copy(path, &backup)
+        .map_err(|error| format!("Failed to back up `{}`: {error}", path.display()))?;
+    fs::write(path, archive::write(&file_games))
+        .map_err(|error| format!("Failed to save `{}`: {error}", path.display()))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    fn tags<'a>(matches: &'a ArgMatches, id: &str) -> Vec<&'a String> {
+        matches.get_many(id).unwrap_or_default().collect()
+    }
+
+    let message = match matches.subcommand() {
+        Some(("import", sub_matches)) => import(
+            &sub_matches
+                .get_many::<String>("files")
+                .unwrap()
+                .collect::<Vec<_>>(),
+        ),
+        Some(("export", sub_matches)) => export(
+            sub_matches.get_one::<String>("output"),
+            sub_matches.get_one::<usize>("game").copied(),
+            &tags(sub_matches, "tag"),
+            if sub_matches.get_flag("csv") {
+                Format::Csv
+            } else if sub_matches.get_flag("transcript") {
+                Format::Transcript
+            } else if sub_matches.get_flag("ggf") {
+                Format::Ggf
+            } else {
+                Format::Archive
+            },
+        ),
+        Some(("list", sub_matches)) => list(&tags(sub_matches, "tag")),
+        Some(("tag", sub_matches)) => tag(
+            *sub_matches.get_one::<usize>("game").unwrap(),
+            &tags(sub_matches, "tags"),
+        ),
+        Some(("replay", sub_matches)) => replay(*sub_matches.get_one::<usize>("game").unwrap()),
+        _ => unreachable!(),
+    }?;
+
+    if !message.is_empty() {
+        println!("{message}");
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! Analyze all games of a directory at once, e.g. to review a week of a student's games.
+
+use super::{load_dir, load_endgames, save_endgames};
+use reversi_game::{
+    archive::{self, Game},
+    engine::{throttle, EndgameCache, Engine, Search, SearchLimits},
+    reversi::*,
+};
+
+use std::{
+    collections::BTreeMap,
+    fs, mem,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use clap::ArgMatches;
+
+/// Evaluations beyond this are clamped, so a won position doesn't make the losses meaningless.
+const MAX_EVAL: i32 = 64;
+
+/// The engine's verdict on a move.
+pub(crate) struct MoveAnalysis {
+    /// The number of the move, starting at 1.
+    pub number: usize,
+    pub color: Color,
+    pub best: Field,
+    /// How many discs the best move is better than the one played, from the player's view.
+    pub loss: i32,
+}
+
+/// The moves of one player over all their games.
+#[derive(Debug, Default)]
+pub(crate) struct Accuracy {
+    games: usize,
+    moves: usize,
+    best_moves: usize,
+    total_loss: i64,
+}
+
+impl Accuracy {
+    /// Count a game and the moves of `color` in its analysis.
+    pub fn add_game(&mut self, analysis: &[MoveAnalysis], color: Color) {
+        self.games += 1;
+        for of_move in analysis.iter().filter(|of_move| of_move.color == color) {
+            self.moves += 1;
+            self.best_moves += usize::from(of_move.loss == 0);
+            self.total_loss += i64::from(of_move.loss);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn best_move_rate(&self) -> f64 {
+        self.best_moves as f64 / self.moves.max(1) as f64
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_loss(&self) -> f64 {
+        self.total_loss as f64 / self.moves.max(1) as f64
+    }
+}
+
+/// The best move and its evaluation, solved exactly once few enough fields are empty that the
+/// result can be kept in the engine's `EndgameCache`.
+fn search(engine: &Engine, board: &Board, color: Color, depth: u8) -> (Option<Field>, i32) {
+    let discs = board.count_pieces(Color::White) + board.count_pieces(Color::Black);
+    if 64 - discs <= *EndgameCache::EMPTIES.end() as usize {
+        engine.solve(board, color)
+    } else {
+        engine.best_move(board, color, SearchLimits::depth(depth))
+    }
+}
+
+/// The engine's best move for `color` on `board` and how many discs worse `played` is than
+/// it, or `None` if `color` has to pass.
+pub(crate) fn judge(
+    engine: &Engine,
+    board: &Board,
+    color: Color,
+    played: Field,
+    depth: u8,
+) -> Result<Option<(Field, i32)>, String> {
+    let (best, best_eval) = search(engine, board, color, depth);
+    let Some(best) = best else { return Ok(None) };
+    if best == played {
+        return Ok(Some((best, 0)));
+    }
+    let mut after = board.clone();
+    after
+        .add_piece(played, color)
+        .map_err(|error| error.to_string())?;
+    let reply = search(engine, &after, color.other(), depth - 1);
+    let played_eval = (-reply.1).clamp(-MAX_EVAL, MAX_EVAL);
+    Ok(Some((
+        best,
+        (best_eval.clamp(-MAX_EVAL, MAX_EVAL) - played_eval).max(0),
+    )))
+}
+
+/// Compare every move of `game` but the passes with the engine's choice.
+pub(crate) fn analyze(
+    engine: &Engine,
+    game: &Game,
+    depth: u8,
+) -> Result<Vec<MoveAnalysis>, String> {
+    engine.clear_hash();
+    let mut analysis = Vec::new();
+
+    for (index, &played) in game.moves.iter().enumerate() {
+        let Some(played) = played else { continue };
+        let color = game.color_of(index + 1);
+        let before = game.board_after(index)?;
+        engine.prune_hash(&before);
+
+        let judged = judge(engine, &before, color, played, depth)
+            .map_err(|error| format!("Move {} ({played}): {error}", index + 1))?;
+        let Some((best, loss)) = judged else { continue };
+        analysis.push(MoveAnalysis {
+            number: index + 1,
+            color,
+            best,
+            loss,
+        });
+    }
+
+    Ok(analysis)
+}
+
+/// Comment on the moves that were worse than the engine's choice, after earlier comments,
+/// and record them in the `Analysis` header for `reversi quiz`.
+fn annotate(game: &mut Game, analysis: &[MoveAnalysis]) {
+    let mut losses = BTreeMap::new();
+    for of_move in analysis.iter().filter(|of_move| of_move.loss > 0) {
+        let note = format!("{} was {} discs better", of_move.best, of_move.loss);
+        let comment = match game.comments.get(&of_move.number) {
+            Some(comment) => format!("{comment} / {note}"),
+            None => note,
+        };
+        game.set_comment(of_move.number, &comment);
+        losses.insert(of_move.number, (of_move.best, of_move.loss));
+    }
+    game.set_analysis(&losses);
+}
+
+/// Analyze the games of `directory` on `jobs` threads, write annotated copies into its
+/// `annotated` subdirectory and report the accuracy of each player.
+fn analyze_directory(directory: &Path, depth: u8, jobs: usize) -> Result<String, String> {
+    let mut games = load_dir(directory).map_err(|error| {
+        format!(
+            "Failed to load the games in {}: {error}",
+            directory.display()
+        )
+    })?;
+    if games.is_empty() {
+        return Err(format!("There are no games in {}", directory.display()));
+    }
+    println!("Analyzing {} games on {jobs} threads...", games.len());
+
+    let mut endgames = load_endgames();
+    let chunk_size = games.len().div_ceil(jobs);
+    let analyses: Vec<Vec<MoveAnalysis>> = thread::scope(|scope| {
+        games
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let known = endgames.clone();
+                scope.spawn(move || {
+                    let engine = Engine::new();
+                    *engine.endgame_cache() = known;
+                    let analyses = chunk
+                        .iter()
+                        .map(|(path, game)| {
+                            analyze(&engine, game, depth)
+                                .map_err(|error| format!("{}: {error}", path.display()))
+                        })
+                        .collect::<Result<Vec<_>, String>>();
+                    let solved = mem::take(&mut *engine.endgame_cache());
+                    (analyses, solved)
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| {
+                let (analyses, solved) = handle.join().unwrap();
+                endgames.merge(solved);
+                analyses
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map(|chunks| chunks.into_iter().flatten().collect())
+    })?;
+    // The endgames only speed up later runs.
+    let _ = save_endgames(&endgames);
+
+    let mut accuracies: BTreeMap<String, Accuracy> = BTreeMap::new();
+    for ((_, game), analysis) in games.iter_mut().zip(&analyses) {
+        for color in [Color::White, Color::Black] {
+            let name = game.header(&format!("{color:?}")).unwrap_or("?").to_owned();
+            accuracies
+                .entry(name)
+                .or_default()
+                .add_game(analysis, color);
+        }
+        annotate(game, analysis);
+    }
+
+    let output = directory.join("annotated");
+    fs::create_dir_all(&output)
+        .map_err(|error| format!("Failed to create {}: {error}", output.display()))?;
+    let mut files: BTreeMap<PathBuf, Vec<Game>> = BTreeMap::new();
+    for (path, game) in games {
+        files.entry(path).or_default().push(game);
+    }
+    for (path, file_games) in &files {
+        let copy = output.join(path.file_name().unwrap_or_default());
+        fs::write(&copy, archive::write(file_games))
+            .map_err(|error| format!("Failed to write {}: {error}", copy.display()))?;
+    }
+
+    let mut report = vec![format!(
+        "Wrote the annotated games to {}\n",
+        output.display()
+    )];
+    report.extend(accuracies.iter().map(|(name, accuracy)| {
+        format!(
+            "{name:<28} {:>3} games {:>5} moves {:>5.1}% best moves, {:.2} discs lost per move",
+            accuracy.games,
+            accuracy.moves,
+            accuracy.best_move_rate() * 100.0,
+            accuracy.average_loss()
+        )
+    }));
+    Ok(report.join("\n"))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let directory = matches.get_one::<PathBuf>("directory").unwrap();
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    let jobs = matches
+        .get_one::<u16>("jobs")
+        .map(|&jobs| usize::from(jobs))
+        .unwrap_or_else(throttle::threads);
+
+    println!("{}", analyze_directory(directory, depth, jobs)?);
+    Ok(())
+}
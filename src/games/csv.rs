@@ -0,0 +1,84 @@
+//! Per-move statistics of games as CSV, e.g. for charting them in a spreadsheet.
+
+use reversi_game::{
+    archive::Game,
+    engine::{from_white_view, to_white_view, Engine, Search, SearchLimits},
+    reversi::*,
+};
+
+/// The depth of the evaluations.
+const DEPTH: u8 = 4;
+
+const COLUMNS: [&str; 10] = [
+    "game",
+    "move",
+    "player",
+    "field",
+    "eval_before",
+    "eval_after",
+    "time",
+    "mobility",
+    "white_discs",
+    "black_discs",
+];
+
+/// The evaluation of `board` with `to_move` to move from the view of `player`,
+/// with won games counted as ±64.
+fn evaluate(engine: &Engine, board: &Board, to_move: Color, player: Color) -> i32 {
+    let evaluation = engine
+        .best_move(board, to_move, SearchLimits::depth(DEPTH))
+        .1;
+    from_white_view(to_white_view(evaluation, to_move), player).clamp(-64, 64)
+}
+
+/// One row per move of each game, numbered from 1, with a header row.
+///
+/// Evaluations are from the view of the player making the move, times in seconds and empty
+/// if the game has none, and the mobility is the number of valid moves the player had.
+pub fn write(games: &[(usize, Game)]) -> Result<String, String> {
+    let engine = Engine::new();
+    let mut rows = vec![COLUMNS.join(",")];
+
+    for (number, game) in games {
+        let times = game.move_times();
+        let start = game.start()?;
+        let mut before = start.board().clone();
+        // From the view of the side to move, which is the player of the next move.
+        let mut eval_before = evaluate(&engine, &before, start.to_move(), start.to_move());
+
+        for (index, &played) in game.moves.iter().enumerate() {
+            let color = game.color_of(index + 1);
+            let after = game.board_after(index + 1)?;
+            let eval_after = evaluate(&engine, &after, color.other(), color);
+
+            rows.push(
+                [
+                    number.to_string(),
+                    (index + 1).to_string(),
+                    match color {
+                        Color::White => "white",
+                        Color::Black => "black",
+                    }
+                    .to_owned(),
+                    played.map_or("--".to_owned(), |field| Notation::Standard.format(field)),
+                    eval_before.to_string(),
+                    eval_after.to_string(),
+                    times
+                        .get(index)
+                        .map(|time| format!("{:.1}", time.as_secs_f64()))
+                        .unwrap_or_default(),
+                    before.mobility(color).to_string(),
+                    after.count_pieces(Color::White).to_string(),
+                    after.count_pieces(Color::Black).to_string(),
+                ]
+                .join(","),
+            );
+
+            before = after;
+            eval_before = -eval_after;
+        }
+    }
+
+    rows.push(String::new());
+    Ok(rows.join("\n"))
+}
@@ -0,0 +1,213 @@
+//! Step through a saved game and comment on its moves.
+
+use reversi_game::{
+    archive::Game,
+    engine::{Engine, Search, SearchLimits},
+    reversi::*,
+};
+
+use crate::play::input;
+
+const HELP: &str = "\
+Commands:
+  <Enter>, next            Show the next move
+  back                     Show the previous move
+  goto <move>              Jump to a move, 0 is the initial board
+  arrows                   Mark the next move played and the engine's choice on the board
+  comment [move] <text>    Comment on a move (the current one by default), empty text removes it
+  help                     Show this help
+  quit                     Leave the replay, saving new comments";
+
+/// The commands completed with tab.
+const COMPLETIONS: [&str; 7] = ["next", "back", "goto", "arrows", "comment", "help", "quit"];
+
+/// A replay command, see `HELP`.
+enum Command {
+    Next,
+    Back,
+    Goto(usize),
+    Arrows,
+    Comment(Option<usize>, String),
+    Help,
+    Quit,
+}
+
+fn parse(input: &str) -> Result<Command, String> {
+    let input = input.trim();
+    let (command, rest) = input.split_once(' ').unwrap_or((input, ""));
+    let rest = rest.trim();
+
+    match command {
+        "" | "next" | "n" => Ok(Command::Next),
+        "back" | "b" => Ok(Command::Back),
+        "goto" | "g" => rest
+            .parse()
+            .map(Command::Goto)
+            .map_err(|_| format!("Invalid move number `{rest}`")),
+        "arrows" | "a" => Ok(Command::Arrows),
+        "comment" | "c" => {
+            let (number, text) = match rest.split_once(' ') {
+                Some((number, text)) if number.parse::<usize>().is_ok() => {
+                    (number.parse().ok(), text)
+                }
+                _ => match rest.parse::<usize>() {
+                    Ok(number) => (Some(number), ""),
+                    Err(_) => (None, rest),
+                },
+            };
+            let text = text.trim();
+            let text = text
+                .strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"'))
+                .unwrap_or(text);
+            Ok(Command::Comment(number, text.to_owned()))
+        }
+        "help" | "?" => Ok(Command::Help),
+        "quit" | "q" | "exit" => Ok(Command::Quit),
+        other => Err(format!("Unknown command `{other}`, try `help`")),
+    }
+}
+
+/// A line describing the `number`th move of a game.
+fn describe(game: &Game, number: usize) -> String {
+    let mut description = match number {
+        0 => format!("Initial position ({} moves)", game.moves.len()),
+        _ => format!(
+            "Move {number}/{}: {} {}",
+            game.moves.len(),
+            game.color_of(number),
+            game.moves[number - 1].map_or("passes".to_owned(), |field| field.to_string())
+        ),
+    };
+    if let Some(comment) = game.comments.get(&number) {
+        description += &format!("\n\"{comment}\"");
+    }
+    description
+}
+
+/// The depth of the post-game analysis.
+const ANALYSIS_DEPTH: u8 = 5;
+
+/// The engine's best move and evaluation for each position of a game but the last one.
+fn analyze(game: &Game) -> Result<Vec<(Option<Field>, i32)>, String> {
+    println!("Analyzing the game...");
+    let engine = Engine::new();
+    (0..game.moves.len())
+        .map(|number| {
+            let board = game.board_after(number)?;
+            engine.prune_hash(&board);
+            Ok(engine.best_move(
+                &board,
+                game.color_of(number + 1),
+                SearchLimits::depth(ANALYSIS_DEPTH),
+            ))
+        })
+        .collect()
+}
+
+/// Mark the move played after the `number`th move and the engine's move instead,
+/// with a line explaining the marks.
+fn arrows(
+    game: &Game,
+    number: usize,
+    analysis: &[(Option<Field>, i32)],
+) -> (Vec<(Field, String)>, String) {
+    let (Some(&played), Some(&(best, evaluation))) = (game.moves.get(number), analysis.get(number))
+    else {
+        return (Vec::new(), "The game is over.".to_owned());
+    };
+    let legend = format!(
+        "() played next, [] engine's choice {} ({evaluation:+}), <> both",
+        best.map_or("pass".to_owned(), |field| field.to_string())
+    );
+
+    let marks = match (played, best) {
+        (Some(played), Some(best)) if played == best => vec![(played, "<>".to_owned())],
+        (played, best) => played
+            .map(|field| (field, "()".to_owned()))
+            .into_iter()
+            .chain(best.map(|field| (field, "[]".to_owned())))
+            .collect(),
+    };
+    (marks, legend)
+}
+
+/// Replay a game. Returns whether comments were changed.
+pub fn run(game: &mut Game) -> Result<bool, String> {
+    let title = format!(
+        "{} vs. {}",
+        game.header("White").unwrap_or("?"),
+        game.header("Black").unwrap_or("?")
+    );
+    let mut number = 0;
+    let mut modified = false;
+    let mut analysis: Option<Vec<(Option<Field>, i32)>> = None;
+    let mut show_arrows = false;
+    let mut message = "Press <Enter> for the next move, type `help` for the commands.".to_owned();
+
+    loop {
+        let mut footer = describe(game, number);
+        let marks = match &analysis {
+            Some(analysis) if show_arrows => {
+                let (marks, legend) = arrows(game, number, analysis);
+                footer += &format!("\n{legend}");
+                marks
+            }
+            _ => Vec::new(),
+        };
+        redraw_board(
+            &game.board_after(number)?,
+            &DisplayOptions {
+                title: Some(title.clone()),
+                footer: Some(footer),
+                marks,
+                hints: if show_arrows {
+                    Hints::Off
+                } else {
+                    Hints::Color
+                },
+                ..Default::default()
+            },
+        );
+        println!("{message}");
+
+        let Ok(input) = input::edit_line("> ", &COMPLETIONS.map(str::to_owned), None) else {
+            break;
+        };
+
+        message = String::new();
+        match parse(&input) {
+            Ok(Command::Next) => {
+                if number < game.moves.len() {
+                    number += 1;
+                } else {
+                    message = "This is the last move.".to_owned();
+                }
+            }
+            Ok(Command::Back) => number = number.saturating_sub(1),
+            Ok(Command::Goto(to)) if to <= game.moves.len() => number = to,
+            Ok(Command::Goto(to)) => message = format!("The game has no move {to}"),
+            Ok(Command::Arrows) => {
+                if analysis.is_none() {
+                    analysis = Some(analyze(game)?);
+                }
+                show_arrows = !show_arrows;
+            }
+            Ok(Command::Comment(to, text)) => {
+                let to = to.unwrap_or(number);
+                if to <= game.moves.len() {
+                    game.set_comment(to, &text);
+                    modified = true;
+                    number = to;
+                } else {
+                    message = format!("The game has no move {to}");
+                }
+            }
+            Ok(Command::Help) => message = HELP.to_owned(),
+            Ok(Command::Quit) => break,
+            Err(error) => message = error,
+        }
+    }
+
+    Ok(modified)
+}
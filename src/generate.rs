@@ -0,0 +1,406 @@
+//! Generation of shell completion scripts and the man page from the command line definition.
+
+use clap::{Arg, Command};
+
+/// The shells completion scripts can be generated for.
+pub const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// Generate the completion script for one of [`SHELLS`].
+pub fn completions(cmd: &Command, shell: &str) -> String {
+    match shell {
+        "bash" => bash(cmd),
+        "zsh" => zsh(cmd),
+        "fish" => fish(cmd),
+        _ => unreachable!(),
+    }
+}
+
+/// The subcommands of `cmd` at any depth, each with the names leading to it from `cmd`,
+/// e.g. `["games", "export"]`, parents before their subcommands. The `help` subcommands
+/// that clap adds are listed, but not the copies of the other subcommands below them.
+fn subcommands(cmd: &Command) -> Vec<(Vec<&str>, &Command)> {
+    let mut found = Vec::new();
+    for sub in cmd.get_subcommands() {
+        found.push((vec![sub.get_name()], sub));
+        if sub.get_name() == "help" {
+            continue;
+        }
+        for (mut path, nested) in subcommands(sub) {
+            path.insert(0, sub.get_name());
+            found.push((path, nested));
+        }
+    }
+    found
+}
+
+fn options(cmd: &Command) -> impl Iterator<Item = &Arg> {
+    cmd.get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_num_args().is_some_and(|range| range.takes_values())
+}
+
+fn possible_values(arg: &Arg) -> Vec<String> {
+    arg.get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_owned())
+        .collect()
+}
+
+fn help(arg: &Arg) -> String {
+    arg.get_help().map(ToString::to_string).unwrap_or_default()
+}
+
+fn flags(arg: &Arg) -> Vec<String> {
+    arg.get_short()
+        .map(|short| format!("-{short}"))
+        .into_iter()
+        .chain(arg.get_long().map(|long| format!("--{long}")))
+        .collect()
+}
+
+fn bash(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let words = |cmd: &Command| {
+        options(cmd)
+            .flat_map(flags)
+            .chain(cmd.get_subcommands().map(|sub| sub.get_name().to_owned()))
+            .chain(
+                cmd.get_arguments()
+                    .filter(|arg| arg.is_positional())
+                    .flat_map(possible_values),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let nested = subcommands(cmd);
+
+    let mut script = format!(
+        "_{name}() {{\n    local cur prev opts command word\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\n    case \"$prev\" in\n"
+    );
+    for arg in options(cmd).chain(nested.iter().flat_map(|(_, sub)| options(sub))) {
+        let values = possible_values(arg);
+        if takes_value(arg) {
+            script += &format!(
+                "        {})\n            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n            return 0\n            ;;\n",
+                flags(arg).join("|"),
+                values.join(" ")
+            );
+        }
+    }
+    // Follow the subcommands typed so far, e.g. to `games export`.
+    script += "    esac\n\n    command=\"\"\n    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n        case \"$command,$word\" in\n";
+    for (path, _) in &nested {
+        let (name, parent) = path.split_last().unwrap();
+        script += &format!(
+            "            \"{},{name}\")\n                command=\"{}\"\n                ;;\n",
+            parent.join(" "),
+            path.join(" ")
+        );
+    }
+    script += "        esac\n    done\n\n    case \"$command\" in\n";
+    for (path, sub) in &nested {
+        script += &format!(
+            "        \"{}\")\n            opts=\"{}\"\n            ;;\n",
+            path.join(" "),
+            words(sub)
+        );
+    }
+    script += &format!(
+        "        *)\n            opts=\"{}\"\n            ;;\n    esac\n\n    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n}}\n\ncomplete -F _{name} {name}\n",
+        words(cmd)
+    );
+
+    script
+}
+
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_action(arg: &Arg) -> String {
+    let values = possible_values(arg);
+    if values.is_empty() {
+        " ".to_owned()
+    } else {
+        format!("({})", values.join(" "))
+    }
+}
+
+fn zsh_arguments(cmd: &Command) -> Vec<String> {
+    let mut specs: Vec<String> = options(cmd)
+        .map(|arg| {
+            let flags = flags(arg);
+            let exclusion = if flags.len() > 1 {
+                format!("'({})'", flags.join(" "))
+            } else {
+                String::new()
+            };
+            let names = if flags.len() > 1 {
+                format!("{{{}}}", flags.join(","))
+            } else {
+                flags.join("")
+            };
+            let value = if takes_value(arg) {
+                format!(":{}:{}", arg.get_id(), zsh_action(arg))
+            } else {
+                String::new()
+            };
+            format!("{exclusion}{names}'[{}]{value}'", zsh_escape(&help(arg)))
+        })
+        .collect();
+
+    for (index, arg) in cmd
+        .get_arguments()
+        .filter(|arg| arg.is_positional())
+        .enumerate()
+    {
+        specs.push(format!(
+            "'{}:{}:{}'",
+            index + 1,
+            arg.get_id(),
+            zsh_action(arg)
+        ));
+    }
+
+    specs
+}
+
+/// The name of the completion function of the command at `path`, e.g. `_reversi_games_export`.
+fn zsh_function(path: &[&str]) -> String {
+    format!("_{}", path.join("_").replace('-', "_"))
+}
+
+/// The completion function of `cmd` at `path`, which hands the words after a subcommand to the
+/// function of that subcommand.
+fn zsh_command(path: &[&str], cmd: &Command) -> String {
+    let mut script = format!("{}() {{\n", zsh_function(path));
+    if !cmd.has_subcommands() {
+        let arguments = zsh_arguments(cmd);
+        if !arguments.is_empty() {
+            script += &format!(
+                "    _arguments -s \\\n        {}\n",
+                arguments.join(" \\\n        ")
+            );
+        }
+        return script + "}\n";
+    }
+
+    script += "    case \"${words[2]}\" in\n";
+    // The subcommands of `help` only name the others, and have no functions of their own.
+    for sub in cmd.get_subcommands().filter(|_| cmd.get_name() != "help") {
+        let sub_path = [path, &[sub.get_name()]].concat();
+        script += &format!(
+            "        {})\n            shift words\n            (( CURRENT-- ))\n            {}\n            ;;\n",
+            sub.get_name(),
+            zsh_function(&sub_path)
+        );
+    }
+
+    let commands = cmd
+        .get_subcommands()
+        .map(|sub| {
+            format!(
+                "{}\\:\"{}\"",
+                sub.get_name(),
+                zsh_escape(&sub.get_about().map(ToString::to_string).unwrap_or_default())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut arguments = zsh_arguments(cmd);
+    if !commands.is_empty() {
+        arguments.push(format!("'1::command:(({commands}))'"));
+    }
+
+    script += &format!(
+        "        *)\n            _arguments -s \\\n                {}\n            ;;\n    esac\n}}\n",
+        arguments.join(" \\\n                ")
+    );
+
+    script
+}
+
+fn zsh(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut script = format!("#compdef {name}\n\n{}", zsh_command(&[name], cmd));
+    for (path, sub) in subcommands(cmd) {
+        script += "\n";
+        script += &zsh_command(&[&[name], path.as_slice()].concat(), sub);
+    }
+    script + &format!("\n_{name} \"$@\"\n")
+}
+
+fn fish_line(name: &str, condition: &str, arg: &Arg) -> String {
+    let mut line = format!("complete -c {name} -n '{condition}'");
+    if let Some(short) = arg.get_short() {
+        line += &format!(" -s {short}");
+    }
+    if let Some(long) = arg.get_long() {
+        line += &format!(" -l {long}");
+    }
+    if takes_value(arg) {
+        line += " -r";
+        let values = possible_values(arg);
+        if !values.is_empty() {
+            line += &format!(" -f -a '{}'", values.join(" "));
+        }
+    }
+    line + &format!(" -d '{}'", help(arg).replace('\'', "\\'"))
+}
+
+/// The completions of the subcommands of `cmd` and of their arguments, where `condition` holds
+/// once `cmd` was typed.
+fn fish_subcommands(name: &str, cmd: &Command, condition: &str) -> String {
+    let mut script = String::new();
+    // The subcommands are offered until one of them was typed.
+    let choosing = if condition == "__fish_use_subcommand" {
+        condition.to_owned()
+    } else {
+        let names: Vec<&str> = cmd.get_subcommands().map(Command::get_name).collect();
+        format!(
+            "{condition}; and not __fish_seen_subcommand_from {}",
+            names.join(" ")
+        )
+    };
+
+    for sub in cmd.get_subcommands() {
+        script += &format!(
+            "complete -c {name} -n '{choosing}' -f -a {} -d '{}'\n",
+            sub.get_name(),
+            sub.get_about()
+                .map(ToString::to_string)
+                .unwrap_or_default()
+                .replace('\'', "\\'")
+        );
+
+        let seen = format!("__fish_seen_subcommand_from {}", sub.get_name());
+        let condition = if condition == "__fish_use_subcommand" {
+            seen
+        } else {
+            format!("{condition}; and {seen}")
+        };
+        for arg in options(sub) {
+            script += &fish_line(name, &condition, arg);
+            script.push('\n');
+        }
+        for arg in sub.get_arguments().filter(|arg| arg.is_positional()) {
+            script += &format!(
+                "complete -c {name} -n '{condition}' -f -a '{}'\n",
+                possible_values(arg).join(" ")
+            );
+        }
+        if sub.get_name() != "help" {
+            script += &fish_subcommands(name, sub, &condition);
+        }
+    }
+
+    script
+}
+
+fn fish(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut script = String::new();
+
+    for arg in options(cmd) {
+        script += &fish_line(name, "__fish_use_subcommand", arg);
+        script.push('\n');
+    }
+
+    script + &fish_subcommands(name, cmd, "__fish_use_subcommand")
+}
+
+fn roff_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('-', "\\-")
+}
+
+fn roff_options(cmd: &Command) -> String {
+    let mut section = String::new();
+
+    for arg in cmd.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let mut names = flags(arg)
+            .iter()
+            .map(|flag| format!("\\fB{}\\fR", roff_escape(flag)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if arg.is_positional() {
+            names = format!("\\fI<{}>\\fR", arg.get_id().as_str().to_uppercase());
+        } else if takes_value(arg) {
+            names += &format!(" \\fI<{}>\\fR", arg.get_id().as_str().to_uppercase());
+        }
+
+        let mut description = roff_escape(&help(arg));
+        let defaults = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy())
+            .collect::<Vec<_>>();
+        if !defaults.is_empty() && takes_value(arg) {
+            description += &format!(" [default: {}]", defaults.join(", "));
+        }
+        let values = possible_values(arg);
+        if !values.is_empty() {
+            description += &format!(" [possible values: {}]", values.join(", "));
+        }
+
+        section += &format!(".TP\n{names}\n{description}\n");
+    }
+
+    section
+}
+
+/// Generate a man page in roff format.
+pub fn manpage(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let version = cmd.get_version().unwrap_or_default();
+    let about = cmd.get_about().map(ToString::to_string).unwrap_or_default();
+
+    let mut page = format!(
+        ".TH {} 1 \"\" \"{name} {version}\"\n.SH NAME\n{name} \\- {}\n",
+        name.to_uppercase(),
+        roff_escape(&about)
+    );
+
+    page += &format!(".SH SYNOPSIS\n\\fB{name}\\fR [\\fIOPTIONS\\fR]");
+    if cmd.has_subcommands() {
+        page += " [\\fICOMMAND\\fR]";
+    }
+    page += "\n";
+
+    page += &format!(
+        ".SH DESCRIPTION\n{}\n",
+        roff_escape(
+            &cmd.get_long_about()
+                .map_or_else(|| about.clone(), ToString::to_string)
+        )
+    );
+
+    page += ".SH OPTIONS\n";
+    page += &roff_options(cmd);
+
+    if cmd.has_subcommands() {
+        page += ".SH COMMANDS\n";
+        for (path, sub) in subcommands(cmd) {
+            page += &format!(
+                ".SS {name} {}\n{}\n",
+                path.join(" "),
+                roff_escape(&sub.get_about().map(ToString::to_string).unwrap_or_default())
+            );
+            page += &roff_options(sub);
+        }
+    }
+
+    if let Some(author) = cmd.get_author() {
+        page += &format!(".SH AUTHOR\n{}\n", roff_escape(author));
+    }
+
+    page
+}
@@ -12,6 +12,7 @@ pub use crate::reversi::*;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use engine::{Search, SearchLimits};
 
     #[test]
     fn initialize_board() {
@@ -40,7 +41,7 @@ mod tests {
     #[test]
     fn move_validity() {
         let mut board = Board::new();
-        board[Field(2, 4)] = Some(Color::White);
+        board.set(Field(2, 4), Some(Color::White));
 
         redraw_board(&board, &DisplayOptions::default());
 
@@ -61,9 +62,1047 @@ mod tests {
     fn board_status() {
         use crate::reversi::Color::{Black, White};
         let mut board = Board::new();
-        board[Field(2, 4)] = Some(White);
-        board[Field(3, 5)] = Some(Black);
+        board.set(Field(2, 4), Some(White));
+        board.set(Field(3, 5), Some(Black));
 
         assert_eq!(board.status(), GameStatus::InProgress);
     }
+
+    #[test]
+    fn canonical_board() {
+        let board = Board::new();
+        let (canonical, _) = board.canonical();
+
+        for symmetry in Symmetry::ALL {
+            let transformed = board.transformed(symmetry);
+            assert_eq!(transformed.canonical().0, canonical);
+            assert_eq!(transformed.transformed(symmetry.inverse()), board);
+        }
+    }
+
+    #[test]
+    fn render_flipping_piece() {
+        let board = Board::new();
+        let theme = Theme::default();
+
+        let rendered = board.render(&[], &theme, Some(Field(3, 3)), &[]);
+        assert_eq!(rendered.matches(theme.edge.as_str()).count(), 1);
+        assert_eq!(
+            rendered,
+            board.render(&[], &theme, None, &[]).replacen("⚪", "━━", 1)
+        );
+    }
+
+    #[test]
+    fn render_marks_on_empty_fields() {
+        let board = Board::new();
+        let theme = Theme::default();
+        let marks = [
+            (Field(2, 3), "()".to_owned()),
+            (Field(3, 3), "[]".to_owned()),
+        ];
+
+        let rendered = board.render(&[Color::White], &theme, None, &marks);
+        assert!(rendered.contains(" () "));
+        assert!(!rendered.contains("[]"));
+        assert!(board
+            .render_compact(&[], &theme, None, &marks)
+            .contains("()"));
+    }
+
+    #[test]
+    fn render_compact_board() {
+        let board = Board::new();
+        let rendered = board.render_compact(
+            &[Color::White],
+            &Theme::named("letters").unwrap(),
+            None,
+            &[],
+        );
+
+        assert!(rendered.lines().all(|line| line.chars().count() <= 17));
+        assert_eq!(rendered.lines().nth(5), Some("4 . . * B W . . ."));
+    }
+
+    #[test]
+    fn archive_round_trip() {
+        let mut game = archive::Game::new();
+        game.set_header("White", "Player \"1\"");
+        game.set_header("Result", "*");
+        game.moves = vec![Some(Field(3, 5)), Some(Field(2, 5)), None];
+        game.set_comment(0, "a {short} game");
+        game.set_comment(2, "should have taken the corner");
+
+        let games = archive::parse(&archive::write(&[game.clone(), game.clone()])).unwrap();
+        assert_eq!(games, vec![game.clone(), game.clone()]);
+        assert!(game.replay().is_ok());
+        assert_eq!(games[0].comments[&0], "a short game");
+        assert!(archive::parse("1. d3 c5").is_err());
+        assert!(archive::parse("1. d3 {unterminated *").is_err());
+    }
+
+    #[test]
+    fn signed_games_survive_the_archive() {
+        let mut game = archive::Game::new();
+        game.set_header("White", "Player 1");
+        game.moves = vec![Some(Field(3, 5)), Some(Field(2, 5))];
+        game.sign();
+
+        let mut games = archive::parse(&archive::write(&[game])).unwrap();
+        assert_eq!(games[0].verify(), Ok(()));
+        games[0].set_header("White", "Player 2");
+        assert!(games[0].verify().is_err());
+        games[0].sign();
+        assert_eq!(games[0].verify(), Ok(()));
+    }
+
+    #[test]
+    fn archive_versions() {
+        let archive = archive::write(&[archive::Game::new()]);
+        assert!(archive.starts_with(&format!("% version {}\n", archive::VERSION)));
+        assert_eq!(archive::parse(&archive).unwrap().len(), 1);
+
+        // Archives from before the version line.
+        assert_eq!(archive::parse("% a comment\n1. d3 *").unwrap().len(), 1);
+        let newer = format!("% version {}\n1. d3 *", archive::VERSION + 1);
+        assert!(archive::parse(&newer)
+            .unwrap_err()
+            .contains("newer version"));
+    }
+
+    #[test]
+    fn archive_tags() {
+        let mut game = archive::Game::new();
+        game.add_tag("training");
+        game.add_tag("  ");
+        game.add_tag("ffo test");
+
+        let games = archive::parse(&archive::write(&[game])).unwrap();
+        assert_eq!(games[0].tags(), vec!["training", "ffo-test"]);
+        assert!(!games[0].has_tag("ffo"));
+    }
+
+    #[test]
+    fn archive_start_position() {
+        use reversi::rules::Position;
+
+        let drawn = "
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . O X . . .
+            . . . X X X . .
+            . . . . . . . .
+            . . . . . . . .
+            . . . . . . . .
+            X to move";
+        let position: Position = drawn.parse().unwrap();
+        assert_eq!(position.to_move(), Color::Black);
+        assert!(Board::from_ascii(". . X").is_err());
+
+        let mut game = archive::Game::new();
+        game.set_start(&position);
+        game.moves = vec![Some(Field(2, 3))];
+        let games = archive::parse(&archive::write(&[game])).unwrap();
+        assert_eq!(games[0].start(), Ok(position));
+        assert_eq!(games[0].color_of(1), Color::Black);
+        assert_eq!(games[0].replay().unwrap()[Field(3, 3)], Some(Color::Black));
+
+        let mut initial = games[0].clone();
+        initial.set_start(&Position::new());
+        assert_eq!(initial.header("Position"), None);
+    }
+
+    #[test]
+    fn move_report_matches_add_piece() {
+        let board = Board::new();
+        for field in Field::all() {
+            let report = board.move_report(field, Color::White);
+            let mut after = board.clone();
+            match after.add_piece(field, Color::White) {
+                Ok(mut captured) => {
+                    let mut reported: Vec<Field> = report.captured().collect();
+                    captured.sort_by_key(|field| (field.0, field.1));
+                    reported.sort_by_key(|field| (field.0, field.1));
+                    assert_eq!(reported, captured);
+                    assert_eq!(report.opponent_mobility.1, after.mobility(Color::Black));
+                }
+                Err(error) => assert_eq!(report.validity, Err(error)),
+            }
+        }
+    }
+
+    #[test]
+    fn precomputed_neighbors_and_rays() {
+        use board::report::Direction;
+
+        for field in Field::all() {
+            let neighbors: Vec<Field> = Field::all()
+                .filter(|other| {
+                    other != &field
+                        && field.0.abs_diff(other.0) <= 1
+                        && field.1.abs_diff(other.1) <= 1
+                })
+                .collect();
+            assert_eq!(field.neighbors(), neighbors);
+
+            for direction in Direction::ALL {
+                let (dx, dy) = direction.offset();
+                let step = |steps| field.offset(dx * steps, dy * steps);
+                let ray: Vec<Field> = direction.ray(field).collect();
+                let expected: Vec<Field> = (1..).map_while(step).collect();
+                assert_eq!(ray, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn game_tracks_the_turn_through_passes() {
+        let mut game = Game::new();
+        game.play(Field(2, 4)).unwrap();
+        assert_eq!(game.pass(), Err(PlaceError::CannotPass));
+        game.forfeit_turn().unwrap();
+        // The discs alone would say it's Black's turn.
+        assert_eq!(game.current_player(), Color::White);
+        assert_eq!(game.play(Field(0, 0)), Err(PlaceError::CapturesNone));
+        let field = game.board().valid_moves(Color::White)[0];
+        game.play(field).unwrap();
+        assert_eq!(game.current_player(), Color::Black);
+        assert_eq!(game.history(), [Some(Field(2, 4)), None, Some(field)]);
+        assert_eq!(game.start(), &rules::Position::new());
+    }
+
+    #[test]
+    fn transcripts_make_the_forced_passes() {
+        let mut game = Game::new();
+        while game.status() == GameStatus::InProgress {
+            if let Some(&field) = game.valid_moves().last() {
+                game.play(field).unwrap();
+            } else {
+                game.pass().unwrap();
+            }
+        }
+        let transcript = game.to_transcript();
+        assert_eq!(
+            transcript.len(),
+            2 * game.history().iter().flatten().count()
+        );
+
+        let read = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(read.board(), game.board());
+        assert_eq!(read.history(), game.history());
+        assert!(Game::from_transcript(&(transcript + "A1")).is_err());
+    }
+
+    #[test]
+    fn wthor_databases_are_read() {
+        let mut game = Game::new();
+        while game.status() == GameStatus::InProgress {
+            if let Some(&field) = game.valid_moves().first() {
+                game.play(field).unwrap();
+            } else {
+                game.pass().unwrap();
+            }
+        }
+
+        let header = |records: u32, year: u16| {
+            let mut header = vec![20, 26, 10, 16];
+            header.extend(records.to_le_bytes());
+            header.extend(u16::try_from(records).unwrap().to_le_bytes());
+            header.extend(year.to_le_bytes());
+            header.extend([8, 0, 22, 0]);
+            header
+        };
+        let mut database = header(1, 1990);
+        database.extend([3, 0, 1, 0, 0, 0]);
+        database.extend([game.board().count_pieces(Color::White) as u8, 40]);
+        let mut moves = [0; 60];
+        for (code, field) in moves.iter_mut().zip(game.history().iter().flatten()) {
+            *code = (10 * (8 - field.1) + field.0 + 1) as u8;
+        }
+        database.extend(moves);
+        let mut players = header(2, 0);
+        for name in ["Anna", "Bo"] {
+            players.extend(format!("{name:\0<20}").bytes());
+        }
+        let mut tournaments = header(4, 0);
+        for name in ["", "", "", "Championnat de France"] {
+            tournaments.extend(format!("{name:\0<26}").bytes());
+        }
+
+        let (header, games) = wthor::parse_games(&database).unwrap();
+        assert_eq!(header.created, (2026, 10, 16));
+        assert_eq!(header.depth, 22);
+        let players = wthor::parse_players(&players).unwrap();
+        let tournaments = wthor::parse_tournaments(&tournaments).unwrap();
+        let saved = games[0]
+            .to_archive(&header, &players, &tournaments)
+            .unwrap();
+        assert_eq!(saved.header("White"), Some("Bo"));
+        assert_eq!(saved.header("Black"), Some("Anna"));
+        assert_eq!(saved.header("Event"), Some("Championnat de France"));
+        assert_eq!(saved.header("Date"), Some("1990.??.??"));
+        assert_eq!(saved.moves, game.history());
+
+        assert!(wthor::parse_games(&database[..50]).is_err());
+        database[24] = 99;
+        assert!(wthor::parse_games(&database).is_err());
+    }
+
+    #[test]
+    fn ggf_games_round_trip() {
+        let mut position = rules::Position::new();
+        position.play(Some(Field(5, 3))).unwrap();
+        let mut game = Game::from_position(position.clone());
+        while game.status() == GameStatus::InProgress {
+            if let Some(&field) = game.valid_moves().last() {
+                game.play(field).unwrap();
+            } else {
+                game.pass().unwrap();
+            }
+        }
+        let mut saved = archive::Game::new();
+        saved.set_header("White", "Ann [1800]");
+        saved.set_header("Date", "2026.10.16");
+        saved.set_start(&position);
+        saved.moves = game.history().to_vec();
+        saved.set_move_times(&vec![
+            std::time::Duration::from_millis(1500);
+            saved.moves.len()
+        ]);
+
+        let written = ggf::write(&[saved.clone(), saved.clone()]).unwrap();
+        assert_eq!(written.lines().count(), 2);
+        let read = ggf::parse(&written).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].moves, saved.moves);
+        assert_eq!(read[0].start(), Ok(position));
+        assert_eq!(read[0].move_times(), saved.move_times());
+        assert_eq!(read[0].header("White"), Some("Ann [1800]"));
+        assert_eq!(read[0].header("Date"), Some("2026.10.16"));
+        assert_eq!(
+            read[0].header("Result"),
+            Some(archive::Game::result_of(game.board()).as_str())
+        );
+
+        saved.set_start(&rules::Variant::Classic.start());
+        assert!(ggf::write_game(&saved).is_err());
+    }
+
+    #[test]
+    fn undo_restores_flipped_discs() {
+        let mut game = Game::new();
+        game.play(Field(2, 4)).unwrap();
+        let reply = game.board().valid_moves(Color::Black)[0];
+        game.play(reply).unwrap();
+        let after = game.board().clone();
+
+        assert_eq!(game.undo(), Some(Some(reply)));
+        assert_eq!(game.undo(), Some(Some(Field(2, 4))));
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.board(), &Board::new());
+        assert_eq!(game.current_player(), Color::White);
+
+        assert_eq!(game.redo(), Some(Some(Field(2, 4))));
+        assert_eq!(game.redo(), Some(Some(reply)));
+        assert_eq!(game.board(), &after);
+        assert_eq!(game.history(), [Some(Field(2, 4)), Some(reply)]);
+
+        // A new move replaces the moves that could be redone.
+        game.undo();
+        game.play(reply).unwrap();
+        assert_eq!(game.redo(), None);
+    }
+
+    #[test]
+    fn notations_round_trip() {
+        for notation in [
+            Notation::Standard,
+            Notation::Numeric,
+            Notation::ReversedRanks,
+            Notation::ZeroIndexed,
+        ] {
+            assert_eq!(notation.to_string().parse(), Ok(notation));
+            for field in Field::all() {
+                let written = notation.format(field);
+                assert_eq!(written.chars().count(), 2);
+                assert_eq!(notation.parse(&written), Ok(field), "{notation} {written}");
+            }
+        }
+        assert_eq!(Notation::ReversedRanks.parse("a1"), Ok(Field(0, 0)));
+        assert_eq!(Notation::ZeroIndexed.parse("70"), Ok(Field(7, 0)));
+        assert_eq!(Notation::Numeric.parse("90"), Err(PlaceError::OutOfBounds));
+        assert_eq!(
+            Notation::Standard.parse("d"),
+            Err(PlaceError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn classic_opening_places_four_discs() {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+        use rules::Variant;
+
+        let center = [Field(3, 3), Field(4, 3), Field(3, 4), Field(4, 4)];
+        let mut game = Game::from_position(Variant::Classic.start());
+        assert_eq!(game.status(), GameStatus::InProgress);
+        assert_eq!(game.play(Field(2, 3)), Err(PlaceError::OutsideCenter));
+
+        // Both players' discs side by side instead of crossed.
+        for field in center {
+            let placed = game.history().len();
+            assert_eq!(game.board().mobility(game.current_player()), 4 - placed);
+            assert_eq!(game.play(field), Ok(vec![]));
+        }
+        assert!(!game.board().in_placement_phase());
+        assert_eq!(game.board().bitboard(Color::White), 1 << 27 | 1 << 35);
+        assert_eq!(Variant::of(game.start()), Variant::Classic);
+
+        let engine = engine::Engine::new();
+        let (best, _) = engine.best_move(&Board::empty(), Color::White, SearchLimits::depth(3));
+        assert!(center.contains(&best.unwrap()));
+
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..20 {
+            let mut game = Game::from_position(Variant::Classic.start());
+            while game.status() == GameStatus::InProgress {
+                match game
+                    .board()
+                    .valid_moves(game.current_player())
+                    .choose(&mut rng)
+                {
+                    Some(&field) => game.play(field).map(drop).unwrap(),
+                    None => game.pass().unwrap(),
+                }
+            }
+            assert!(game.history().len() >= 4);
+        }
+    }
+
+    #[test]
+    fn bitboard_moves_match_the_rays() {
+        use board::report::Direction;
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        // A move is valid if a ray from it runs over the opponent's discs into an own one.
+        let captures = |board: &Board, field: Field, color: Color| -> Vec<Field> {
+            Direction::ALL
+                .into_iter()
+                .flat_map(|direction| {
+                    let ray: Vec<Field> = direction.ray(field).collect();
+                    let line = ray
+                        .iter()
+                        .take_while(|&&other| board[other] == Some(color.other()))
+                        .count();
+                    let closed = ray.get(line).is_some_and(|&end| board[end] == Some(color));
+                    ray[..if closed { line } else { 0 }].to_vec()
+                })
+                .collect()
+        };
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let mut board = Board::new();
+            let mut color = Color::White;
+            while board.status() == GameStatus::InProgress {
+                for field in Field::all() {
+                    let expected = captures(&board, field, color);
+                    let valid = board[field].is_none() && !expected.is_empty();
+                    assert_eq!(board.is_valid(field, color), valid, "{field} on {board:?}");
+                    if valid {
+                        assert_eq!(board.move_validity(field, color), Ok(expected));
+                    }
+                }
+                if let Some(&field) = board.valid_moves(color).choose(&mut rng) {
+                    board.add_piece(field, color).unwrap();
+                }
+                color = color.other();
+            }
+        }
+    }
+
+    #[test]
+    fn valid_moves_mask_matches_valid_moves() {
+        let mut board = Board::new();
+        let mut color = Color::White;
+        while let Some(&field) = board.valid_moves(color).first() {
+            let mut moves: Vec<Field> = Field::in_mask(board.valid_moves_mask(color)).collect();
+            moves.sort_by_key(|field| (field.0, field.1));
+            assert_eq!(moves, board.valid_moves(color));
+            assert!(moves
+                .iter()
+                .all(|field| Field::from_index(field.index()) == *field));
+
+            board.add_piece(field, color).unwrap();
+            color = color.other();
+        }
+    }
+
+    #[test]
+    fn tracked_board_keeps_up_with_the_moves() {
+        let mut tracked = TrackedBoard::new(Board::empty());
+        let mut color = Color::White;
+        // The last move of each color, so the game runs through passes and to the end.
+        while let Some(&field) = tracked
+            .valid_moves(color)
+            .last()
+            .or(tracked.valid_moves(color.other()).last())
+        {
+            if !tracked.is_valid(field, color) {
+                color = color.other();
+            }
+            tracked.add_piece(field, color).unwrap();
+            let board = tracked.board();
+            for color in [Color::White, Color::Black] {
+                assert_eq!(
+                    tracked.valid_moves_mask(color),
+                    board.valid_moves_mask(color)
+                );
+                assert_eq!(tracked.valid_moves(color), board.valid_moves(color));
+                assert_eq!(tracked.volatility(color), board.volatility(color));
+            }
+            assert_eq!(tracked.status(), board.status());
+            color = color.other();
+        }
+        assert_ne!(tracked.status(), GameStatus::InProgress);
+        assert!(tracked.add_piece(Field(0, 0), color).is_err());
+    }
+
+    #[test]
+    fn empty_regions_split_the_empty_fields() {
+        let mut board = Board::new();
+        let mut color = Color::White;
+        while let Some(&field) = board.valid_moves(color).last() {
+            board.add_piece(field, color).unwrap();
+            color = color.other();
+
+            let regions = board.empty_regions();
+            let mut fields: Vec<Field> = regions.iter().flatten().copied().collect();
+            assert_eq!(
+                fields.len(),
+                64 - board.count_pieces(Color::White) - board.count_pieces(Color::Black)
+            );
+            fields.sort_by_key(|field| (field.0, field.1));
+            fields.dedup();
+            assert_eq!(fields.len(), regions.iter().map(Vec::len).sum::<usize>());
+
+            let parity = engine::Weights {
+                discs: 0,
+                parity: 1,
+                ..Default::default()
+            };
+            assert_eq!(
+                parity.evaluate(&board, color),
+                -parity.evaluate(&board, color.other())
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_large_batch() {
+        let engine = engine::Engine::new();
+        // Enough distinct boards to be evaluated on several threads.
+        let boards: Vec<Board> = (0..3000_usize)
+            .map(|i| {
+                let mut board = Board::new();
+                for (bit, field) in Field::all().enumerate().take(12) {
+                    if i >> bit & 1 == 1 {
+                        board.set(
+                            field,
+                            Some(if bit % 3 == 0 {
+                                Color::Black
+                            } else {
+                                Color::White
+                            }),
+                        );
+                    }
+                }
+                board
+            })
+            .collect();
+
+        let expected: Vec<i32> = boards
+            .iter()
+            .map(|board| engine.eval(board, Color::White))
+            .collect();
+        assert_eq!(engine.evaluate_batch(&boards, Color::White), expected);
+    }
+
+    #[test]
+    fn engine_is_thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<engine::Engine>();
+
+        let handle = engine::EngineHandle::default();
+        let searches = [Color::White, Color::Black]
+            .map(|color| handle.analyze_async(&Board::new(), color, SearchLimits::depth(2)));
+        for search in searches {
+            assert!(search.join().unwrap().0.is_some());
+        }
+    }
+
+    #[test]
+    fn transposition_table_persists_between_moves() {
+        let persistent = engine::Engine::new();
+        let (mut persistent_nodes, mut fresh_nodes) = (0, 0);
+        let mut board = Board::new();
+        let mut color = Color::White;
+
+        for _ in 0..10 {
+            persistent.prune_hash(&board);
+            persistent.reset_counters();
+            let fresh = engine::Engine::new();
+
+            let limits = SearchLimits::depth(4);
+            let (best_move, evaluation) = persistent.best_move(&board, color, limits);
+            assert_eq!(fresh.best_move(&board, color, limits).1, evaluation);
+            persistent_nodes += persistent.nodes();
+            fresh_nodes += fresh.nodes();
+
+            board.add_piece(best_move.unwrap(), color).unwrap();
+            color = color.other();
+        }
+
+        assert!(persistent_nodes < fresh_nodes);
+    }
+
+    #[test]
+    fn solved_endgames_are_cached() {
+        let engine = engine::Engine::new();
+        let mut board = Board::new();
+        let mut color = Color::White;
+        while board.count_pieces(Color::White) + board.count_pieces(Color::Black) < 52 {
+            let (best_move, _) = engine.best_move(&board, color, SearchLimits::depth(1));
+            if let Some(field) = best_move {
+                board.add_piece(field, color).unwrap();
+            }
+            color = color.other();
+        }
+
+        let solved = engine.solve(&board, color);
+        assert!(!engine.endgame_cache().is_empty());
+        let saved: engine::EndgameCache = engine.endgame_cache().to_string().parse().unwrap();
+
+        let fresh = engine::Engine::new();
+        *fresh.endgame_cache() = saved;
+        assert_eq!(fresh.solve(&board, color), solved);
+        assert_eq!(fresh.nodes(), 1);
+    }
+
+    #[test]
+    fn exported_hash_resumes_the_search() {
+        let engine = engine::Engine::new();
+        let board = Board::new();
+        let searched = engine.best_move(&board, Color::White, SearchLimits::depth(5));
+        let line = engine.best_line(&board, Color::White);
+        assert_eq!(line.first().copied(), searched.0);
+
+        let fresh = engine::Engine::new();
+        assert!(fresh.import_hash(&engine.export_hash()).unwrap() > 0);
+        assert_eq!(fresh.best_line(&board, Color::White), line);
+        assert_eq!(
+            fresh.best_move(&board, Color::White, SearchLimits::depth(5)),
+            searched
+        );
+        assert!(fresh.nodes() < engine.nodes());
+    }
+
+    #[test]
+    fn limited_strength() {
+        let strength = engine::Strength::from_elo(800);
+        let engine = engine::Engine::new();
+        engine.limit_strength(strength);
+
+        let board = Board::new();
+        let evaluation = engine.eval(&board, Color::White);
+        assert!(evaluation.abs() <= strength.noise);
+        assert_eq!(engine.eval(&board, Color::White), evaluation);
+
+        engine.best_move(&board, Color::White, SearchLimits::depth(6));
+        assert!(engine.nodes() < strength.node_limit + 100);
+    }
+
+    #[test]
+    fn strength_limits_each_search() {
+        let strength = engine::Strength::from_elo(800);
+        let engine = engine::Engine::new();
+        engine.limit_strength(strength);
+
+        // Like the candidates of a bot with variety, without resetting the counters in between.
+        let board = Board::new();
+        for field in board.valid_moves(Color::White) {
+            let after = board.with_move(field, Color::White).unwrap();
+            let nodes = engine.nodes();
+            let (best, _) = engine.best_move(&after, Color::Black, SearchLimits::depth(6));
+            assert!(best.is_some());
+            assert!(engine.nodes() - nodes > strength.node_limit / 2);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn fields_out_of_bounds_panic() {
+        // Its bit would be the one of `Field(0, 1)`.
+        let _ = Board::new()[Field(8, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn setting_fields_out_of_bounds_panics() {
+        Board::new().set(Field(0, 8), Some(Color::White));
+    }
+
+    #[test]
+    fn stable_position_hash() {
+        // Peers built from other versions compare this value, so it must never change.
+        assert_eq!(Board::new().position_hash(), 0x81d9_bd2c_9cbd_904b);
+
+        let mut board = Board::new();
+        board.add_piece(Field(2, 4), Color::White).unwrap();
+        let mut transposed = Board::new();
+        transposed.add_piece(Field(4, 2), Color::White).unwrap();
+        assert_ne!(board.position_hash(), transposed.position_hash());
+
+        // A pass doesn't change the discs, but the side to move.
+        let position = rules::Position::from_board(board.clone(), Color::Black);
+        assert_eq!(position.position_hash(), 0x1e37_ef21_71a1_e759);
+        assert_ne!(
+            position.position_hash(),
+            rules::Position::from_board(board, Color::White).position_hash()
+        );
+    }
+
+    #[test]
+    fn search_node_limit() {
+        let engine = engine::Engine::new();
+        let limits = SearchLimits {
+            depth: 6,
+            nodes: Some(200),
+        };
+        let (best_move, _) = engine.best_move(&Board::new(), Color::White, limits);
+        assert!(best_move.is_some());
+        assert!(engine.nodes() < 300);
+
+        // The limit only applies to the search it was given to.
+        engine.clear_hash();
+        engine.reset_counters();
+        engine.best_move(&Board::new(), Color::White, SearchLimits::depth(6));
+        assert!(engine.nodes() > 300);
+    }
+
+    /// Search the start position to `depth` on `handle` while `other` searches run on it in
+    /// another thread, returning the evaluation.
+    fn search_beside(
+        handle: &engine::EngineHandle,
+        depth: u8,
+        other: impl Fn(&Board, Color) + Sync,
+    ) -> i32 {
+        use std::{
+            sync::atomic::{AtomicBool, Ordering},
+            thread,
+        };
+
+        // A position of its own, so the searches don't share table entries.
+        let mut board = Board::new();
+        let mut color = Color::White;
+        for _ in 0..6 {
+            board = board.with_move(board.valid_moves(color)[0], color).unwrap();
+            color = color.other();
+        }
+        let (started, done) = (AtomicBool::new(false), AtomicBool::new(false));
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    other(&board, color);
+                    started.store(true, Ordering::Relaxed);
+                }
+            });
+            while !started.load(Ordering::Relaxed) {
+                thread::yield_now();
+            }
+            let (_, evaluation) =
+                handle.best_move(&Board::new(), Color::White, SearchLimits::depth(depth));
+            done.store(true, Ordering::Relaxed);
+            evaluation
+        })
+    }
+
+    #[test]
+    fn limits_only_stop_their_own_search() {
+        use std::time::Duration;
+
+        let handle = engine::EngineHandle::default();
+        let limits = SearchLimits {
+            depth: 8,
+            nodes: Some(50),
+        };
+        let evaluation = search_beside(&handle, 9, |board, color| {
+            handle.best_move(board, color, limits);
+        });
+        let (_, expected) =
+            engine::Engine::new().best_move(&Board::new(), Color::White, SearchLimits::depth(9));
+        assert_eq!(evaluation, expected);
+
+        handle.clear_hash();
+        let evaluation = search_beside(&handle, 9, |board, color| {
+            handle.deepen(board, color, Duration::from_millis(1), 8);
+        });
+        assert_eq!(evaluation, expected);
+    }
+
+    #[test]
+    fn volatile_leaves_are_extended() {
+        let engine = engine::Engine::new();
+        let mut board = Board::new();
+        let mut color = Color::White;
+
+        // Play until a move gives the opponent many discs to flip.
+        let volatile = loop {
+            let moves = board.valid_moves(color);
+            let volatile = moves.iter().any(|&field| {
+                let after = board.with_move(field, color).unwrap();
+                after.volatility(color.other()) >= engine::VOLATILE_DISCS
+            });
+            if volatile {
+                break moves.len();
+            }
+            let (best, _) = engine.best_move(&board, color, SearchLimits::depth(2));
+            board.add_piece(best.unwrap(), color).unwrap();
+            color = color.other();
+        };
+
+        engine.clear_hash();
+        engine.reset_counters();
+        engine.best_move(&board, color, SearchLimits::depth(1));
+        assert!(engine.nodes() > 1 + volatile as u64);
+    }
+
+    #[test]
+    fn passes_keep_the_turn_order() {
+        use reversi::rules::Position;
+
+        // Black has no valid moves until all of its discs are captured.
+        let mut board = Board::empty();
+        for row in [0, 3, 7] {
+            board.set(Field(0, row), Some(Color::White));
+            board.set(Field(1, row), Some(Color::Black));
+        }
+        let mut position = Position::from_board(board, Color::White);
+
+        for row in [0, 7] {
+            position.play(Some(Field(2, row))).unwrap();
+            assert_eq!(position.to_move(), Color::Black);
+            assert!(position.must_pass());
+            assert_eq!(
+                position.play(Some(Field(2, 3))),
+                Err(PlaceError::CapturesNone)
+            );
+            position.play(None).unwrap();
+            assert_eq!(position.to_move(), Color::White);
+            assert!(!position.must_pass());
+        }
+
+        // White has valid moves, so it can only give up its turn.
+        assert_eq!(position.play(None), Err(PlaceError::CannotPass));
+        position.forfeit_turn().unwrap();
+        position.play(None).unwrap();
+        assert_eq!(position.to_move(), Color::White);
+
+        position.play(Some(Field(2, 3))).unwrap();
+        assert_eq!(position.status(), GameStatus::Win(Color::White));
+        assert_eq!(position.play(None), Err(PlaceError::GameOver));
+    }
+
+    #[test]
+    fn scores_are_from_the_side_to_move() {
+        let engine = engine::Engine::new();
+        let mut board = Board::new();
+        board.add_piece(Field(2, 4), Color::White).unwrap();
+        assert_eq!(
+            engine.eval(&board, Color::Black),
+            -engine.eval(&board, Color::White)
+        );
+
+        let best = board
+            .valid_moves(Color::Black)
+            .into_iter()
+            .map(|field| -engine.eval(&board.with_move(field, Color::Black).unwrap(), Color::White))
+            .max();
+        assert_eq!(
+            engine
+                .best_move(&board, Color::Black, SearchLimits::depth(1))
+                .1,
+            best.unwrap()
+        );
+    }
+
+    #[cfg(feature = "bot-bridge")]
+    #[test]
+    fn bot_bridge_commands() {
+        use bot_bridge::{Bridge, Render};
+
+        let mut bridge = Bridge::new(Render::Emoji);
+        assert!(bridge
+            .handle("#a", "alice", "!board")
+            .unwrap()
+            .contains("no game"));
+        assert!(bridge.handle("#a", "alice", "!new").unwrap().contains("🟩"));
+        assert!(bridge
+            .handle("#a", "alice", "!new")
+            .unwrap()
+            .contains("already"));
+
+        // The engine answers at once, so it is White's turn again.
+        let reply = bridge.handle("#a", "alice", "!move d3").unwrap();
+        assert!(reply.contains("The engine plays"));
+        assert!(reply.ends_with(&format!(
+            "{} alice to move ({} 3, {} 3)",
+            Color::White,
+            Color::White,
+            Color::Black
+        )));
+
+        bridge.handle("#b", "alice", "!new @bob");
+        assert!(bridge
+            .handle("#b", "bob", "!move d3")
+            .unwrap()
+            .contains("alice's turn"));
+        assert!(bridge
+            .handle("#b", "alice", "!move a1")
+            .unwrap()
+            .starts_with("Invalid move"));
+        assert_eq!(
+            bridge.handle("#b", "bob", "!resign").unwrap(),
+            "bob resigns, alice wins."
+        );
+        assert!(bridge
+            .handle("#b", "bob", "!board")
+            .unwrap()
+            .contains("no game"));
+        assert_eq!(bridge.handle("#b", "bob", "good game"), None);
+    }
+
+    #[test]
+    fn boards_round_trip_through_bytes() {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        // The discs as an array of rows, to compare without the bitboards.
+        let fields = |board: &Board| -> [[Option<Color>; 8]; 8] {
+            std::array::from_fn(|y| std::array::from_fn(|x| board[Field(x, y)]))
+        };
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let mut game = Game::new();
+            while game.status() == GameStatus::InProgress {
+                let board = game.board();
+                let decoded = Board::from_bytes(board.to_bytes()).unwrap();
+                assert_eq!(fields(&decoded), fields(board));
+                assert_eq!(&decoded, board);
+                match board.valid_moves(game.current_player()).choose(&mut rng) {
+                    Some(&field) => game.play(field).map(drop).unwrap(),
+                    None => game.pass().unwrap(),
+                }
+            }
+        }
+        assert_eq!(Board::empty().to_bytes(), [0; 16]);
+
+        let mut bytes = Board::new().to_bytes();
+        bytes[11] |= 0x08;
+        assert_eq!(
+            Board::from_bytes(bytes),
+            Err(format!("Fields hold discs of both colors: {}", Field(3, 3)))
+        );
+    }
+
+    #[test]
+    fn legal_move_map_matches_move_validity() {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut game = Game::new();
+        while game.status() == GameStatus::InProgress {
+            let board = game.board();
+            for color in [Color::White, Color::Black] {
+                let map = board.legal_move_map(color);
+                for field in Field::all() {
+                    let Field(x, y) = field;
+                    assert_eq!(map[y][x], board.move_validity(field, color).is_ok());
+                }
+            }
+            match board.valid_moves(game.current_player()).choose(&mut rng) {
+                Some(&field) => game.play(field).map(drop).unwrap(),
+                None => game.pass().unwrap(),
+            }
+        }
+    }
+
+    #[test]
+    fn game_records_keep_the_archive_headers() {
+        use record::GameRecord;
+
+        let archived = &archive::parse(
+            "[White \"Ann\"]\n[Black \"Bo\"]\n[Site \"Club\"]\n[Result \"*\"]\n\
+             [MoveTimes \"1.5 2.0\"]\n\n{friendly} 1. d3 c5 {sharp} *",
+        )
+        .unwrap()[0];
+        let mut record = GameRecord::from_archive(archived).unwrap();
+        assert_eq!(
+            (record.white.as_str(), record.black.as_str()),
+            ("Ann", "Bo")
+        );
+        assert_eq!(record.headers, [("Site".to_owned(), "Club".to_owned())]);
+        assert_eq!(record.comment.as_deref(), Some("friendly"));
+        assert_eq!(record.moves[1].played, Some(Field(2, 3)));
+        assert_eq!(record.moves[1].comment.as_deref(), Some("sharp"));
+        assert_eq!(
+            record.moves[0].time,
+            Some(std::time::Duration::from_millis(1500))
+        );
+        assert_eq!(record.to_archive().to_string(), archived.to_string());
+
+        // Times are only written if every move has one.
+        record.moves[0].time = None;
+        assert_eq!(record.to_archive().header("MoveTimes"), None);
+        assert_eq!(record.replay().unwrap().history().len(), 2);
+
+        let mut commented = archived.clone();
+        commented.comments.insert(3, "later".to_owned());
+        assert!(GameRecord::from_archive(&commented).is_err());
+    }
+
+    #[test]
+    fn diff_names_every_change() {
+        use board::FieldChange;
+
+        let before = Board::new();
+        let mut after = before.clone();
+        after.set(Field(3, 3), None);
+        after.set(Field(4, 3), Some(Color::White));
+        after.set(Field(0, 0), Some(Color::Black));
+        assert_eq!(
+            before.diff(&after),
+            [
+                (Field(0, 0), FieldChange::Added(Color::Black)),
+                (Field(3, 3), FieldChange::Removed(Color::White)),
+                (Field(4, 3), FieldChange::Flipped(Color::White)),
+            ]
+        );
+        assert_eq!(after.diff(&before).len(), 3);
+    }
+
+    #[test]
+    fn deepening_keeps_the_result_of_each_depth() {
+        use std::time::Duration;
+
+        let board = Board::new();
+        let depths = engine::Engine::new().deepen(&board, Color::White, Duration::from_secs(30), 4);
+        assert_eq!(depths.len(), 4);
+        for (depth, &(best_move, evaluation)) in (1..).zip(&depths) {
+            let fresh = engine::Engine::new();
+            let (_, expected) = fresh.best_move(&board, Color::White, SearchLimits::depth(depth));
+            assert_eq!(evaluation, expected, "depth {depth}");
+            assert!(board.is_valid(best_move.unwrap(), Color::White));
+        }
+    }
 }
@@ -1,8 +1,91 @@
+pub mod analyze;
+pub mod bench;
+pub mod correspond;
+pub mod crash;
+pub mod demo;
+pub mod diff;
+pub mod drill;
+pub mod duel;
+pub mod games;
+pub mod generate;
+pub mod me;
 pub mod play;
+pub mod quiz;
+pub mod rpc;
+pub mod simul;
 
-use clap::{builder::PossibleValuesParser, crate_version, value_parser, Arg, Command, ValueSource};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    crate_version,
+    parser::ValueSource,
+    value_parser, Arg, ArgAction, ArgMatches, Command,
+};
+use reversi_game::{
+    board::{Notation, TextDirection, Theme},
+    engine::{throttle, Engine, Weights},
+    rules::{Position, Variant},
+};
 
-fn cli() -> Command<'static> {
+use std::{env, fs, path::PathBuf, process, time::Duration};
+
+/// Parse the name of a built-in theme or the path of a theme file.
+fn parse_theme(value: &str) -> Result<Theme, String> {
+    match Theme::named(value) {
+        Some(theme) => Ok(theme),
+        None => fs::read_to_string(value)
+            .map_err(|error| format!("not a built-in theme and not a readable file: {error}"))?
+            .parse(),
+    }
+}
+
+fn parse_position(value: &str) -> Result<Position, String> {
+    analyze::load_position(value)
+}
+
+/// Parse a time like `2s`, `500ms` or `1.5` (seconds).
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.strip_suffix("ms") {
+        Some(millis) => (millis, 1e-3),
+        None => (value.strip_suffix('s').unwrap_or(value), 1.0),
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * unit).ok())
+        .filter(|time| !time.is_zero())
+        .ok_or_else(|| format!("`{value}` is not a time like 2s, 500ms or 1.5"))
+}
+
+fn parse_weights(value: &str) -> Result<Weights, String> {
+    fs::read_to_string(value)
+        .map_err(|error| format!("failed to read the file: {error}"))?
+        .parse()
+}
+
+fn theme_arg() -> Arg {
+    Arg::new("theme")
+        .help("The glyphs used to draw the board")
+        .long_help(format!(
+            "The glyphs used to draw the board: one of the built-in themes ({}) or a file with \
+             `key = value` lines for the glyphs white, black, empty, marker and edge.",
+            Theme::NAMES.join(", ")
+        ))
+        .long("theme")
+        .value_name("NAME|FILE")
+        .value_parser(parse_theme)
+}
+
+/// Only include saved games with all of the given tags.
+fn tag_filter_arg() -> Arg {
+    Arg::new("tag")
+        .help("Only include games with this tag, can be given several times")
+        .long("tag")
+        .value_name("TAG")
+        .action(ArgAction::Append)
+}
+
+pub fn cli() -> Command {
     Command::new("reversi")
         .version(crate_version!())
         .author("Leo Blume <leoblume@gmx.de>")
@@ -12,6 +95,7 @@ fn cli() -> Command<'static> {
                 .help("Play against another player")
                 .short('p')
                 .long("player")
+                .action(ArgAction::SetTrue)
                 .conflicts_with("bot"),
         )
         .arg(
@@ -19,25 +103,155 @@ fn cli() -> Command<'static> {
                 .help("Play against a bot")
                 .short('b')
                 .long("bot")
+                .action(ArgAction::SetTrue)
                 .conflicts_with("player"),
         )
+        .arg(
+            Arg::new("privacy")
+                .help("Hide the board between the turns of two human players")
+                .long("privacy")
+                .action(ArgAction::SetTrue)
+                .requires("player"),
+        )
+        .arg(
+            Arg::new("no-hints")
+                .help("Don't mark the valid moves on the board")
+                .long("no-hints")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-confirm")
+                .help("Resign, start a new game or quit without asking first")
+                .long("no-confirm")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("move-timeout")
+                .help("The time in seconds a human has to enter each move")
+                .long("move-timeout")
+                .value_name("SECONDS")
+                .value_parser(value_parser!(u64).range(1..=3600))
+                .conflicts_with("watch"),
+        )
+        .arg(
+            Arg::new("timeout-action")
+                .help("What happens when a human doesn't enter a move in time")
+                .long("timeout-action")
+                .value_parser(PossibleValuesParser::new(["pass", "random", "forfeit", "engine"]))
+                .default_value("random")
+                .requires("move-timeout"),
+        )
+        .arg(
+            Arg::new("watch")
+                .help("Watch two bots play against each other")
+                .long_help("Watch two bots play against each other. During the game, press space to pause or resume, +/- to change the speed and s to play a single move.")
+                .short('w')
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["player", "bot"]),
+        )
+        .arg(
+            Arg::new("commentary")
+                .help("Comment on each move while watching")
+                .long("commentary")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["player", "bot"]),
+        )
+        .arg(
+            Arg::new("watch-delay")
+                .help("The initial time between two moves in watch mode in milliseconds")
+                .long("watch-delay")
+                .value_name("MS")
+                .default_value("1000")
+                .value_parser(value_parser!(u64).range(100..=8000)),
+        )
         .arg(
             Arg::new("depth")
                 .help("The depth of the bot's search (implies --bot)")
                 .short('d')
                 .long("depth")
-                .takes_value(true)
                 .default_value("3")
                 .value_parser(value_parser!(u8).range(1..=8)),
         )
+        .arg(
+            Arg::new("think-time")
+                .help("Let the bot choose its depth to think about SECONDS per move (implies --bot)")
+                .long_help("Let the bot choose its depth to think about SECONDS per move (implies --bot). The first move is searched with --depth to measure the speed of the engine; after that, the depth grows by at most one per move. SECONDS is the average: the only valid move is played at once, and the time saved goes to critical moves with few valid moves, after the evaluation swung, in volatile positions and just before the endgame.")
+                .long("think-time")
+                .value_name("SECONDS")
+                .value_parser(value_parser!(u64).range(1..=600)),
+        )
+        .arg(
+            Arg::new("movetime")
+                .help("Let the bot search deeper and deeper until TIME per move is up, e.g. 2s or 500ms (implies --bot)")
+                .long_help("Let the bot search depth 1, 2, 3, ... until TIME per move is up and play the best move of the deepest search it completed (implies --bot). TIME is like 2s, 500ms or 1.5 (seconds). A search cut short by the deadline is thrown away.")
+                .long("movetime")
+                .value_name("TIME")
+                .value_parser(parse_duration)
+                .conflicts_with_all(["depth", "think-time", "variety", "adaptive"]),
+        )
+        .arg(
+            Arg::new("strength")
+                .help("Limit the bot to play roughly like a player with this Elo rating (implies --bot)")
+                .long_help("Limit the bot to play roughly like a player with this Elo rating (implies --bot). Weaker bots search shallower, look at fewer positions and misjudge positions by a few discs.")
+                .long("strength")
+                .value_name("ELO")
+                .value_parser(value_parser!(u16).range(400..=2000))
+                .conflicts_with_all(["depth", "think-time", "movetime"]),
+        )
+        .arg(
+            Arg::new("variety")
+                .help("Let the bot pick randomly among almost equally good moves for the first PLIES plies")
+                .long("variety")
+                .value_name("PLIES")
+                .value_parser(value_parser!(u8).range(1..=60)),
+        )
+        .arg(
+            Arg::new("adaptive")
+                .help("Keep the game close: when further ahead than LEAD discs, the bot plays worse moves (implies --bot)")
+                .long_help("Keep the game close for learners (implies --bot): when the bot is further ahead than LEAD discs, it plays the worst move that keeps that lead, at most --adaptive-max-loss discs worse than the best one. When it isn't that far ahead, it plays its best moves. Each deviation is printed and kept as a comment on the move in saved games.")
+                .long("adaptive")
+                .value_name("LEAD")
+                .value_parser(value_parser!(u8).range(0..=64)),
+        )
+        .arg(
+            Arg::new("adaptive-max-loss")
+                .help("How many discs worse than the best move an adaptive bot's move may be")
+                .long("adaptive-max-loss")
+                .value_name("DISCS")
+                .default_value("8")
+                .value_parser(value_parser!(u8).range(1..=64))
+                .requires("adaptive"),
+        )
+        .arg(
+            Arg::new("weights")
+                .help("A file with the weights of the bot's evaluation function")
+                .long_help("A file with the weights of the bot's evaluation function: `version = 1`, followed by `key = value` lines for the weights discs, mobility, corners, x_squares, edges and parity. See --export-weights for an example.")
+                .long("weights")
+                .value_name("FILE")
+                .value_parser(parse_weights),
+        )
+        .arg(
+            Arg::new("export-weights")
+                .help("Print the weights of the evaluation function (the default ones or those of --weights) in the weights file format")
+                .long("export-weights")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("hash")
+                .help("The size of the bot's transposition table in MB")
+                .long("hash")
+                .value_name("MB")
+                .default_value("16")
+                .value_parser(value_parser!(u16).range(1..=4096)),
+        )
         .arg(
             Arg::new("animation-speed")
             .help("The speed of the animation")
             .long_help("How long it takes to animate one flip. 'slow' corresponds to 0.8 seconds, 'medium' to 0.3 seconds and 'fast' to 0.1 seconds.")
             .short('s')
             .long("speed")
-            .takes_value(true)
-            .value_parser(PossibleValuesParser::new(vec![
+            .value_parser(PossibleValuesParser::new([
                 "slow",
                 "medium",
                 "fast",
@@ -51,19 +265,689 @@ fn cli() -> Command<'static> {
             .help("Disable the animation")
             .long("no-animation")
             .short('A')
+            .action(ArgAction::SetTrue)
             .conflicts_with("animation-speed")
             )
+        .arg(theme_arg())
+        .arg(
+            Arg::new("save")
+                .help("Save the game to the games directory when it is over, or to FILE as JSON")
+                .long_help("Save the game to the games directory when it is over. With FILE, the game is written to FILE as JSON instead, also when it is adjourned or abandoned, so it can be continued with --load FILE.")
+                .long("save")
+                .value_name("FILE")
+                .num_args(0..=1)
+                .default_missing_value(""),
+        )
+        .arg(
+            Arg::new("load")
+                .help("Continue a game saved as JSON with --save FILE, saving it there again")
+                .long("load")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with_all(["resume", "start-position", "variant"]),
+        )
+        .arg(
+            Arg::new("start-position")
+                .help("Start the game from a board drawn in a file, with rows of . X O")
+                .long_help("Start the game from a board drawn in a file: eight rows of . (empty), X (black) and O (white), optionally followed by a line like `X to move`. Spaces, row numbers and column letters are ignored.")
+                .long("start-position")
+                .value_name("FILE")
+                .value_parser(parse_position),
+        )
+        .arg(
+            Arg::new("variant")
+                .help("The opening rules: standard (Othello) or classic (Reversi, the first four discs are placed on the center)")
+                .long_help("The opening rules: standard (Othello, starting with two discs of each color crossed in the center) or classic (the original Reversi, starting with an empty board on which the players take turns placing the first four discs on any of the center fields, without capturing).")
+                .long("variant")
+                .value_name("NAME")
+                .value_parser(
+                    PossibleValuesParser::new(Variant::NAMES)
+                        .map(|name| name.parse::<Variant>().unwrap()),
+                )
+                .conflicts_with("start-position"),
+        )
+        .arg(
+            Arg::new("resume")
+                .help("Continue a game saved with the `adjourn` command, with the time that was left")
+                .long("resume")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with_all(["start-position", "variant"]),
+        )
+        .arg(
+            Arg::new("tag")
+                .help("Label the saved game, can be given several times")
+                .long("tag")
+                .value_name("TAG")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("eval-graph")
+                .help("Show a graph of the evaluation under the board")
+                .short('g')
+                .long("eval-graph")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("notation")
+                .help("How fields are written and entered: standard (d3), numeric (43), reversed (d6) or zero (35)")
+                .long_help("How fields are written and entered everywhere in the program: standard (d3, ranks 8 to 1 from the top), numeric (43, files as numbers), reversed (d6, ranks 1 to 8 from the top) or zero (35, column and row from 0 at the top left). Defaults to $REVERSI_NOTATION. Saved games, CSV and JSON-RPC always use the standard notation.")
+                .long("notation")
+                .value_name("NAME")
+                .value_parser(
+                    PossibleValuesParser::new(Notation::NAMES)
+                        .map(|name| name.parse::<Notation>().unwrap()),
+                )
+                .global(true),
+        )
+        .arg(
+            Arg::new("direction")
+                .help("Draw the board left to right, right to left (mirrored) or as the locale is written")
+                .long_help("The direction the board is drawn in: ltr, rtl or auto for the direction of the language of $LC_ALL, $LC_MESSAGES or $LANG. Right to left, the board is mirrored with the file a on the right, and each line starts with a left-to-right mark so that terminals with bidirectional text support keep the columns aligned. Fields are named and entered as usual. Defaults to $REVERSI_DIRECTION.")
+                .long("direction")
+                .value_name("DIRECTION")
+                .value_parser(
+                    PossibleValuesParser::new(TextDirection::NAMES)
+                        .map(|name| name.parse::<TextDirection>().unwrap()),
+                )
+                .global(true),
+        )
+        .arg(
+            Arg::new("nice")
+                .help("Search in a low-power mode, e.g. for long batches in the background")
+                .long_help("Let the engine use less CPU, e.g. for long self-play or analysis batches in the background on a laptop: each search pauses as long as it works, and batches use at most half of the cores. Also turned on by `nice = on` in the settings file.")
+                .long("nice")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("broadcast")
+                .help("Stream the board to web pages, see `http://ADDR/`, e.g. 127.0.0.1:8080")
+                .long_help("Let spectators follow the game in a browser: the board after every move is sent as JSON to `http://ADDR/events` as Server-Sent Events, and `http://ADDR/` shows them, e.g. with the address 127.0.0.1:8080.")
+                .long("broadcast")
+                .value_name("ADDR"),
+        )
+        .arg(
+            Arg::new("webhook")
+                .help("POST the result of each game as JSON to an http:// URL (no TLS), default: $REVERSI_WEBHOOK")
+                .long_help("Send the result of each finished game to URL in a POST request, as JSON with the players, the score, the winner and the moves, e.g. to feed a club's leaderboard. Only http:// URLs are supported, as there is no TLS; for https, use a local relay. Defaults to $REVERSI_WEBHOOK.")
+                .long("webhook")
+                .value_name("URL"),
+        )
+        .arg(
+            Arg::new("json-rpc")
+                .help("Answer JSON-RPC 2.0 requests on the standard input, one per line, e.g. for a graphical front end")
+                .long("json-rpc")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(
+            Arg::new("engine-info")
+                .help("Print the engine's name, version and capabilities")
+                .long("engine-info")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Generate a shell completion script")
+                .arg(
+                    Arg::new("shell")
+                        .help("The shell to generate the script for")
+                        .required(true)
+                        .value_parser(PossibleValuesParser::new(generate::SHELLS)),
+                ),
+        )
+        .subcommand(
+            Command::new("analyze")
+                .visible_alias("explore")
+                .about("Set up positions and let the engine analyze them")
+                .arg(
+                    Arg::new("depth")
+                        .help("The default depth of the engine's search")
+                        .short('d')
+                        .long("depth")
+                        .default_value("5")
+                        .value_parser(value_parser!(u8).range(1..=10)),
+                )
+                .arg(
+                    Arg::new("no-hints")
+                        .help("Don't mark the valid moves on the board")
+                        .long("no-hints")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("always-hints"),
+                )
+                .arg(
+                    Arg::new("always-hints")
+                        .help("Mark the valid moves of both colors")
+                        .long("always-hints")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(theme_arg())
+                .arg(
+                    Arg::new("position")
+                        .help("Start from a board drawn in a file, with rows of . X O")
+                        .long("position")
+                        .value_name("FILE")
+                        .value_parser(parse_position),
+                )
+                .arg(
+                    Arg::new("resume-analysis")
+                        .help("Keep the analysis in FILE after each search, continuing the one saved there")
+                        .long_help("Keep the analysis in FILE after each search, continuing the one saved there: the position, the deepest search, its best line and the engine's cached search results. `deeper` then searches on from the saved depth.")
+                        .long("resume-analysis")
+                        .value_name("FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .conflicts_with("position"),
+                ),
+        )
+        .subcommand(
+            Command::new("analyze-batch")
+                .about("Analyze all games of a directory, write annotated copies and report the accuracy of each player")
+                .arg(
+                    Arg::new("directory")
+                        .help("The directory with the games, e.g. the saved games")
+                        .required(true)
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .help("The depth of the engine's search")
+                        .short('d')
+                        .long("depth")
+                        .default_value("4")
+                        .value_parser(value_parser!(u8).range(1..=10)),
+                )
+                .arg(
+                    Arg::new("jobs")
+                        .help("The number of games analyzed at the same time, by default one per CPU (half as many with --nice)")
+                        .short('j')
+                        .long("jobs")
+                        .value_parser(value_parser!(u16).range(1..=1024)),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Solve a suite of endgame positions and report correctness, nodes and time")
+                .arg(
+                    Arg::new("suite")
+                        .help("A suite of positions in OBF format, e.g. the FFO endgame test suite")
+                        .long("suite")
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("ffo")
+                        .help("Solve the bundled FFO positions #40 to #45 but #44, with their published results")
+                        .long("ffo")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("suite"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .help("Print the results as JSON, e.g. to compare a later run against")
+                        .long("json")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .help("Compare nodes and time with the output of an earlier `bench --json`")
+                        .long("baseline")
+                        .value_name("FILE")
+                        .conflicts_with("json"),
+                ),
+        )
+        .subcommand(
+            Command::new("correspond")
+                .about("Make one move in a correspondence game file, e.g. to play by email")
+                .long_about("Make one move in a correspondence game file, e.g. to play by email or in a shared folder: show the position, read the move of the side to move, write the file back with a new checksum and exit. Forced passes are made right away. A file that doesn't exist yet starts a new game.")
+                .arg(
+                    Arg::new("file")
+                        .help("The game file, passed back and forth between the players")
+                        .required(true)
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("white")
+                        .help("The name of White when starting a new game")
+                        .long("white")
+                        .value_name("NAME")
+                        .default_value("Player 1"),
+                )
+                .arg(
+                    Arg::new("black")
+                        .help("The name of Black when starting a new game")
+                        .long("black")
+                        .value_name("NAME")
+                        .default_value("Player 2"),
+                ),
+        )
+        .subcommand(
+            Command::new("demo")
+                .about("Let bots play each other in a loop, without any input, e.g. on a display at events")
+                .arg(
+                    Arg::new("depth")
+                        .help("The depth of the bots")
+                        .short('d')
+                        .long("depth")
+                        .default_value("3")
+                        .value_parser(value_parser!(u8).range(1..=8)),
+                )
+                .arg(
+                    Arg::new("delay")
+                        .help("The time between two moves in milliseconds")
+                        .long("delay")
+                        .default_value("1000")
+                        .value_parser(value_parser!(u64).range(100..=8000)),
+                )
+                .arg(
+                    Arg::new("pause")
+                        .help("The time the results and the title are shown between games in seconds")
+                        .long("pause")
+                        .default_value("5")
+                        .value_parser(value_parser!(u64).range(0..=600)),
+                )
+                .arg(
+                    Arg::new("games")
+                        .help("Stop after this many games instead of looping forever")
+                        .long("games")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("broadcast")
+                        .help("Also stream the games to web pages, see `reversi --help`")
+                        .long("broadcast")
+                        .value_name("ADDR"),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Show how two positions differ on one board, e.g. to debug an engine or protocol desync")
+                .long_about("Show the second position with the changes from the first one: unchanged discs are dim, new discs green (+), removed discs red (-) and flipped discs yellow (~). The changed fields are listed below. Exits with 1 if the positions differ.")
+                .arg(
+                    Arg::new("before")
+                        .help("The first position: a file with a board drawn like for --start-position, or the rows on one line separated by `/`")
+                        .required(true)
+                        .value_name("POSITION")
+                        .value_parser(diff::parse_position),
+                )
+                .arg(
+                    Arg::new("after")
+                        .help("The second position, like the first")
+                        .required(true)
+                        .value_name("POSITION")
+                        .value_parser(diff::parse_position),
+                ),
+        )
+        .subcommand(
+            Command::new("drill")
+                .about("Practice tactics on positions from random games, with hints")
+                .arg(
+                    Arg::new("theme")
+                        .help("The tactic to practice: winning a corner by force or wedging in on an edge")
+                        .long("theme")
+                        .default_value("corners")
+                        .value_parser(PossibleValuesParser::new(drill::THEMES)),
+                )
+                .arg(
+                    Arg::new("count")
+                        .help("The number of drills")
+                        .short('n')
+                        .long("count")
+                        .default_value("5")
+                        .value_parser(value_parser!(u64).range(1..=50)),
+                ),
+        )
+        .subcommand(
+            Command::new("duel")
+                .about("Let two bots play several games at once, shown side by side as they go")
+                .long_about("Let two bots play several games at once, shown side by side as they go, with the discs and the thinking time of each side. Every two games start with the same few random moves, with the bots swapping colors; the results are listed when all games are over.")
+                .arg(
+                    Arg::new("depths")
+                        .help("The depths of the two bots, A and B")
+                        .short('d')
+                        .long("depths")
+                        .num_args(2)
+                        .value_names(["A", "B"])
+                        .default_values(["3", "3"])
+                        .value_parser(value_parser!(u8).range(1..=8)),
+                )
+                .arg(
+                    Arg::new("games")
+                        .help("The number of games played at once")
+                        .short('n')
+                        .long("games")
+                        .default_value("4")
+                        .value_parser(value_parser!(u8).range(1..=16)),
+                )
+                .arg(
+                    Arg::new("delay")
+                        .help("The pause after each move of a game in milliseconds, to follow the games")
+                        .long("delay")
+                        .default_value("0")
+                        .value_parser(value_parser!(u64).range(0..=8000)),
+                )
+                .arg(
+                    Arg::new("save")
+                        .help("Save the games to the games directory, tagged `duel`")
+                        .long("save")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("games")
+                .about("Manage the saved games")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("import")
+                        .about("Add the games of archive files to the saved games")
+                        .long_about("Add the games of archive files to the saved games. Files of Othello transcripts like F5D6C3, one game per line, are imported too, tagged `transcript`, games in the Generic Game Format of GGS and NBoard, tagged `ggf`, and WTHOR databases (.wtb) with WTHOR.JOU and WTHOR.TRN next to them, tagged `wthor`, each into a single file.")
+                        .arg(
+                            Arg::new("files")
+                                .help("The archive, transcript, GGF or WTHOR files to import")
+                                .required(true)
+                                .num_args(1..)
+                                .value_name("FILE"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("export")
+                        .about("Write all saved games into a single archive")
+                        .arg(
+                            Arg::new("game")
+                                .help("Only export this game, as numbered by `reversi games list`")
+                                .value_parser(value_parser!(usize)),
+                        )
+                        .arg(tag_filter_arg())
+                        .arg(
+                            Arg::new("output")
+                                .help("The file to write to instead of the standard output")
+                                .short('o')
+                                .long("output")
+                                .value_name("FILE"),
+                        )
+                        .arg(
+                            Arg::new("csv")
+                                .help("Write one row of evaluations, time, mobility and discs per move instead")
+                                .long("csv")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("transcript")
+                                .help("Write one Othello transcript like F5D6C3 per game instead")
+                                .long("transcript")
+                                .action(ArgAction::SetTrue)
+                                .conflicts_with("csv"),
+                        )
+                        .arg(
+                            Arg::new("ggf")
+                                .help("Write the games in the Generic Game Format of GGS and NBoard instead")
+                                .long("ggf")
+                                .action(ArgAction::SetTrue)
+                                .conflicts_with_all(["csv", "transcript"]),
+                        ),
+                )
+                .subcommand(
+                    Command::new("list")
+                        .about("List the saved games")
+                        .arg(tag_filter_arg()),
+                )
+                .subcommand(
+                    Command::new("tag")
+                        .about("Label a saved game")
+                        .arg(
+                            Arg::new("game")
+                                .help("The number of the game, as shown by `reversi games list`")
+                                .required(true)
+                                .value_parser(value_parser!(usize)),
+                        )
+                        .arg(
+                            Arg::new("tags")
+                                .help("The tags to add")
+                                .required(true)
+                                .num_args(1..)
+                                .value_name("TAG"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("replay")
+                        .about("Step through a saved game and comment on its moves")
+                        .arg(
+                            Arg::new("game")
+                                .help("The number of the game, as shown by `reversi games list`")
+                                .required(true)
+                                .value_parser(value_parser!(usize)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("me")
+                .about("Show a player's rating history, drill streak, openings, results and accuracy")
+                .arg(
+                    Arg::new("name")
+                        .help("The player's name in the saved games")
+                        .long("name")
+                        .default_value("Player 1"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .help("The depth of the engine's search when judging the accuracy")
+                        .short('d')
+                        .long("depth")
+                        .default_value("3")
+                        .value_parser(value_parser!(u8).range(1..=10)),
+                ),
+        )
+        .subcommand(
+            Command::new("quiz")
+                .about("Find better moves where you lost discs in one of your saved games")
+                .long_about("Find better moves where you lost discs in one of your saved games. The game is replayed up to each of your moves that lost at least --min-loss discs according to `reversi analyze-batch`, whose `Analysis` header is read from the game or from its copy in the `annotated` directory, and with your move hidden you enter a better one. The score of each quiz is kept to show your progress.")
+                .arg(
+                    Arg::new("game")
+                        .help("The game, as numbered by `reversi games list`, by default your last analyzed game with mistakes")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("name")
+                        .help("Your name in the saved games")
+                        .long("name")
+                        .default_value("Player 1"),
+                )
+                .arg(
+                    Arg::new("min-loss")
+                        .help("Only ask about moves that lost at least this many discs")
+                        .long("min-loss")
+                        .value_name("DISCS")
+                        .default_value("4")
+                        .value_parser(value_parser!(u8).range(1..=64)),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .help("The depth of the engine's search when judging your moves")
+                        .short('d')
+                        .long("depth")
+                        .default_value("4")
+                        .value_parser(value_parser!(u8).range(1..=10)),
+                ),
+        )
+        .subcommand(
+            Command::new("simul")
+                .about("Play several bots at once, moving from board to board")
+                .long_about("Play several bots at once, moving from board to board. You play White everywhere; after each move you walk on to the next board while the bot thinks. Enter the number of a board to go there, and press <Tab> or <Enter> on an empty line for the next one.")
+                .arg(
+                    Arg::new("opponents")
+                        .help("The number of bots")
+                        .short('n')
+                        .long("opponents")
+                        .default_value("4")
+                        .value_parser(value_parser!(u8).range(2..=8)),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .help("The depth of the bots' search")
+                        .short('d')
+                        .long("depth")
+                        .default_value("3")
+                        .value_parser(value_parser!(u8).range(1..=8)),
+                )
+                .arg(
+                    Arg::new("time")
+                        .help("The time you have for each board in minutes, losing the board when it runs out")
+                        .long("time")
+                        .value_name("MINUTES")
+                        .value_parser(value_parser!(u64).range(1..=120)),
+                )
+                .arg(
+                    Arg::new("name")
+                        .help("Your name in the saved games")
+                        .long("name")
+                        .default_value("Player 1"),
+                )
+                .arg(
+                    Arg::new("save")
+                        .help("Save the games to the games directory, tagged `simul`")
+                        .long("save")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(Command::new("manpage").about("Generate the man page in roff format"))
+        .args_conflicts_with_subcommands(true)
 }
 
 fn main() {
+    crash::install();
     let matches = cli().get_matches();
-    if matches.is_present("player") {
-        play::run(&play::Opponent::Human, &matches);
-    } else if matches.is_present("bot")
+    match matches.get_one::<Notation>("notation") {
+        Some(&notation) => notation.make_current(),
+        None => match env::var("REVERSI_NOTATION").map(|name| name.parse::<Notation>()) {
+            Ok(Ok(notation)) => notation.make_current(),
+            Ok(Err(error)) => {
+                eprintln!("Invalid $REVERSI_NOTATION: {error}");
+                process::exit(2);
+            }
+            Err(_) => {}
+        },
+    }
+
+    match matches.get_one::<TextDirection>("direction") {
+        Some(&direction) => direction.make_current(),
+        None => match env::var("REVERSI_DIRECTION").map(|name| name.parse::<TextDirection>()) {
+            Ok(Ok(direction)) => direction.make_current(),
+            Ok(Err(error)) => {
+                eprintln!("Invalid $REVERSI_DIRECTION: {error}");
+                process::exit(2);
+            }
+            Err(_) => {}
+        },
+    }
+    throttle::set_low_power(play::Settings::nice(&matches));
+
+    match matches.subcommand() {
+        Some(("completions", sub_matches)) => {
+            let mut cmd = cli();
+            cmd.build();
+            let shell = sub_matches.get_one::<String>("shell").unwrap();
+            print!("{}", generate::completions(&cmd, shell));
+            return;
+        }
+        Some(("analyze", sub_matches)) => {
+            exit_on_error(analyze::run(sub_matches));
+            return;
+        }
+        Some(("analyze-batch", sub_matches)) => {
+            exit_on_error(games::batch::run(sub_matches));
+            return;
+        }
+        Some(("bench", sub_matches)) => {
+            exit_on_error(bench::run(sub_matches));
+            return;
+        }
+        Some(("correspond", sub_matches)) => {
+            correspond::run(sub_matches);
+            return;
+        }
+        Some(("demo", sub_matches)) => {
+            demo::run(sub_matches);
+            return;
+        }
+        Some(("diff", sub_matches)) => {
+            diff::run(sub_matches);
+            return;
+        }
+        Some(("drill", sub_matches)) => {
+            drill::run(sub_matches);
+            return;
+        }
+        Some(("duel", sub_matches)) => {
+            duel::run(sub_matches);
+            return;
+        }
+        Some(("games", sub_matches)) => {
+            exit_on_error(games::run(sub_matches));
+            return;
+        }
+        Some(("me", sub_matches)) => {
+            exit_on_error(me::run(sub_matches));
+            return;
+        }
+        Some(("quiz", sub_matches)) => {
+            exit_on_error(quiz::run(sub_matches));
+            return;
+        }
+        Some(("simul", sub_matches)) => {
+            simul::run(sub_matches);
+            return;
+        }
+        Some(("manpage", _)) => {
+            let mut cmd = cli();
+            cmd.build();
+            print!("{}", generate::manpage(&cmd));
+            return;
+        }
+        _ => {}
+    }
+
+    if matches.get_flag("export-weights") {
+        print!(
+            "{}",
+            matches
+                .get_one::<Weights>("weights")
+                .copied()
+                .unwrap_or_default()
+        );
+    } else if matches.get_flag("json-rpc") {
+        rpc::run();
+    } else if matches.get_flag("engine-info") {
+        println!("{}", Engine::info());
+    } else if matches.get_flag("watch") {
+        play(&play::Opponent::Watch, &matches);
+    } else if matches.get_flag("player") {
+        play(&play::Opponent::Human, &matches);
+    } else if matches.get_flag("bot")
         || matches.value_source("depth").unwrap() != ValueSource::DefaultValue
+        || matches.contains_id("think-time")
+        || matches.contains_id("movetime")
+        || matches.contains_id("strength")
+        || matches.contains_id("adaptive")
     {
-        play::run(&play::Opponent::Bot, &matches);
+        play(&play::Opponent::Bot, &matches);
     } else {
         eprintln!("Please specify either --player or --bot");
     }
 }
+
+/// Play a game, exiting with an error code if it fails.
+fn play(opponent: &play::Opponent, matches: &ArgMatches) {
+    if let Err(error) = play::run(opponent, matches) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+/// Exit with an error code if a subcommand failed.
+fn exit_on_error(result: Result<(), String>) {
+    if let Err(error) = result {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,268 @@
+//! A dashboard of one player's statistics, from the saved games and the drill results.
+
+use crate::{
+    analyze::TEST_POSITIONS,
+    drill,
+    games::{
+        self,
+        batch::{self, Accuracy},
+    },
+};
+use reversi_game::{archive::Game, engine::Engine, reversi::*};
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, HashMap},
+    fs, io,
+};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+/// The rating every player starts with.
+const INITIAL_RATING: f64 = 1200.0;
+/// How much a single game can change a rating.
+const K_FACTOR: f64 = 32.0;
+/// The number of moves that make up an opening.
+const OPENING_PLIES: usize = 3;
+/// The number of rows of the rating chart.
+const CHART_HEIGHT: usize = 6;
+
+/// The score of White in a finished game, 1 for a win, 0.5 for a draw and 0 for a loss.
+fn white_score(game: &Game) -> Option<f64> {
+    let (white, black) = game.header("Result")?.split_once('-')?;
+    let (white, black): (u32, u32) = (white.parse().ok()?, black.parse().ok()?);
+    Some(match white.cmp(&black) {
+        Ordering::Greater => 1.0,
+        Ordering::Equal => 0.5,
+        Ordering::Less => 0.0,
+    })
+}
+
+/// The Elo ratings of `name` after each of their finished games, rating every player and bot
+/// of the saved games from `INITIAL_RATING` on in the order the games were played.
+fn rating_history(games: &[Game], name: &str) -> Vec<f64> {
+    let mut ratings: HashMap<&str, f64> = HashMap::new();
+    let mut history = Vec::new();
+
+    for game in games {
+        let (Some(white), Some(black), Some(score)) = (
+            game.header("White"),
+            game.header("Black"),
+            white_score(game),
+        ) else {
+            continue;
+        };
+        let white_rating = *ratings.get(white).unwrap_or(&INITIAL_RATING);
+        let black_rating = *ratings.get(black).unwrap_or(&INITIAL_RATING);
+        let expected = 1.0 / (1.0 + 10f64.powf((black_rating - white_rating) / 400.0));
+        let change = K_FACTOR * (score - expected);
+        ratings.insert(white, white_rating + change);
+        ratings.insert(black, black_rating - change);
+
+        if white == name || black == name {
+            history.push(ratings[name]);
+        }
+    }
+
+    history
+}
+
+/// Draw `values` as a chart of `CHART_HEIGHT` rows with the highest and lowest value on the left.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn chart(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let row_of = |value: f64| {
+        if max > min {
+            ((value - min) / (max - min) * (CHART_HEIGHT - 1) as f64).round() as usize
+        } else {
+            0
+        }
+    };
+
+    (0..CHART_HEIGHT)
+        .rev()
+        .map(|row| {
+            let label = match row {
+                _ if row == CHART_HEIGHT - 1 => format!("{max:>5.0}"),
+                0 => format!("{min:>5.0}"),
+                _ => " ".repeat(5),
+            };
+            let line: String = values
+                .iter()
+                .map(|&value| if row_of(value) == row { '*' } else { ' ' })
+                .collect();
+            format!("{label} ┤{}", line.trim_end())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The current and the longest streak of solved drills, and the solved and all drills.
+fn drill_streaks() -> io::Result<(usize, usize, usize, usize)> {
+    let results = match fs::read_to_string(drill::results_path()) {
+        Ok(results) => results,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+
+    let (mut current, mut best, mut solved, mut total) = (0, 0, 0, 0);
+    for line in results.lines().filter(|line| !line.trim().is_empty()) {
+        total += 1;
+        if line.ends_with("solved") {
+            solved += 1;
+            current += 1;
+            best = usize::max(best, current);
+        } else {
+            current = 0;
+        }
+    }
+    Ok((current, best, solved, total))
+}
+
+/// The name of the test position an opening leads to, also mirrored or rotated.
+fn opening_name(moves: &[Option<Field>]) -> Option<&'static str> {
+    let mut board = Board::new();
+    let mut color = Color::White;
+    for &field in moves.iter().take(2) {
+        board.add_piece(field?, color).ok()?;
+        color = color.other();
+    }
+
+    TEST_POSITIONS
+        .iter()
+        .filter(|position| position.moves.len() == 2)
+        .find(|position| position.setup().0.canonical().0 == board.canonical().0)
+        .map(|position| position.description)
+}
+
+/// The openings of `games` as their moves, written out and as fields, and how often they were
+/// played, the most frequent first.
+fn openings<'a>(games: &[&'a Game]) -> Vec<(String, &'a [Option<Field>], usize)> {
+    let mut counts: BTreeMap<String, (&[Option<Field>], usize)> = BTreeMap::new();
+    for game in games
+        .iter()
+        .filter(|game| game.moves.len() >= OPENING_PLIES)
+    {
+        let moves = &game.moves[..OPENING_PLIES];
+        let line = moves
+            .iter()
+            .map(|field| field.map_or("--".to_owned(), |field| field.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        counts.entry(line).or_insert((moves, 0)).1 += 1;
+    }
+    let mut openings: Vec<_> = counts
+        .into_iter()
+        .map(|(line, (moves, count))| (line, moves, count))
+        .collect();
+    openings.sort_by_key(|&(_, _, count)| Reverse(count));
+    openings
+}
+
+/// The wins, draws and losses of `name` with `color`.
+fn record(games: &[&Game], name: &str, color: Color) -> (usize, usize, usize) {
+    let mut record = (0, 0, 0);
+    for game in games {
+        if game.header(&format!("{color:?}")) != Some(name) {
+            continue;
+        }
+        let Some(score) = white_score(game) else {
+            continue;
+        };
+        let score = if color == Color::White {
+            score
+        } else {
+            1.0 - score
+        };
+        if score > 0.5 {
+            record.0 += 1;
+        } else if score < 0.5 {
+            record.2 += 1;
+        } else {
+            record.1 += 1;
+        }
+    }
+    record
+}
+
+fn dashboard(name: &str, depth: u8) -> Result<String, String> {
+    let games: Vec<Game> = games::load_all()
+        .map_err(|error| format!("Failed to load the saved games: {error}"))?
+        .into_iter()
+        .map(|(_, game)| game)
+        .collect();
+    let mine: Vec<&Game> = games
+        .iter()
+        .filter(|game| game.header("White") == Some(name) || game.header("Black") == Some(name))
+        .collect();
+
+    let mut out = vec![format!("{} ({} games)\n", name.bold(), mine.len())];
+
+    let history = rating_history(&games, name);
+    match history.last() {
+        Some(rating) => {
+            let best = history.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            out.push(format!("Rating {rating:.0} (best {best:.0})"));
+            out.push(chart(&history));
+        }
+        None => out.push("No finished games to rate yet.".to_owned()),
+    }
+
+    let (current, best, solved, total) =
+        drill_streaks().map_err(|error| format!("Failed to read the drill results: {error}"))?;
+    out.push(format!(
+        "\nDrills: {solved} of {total} solved, streak {current} (best {best})"
+    ));
+
+    let openings = openings(&mine);
+    if !openings.is_empty() {
+        out.push("\nMost played openings:".to_owned());
+    }
+    for (line, moves, count) in openings.iter().take(5) {
+        let opening = opening_name(moves).unwrap_or_default().to_lowercase();
+        out.push(format!("{count:>4}  {line:<10} {opening}"));
+    }
+
+    out.push(String::new());
+    for color in [Color::White, Color::Black] {
+        let (wins, draws, losses) = record(&mine, name, color);
+        let played = wins + draws + losses;
+        #[allow(clippy::cast_precision_loss)]
+        let rate = wins as f64 / played.max(1) as f64 * 100.0;
+        out.push(format!(
+            "As {color:?}: {wins} wins, {draws} draws, {losses} losses ({rate:.0}% won)"
+        ));
+    }
+
+    if !mine.is_empty() {
+        let engine = Engine::new();
+        *engine.endgame_cache() = games::load_endgames();
+        let mut accuracy = Accuracy::default();
+        for game in &mine {
+            let analysis = batch::analyze(&engine, game, depth)?;
+            for color in [Color::White, Color::Black] {
+                if game.header(&format!("{color:?}")) == Some(name) {
+                    accuracy.add_game(&analysis, color);
+                }
+            }
+        }
+        // The endgames only speed up later runs.
+        let _ = games::save_endgames(&engine.endgame_cache());
+        out.push(format!(
+            "\nAccuracy: {:.1}% best moves, {:.2} discs lost per move (depth {depth})",
+            accuracy.best_move_rate() * 100.0,
+            accuracy.average_loss()
+        ));
+    }
+
+    Ok(out.join("\n"))
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    println!("{}", dashboard(name, depth)?);
+    Ok(())
+}
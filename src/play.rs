@@ -1,80 +1,531 @@
+pub mod broadcast;
+pub mod commentary;
+pub mod game_file;
+pub mod input;
+pub mod log;
 pub mod player;
+pub mod settings;
+pub mod watch;
+pub mod webhook;
 
 pub use player::*;
+pub use settings::Settings;
+pub use watch::Playback;
 
-use reversi_game::reversi::*;
+use crate::{crash, games};
+use reversi_game::{
+    archive::Game,
+    board::display::{clear_screen, sparkline},
+    engine::{Engine, Weights},
+    record::GameRecord,
+    reversi::*,
+    rules::{self, Position, Variant},
+};
 
-use std::time::Duration;
+use std::{
+    env, fmt, io,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::ArgMatches;
-use colored::Colorize;
+use reversi_game::board::display::style::Colorize;
 
 pub enum Opponent {
     Human,
     Bot,
+    /// Let two bots play against each other.
+    Watch,
+    /// Let two bots play against each other without any input, see `demo`.
+    Demo,
 }
 
-pub fn run(opponent: &Opponent, matches: &ArgMatches) {
-    let mut board = Board::new();
-    let animation_speed: Duration = match matches.value_of("animation-speed") {
-        Some("slow") => Duration::from_millis(800),
-        Some("medium") => {
-            if matches.is_present("no-animation") {
-                Duration::ZERO
-            } else {
-                Duration::from_millis(300)
-            }
+/// How a game ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// The outcome, `InProgress` if the game was abandoned.
+    pub status: GameStatus,
+    /// Why the game ended before the board was full, as in the `Termination` header.
+    pub termination: Option<&'static str>,
+}
+
+/// Something that went wrong while playing a game.
+#[derive(Debug)]
+pub enum GameError {
+    /// The game was over, but couldn't be saved.
+    Save(io::Error),
+    /// The adjourned game to resume couldn't be loaded.
+    Resume(String),
+    /// The address given with `--broadcast` couldn't be listened on.
+    Broadcast(io::Error),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Save(error) => write!(f, "Failed to save the game: {error}"),
+            GameError::Resume(error) => write!(f, "Failed to resume the game: {error}"),
+            GameError::Broadcast(error) => write!(f, "Failed to start the broadcast: {error}"),
         }
-        Some("fast") => Duration::from_millis(100),
-        _ => unreachable!(),
-    };
+    }
+}
 
-    redraw_board(&board, &Default::default());
+/// How often a human may choose an invalid move in a row before forfeiting the game.
+const MAX_INVALID_MOVES: u32 = 3;
 
-    let player_white: Box<dyn Player> =
-        Box::new(HumanPlayer::new(Color::White, "Player 1".to_string()));
-    let player_black: Box<dyn Player> = match opponent {
-        Opponent::Human => Box::new(HumanPlayer::new(Color::Black, "Player 2".to_string())),
-        Opponent::Bot => Box::new(MinimaxBot::new(
-            Color::Black,
-            *matches.get_one::<u8>("depth").unwrap(),
+/// Play a game, or continue the adjourned game given with `--resume` or the game saved as JSON
+/// given with `--load`, returning how it ended.
+///
+/// A player choosing an invalid move is asked again if they are human,
+/// otherwise they forfeit the game.
+pub fn run(opponent: &Opponent, matches: &ArgMatches) -> Result<GameResult, GameError> {
+    let resumed = match (
+        matches.get_one::<PathBuf>("resume"),
+        matches.get_one::<PathBuf>("load"),
+    ) {
+        (Some(path), _) => Some((
+            path.clone(),
+            games::load_adjourned(path).map_err(GameError::Resume)?,
+        )),
+        (None, Some(path)) => Some((
+            path.clone(),
+            game_file::load(path).map_err(GameError::Resume)?,
         )),
+        (None, None) => None,
     };
+    play(opponent, matches, resumed)
+}
 
-    let mut counter = 0;
-    while board.status() == board::GameStatus::InProgress {
-        counter += 1;
+/// Play a game, continuing the `resumed` game from its file if given.
+fn play(
+    opponent: &Opponent,
+    matches: &ArgMatches,
+    resumed: Option<(PathBuf, Game)>,
+) -> Result<GameResult, GameError> {
+    let mut game = rules::Game::from_position(match &resumed {
+        Some((_, saved)) => saved.start().map_err(GameError::Resume)?,
+        None => match matches.get_one::<Position>("start-position") {
+            Some(position) => position.clone(),
+            None => matches
+                .get_one::<Variant>("variant")
+                .copied()
+                .unwrap_or_default()
+                .start(),
+        },
+    });
+    // The moves with their times and the notes of the players on them, see `Player::note`.
+    let mut record = match &resumed {
+        Some((_, saved)) => GameRecord::from_archive(saved).map_err(GameError::Resume)?,
+        None => GameRecord::new("?", "?", game.start().clone()),
+    };
+    // The time the side to move had left when the game was adjourned.
+    let mut clock = None;
+    if resumed.is_some() {
+        for (index, recorded) in record.moves.iter().enumerate() {
+            game.replay_move(recorded.played)
+                .map_err(|error| GameError::Resume(format!("Move {}: {error}", index + 1)))?;
+        }
+        clock = record
+            .header("Clock")
+            .and_then(|seconds| seconds.parse().ok())
+            .map(|seconds| (game.current_player(), Duration::from_secs_f64(seconds)));
+        record.remove_header("Clock");
+        record.termination = None;
+    }
+    record.date = Some(games::today());
+    record.time_control = Some("-".to_owned());
+    record.set_header("Variant", Variant::of(game.start()).to_string());
+    // The theme, animation and hints can change during the game, see `settings`.
+    let mut settings = Settings::new(matches);
+    // Without a terminal, the game is logged line by line instead of drawn, see `log`.
+    let pipe = log::enabled();
+
+    if !pipe {
+        redraw_board(
+            game.board(),
+            &DisplayOptions {
+                theme: settings.theme.clone(),
+                ..Default::default()
+            },
+        );
+    }
+
+    let bot = |color| {
+        let mut bot = MinimaxBot::new(color, *matches.get_one::<u8>("depth").unwrap());
+        if let Some(&seconds) = matches.get_one::<u64>("think-time") {
+            bot = bot.with_think_time(Duration::from_secs(seconds));
+        }
+        if let Some(&time) = matches.get_one::<Duration>("movetime") {
+            bot = bot.with_move_time(time);
+        }
+        if let Some(&weights) = matches.get_one::<Weights>("weights") {
+            bot = bot.with_weights(weights);
+        }
+        if let Some(&elo) = matches.get_one::<u16>("strength") {
+            bot = bot.with_strength(elo);
+        }
+        if let Some(&plies) = matches.get_one::<u8>("variety") {
+            bot = bot.with_variety(plies);
+        }
+        if let Some(&lead) = matches.get_one::<u8>("adaptive") {
+            bot = bot.with_adaptive(Adaptive {
+                lead: lead.into(),
+                max_loss: (*matches.get_one::<u8>("adaptive-max-loss").unwrap()).into(),
+            });
+        }
+        bot.engine()
+            .resize_hash((*matches.get_one::<u16>("hash").unwrap()).into());
+        if pipe {
+            bot = bot.quiet();
+        }
+        bot
+    };
 
-        let player = match counter % 2 {
-            0 => &player_black,
-            1 => &player_white,
+    let human = |color, name: &str| -> Box<dyn Player> {
+        let mut player = HumanPlayer::new(color, name.to_owned())
+            .with_confirmations(!matches.get_flag("no-confirm"));
+        let Some(&seconds) = matches.get_one::<u64>("move-timeout") else {
+            return Box::new(player);
+        };
+        if let Some((_, time_left)) = clock.filter(|&(to_move, _)| to_move == color) {
+            player = player.with_time_left(time_left);
+        }
+        let timeout = Duration::from_secs(seconds);
+        let action = match matches
+            .get_one::<String>("timeout-action")
+            .map(String::as_str)
+        {
+            Some("pass") => TimeoutAction::Pass,
+            Some("random") => TimeoutAction::Random,
+            Some("forfeit") => TimeoutAction::Forfeit,
+            // The bot moves instead of a human who passes when the time is up.
+            Some("engine") => {
+                return Box::new(Fallback::new(
+                    Box::new(player.with_timeout(timeout, TimeoutAction::Pass)),
+                    Box::new(bot(color).without_confirmation()),
+                ))
+            }
             _ => unreachable!(),
         };
+        Box::new(player.with_timeout(timeout, action))
+    };
 
-        redraw_board(&board, &player.redraw_options());
+    let player_white: Box<dyn Player> = match opponent {
+        Opponent::Watch | Opponent::Demo => Box::new(bot(Color::White).without_confirmation()),
+        _ => human(Color::White, "Player 1"),
+    };
+    let player_black: Box<dyn Player> = match opponent {
+        Opponent::Human => human(Color::Black, "Player 2"),
+        Opponent::Bot => Box::new(bot(Color::Black)),
+        Opponent::Watch | Opponent::Demo => Box::new(bot(Color::Black).without_confirmation()),
+    };
+    record.white = player_white.name();
+    record.black = player_black.name();
 
-        let field = player.turn(&board);
+    let broadcast = match matches.get_one::<String>("broadcast") {
+        Some(address) => Some(broadcast::start(address).map_err(GameError::Broadcast)?),
+        None => None,
+    };
+    let send_state = |game: &rules::Game| {
+        if let Some(broadcast) = broadcast {
+            broadcast.send(&player_white.name(), &player_black.name(), game);
+        }
+    };
+    send_state(&game);
+    if pipe {
+        log::start(&player_white.name(), &player_black.name());
+    }
+
+    // The playback controls need a terminal to show their state.
+    let mut playback = (matches!(opponent, Opponent::Watch) && !pipe).then(|| {
+        let delay = Duration::from_millis(*matches.get_one::<u64>("watch-delay").unwrap());
+        Playback::new(delay)
+    });
+
+    // Disc differences beyond this are clamped so a won game doesn't flatten the graph.
+    const MAX_GRAPH_EVAL: i32 = 64;
+    // Only the most recent evaluations fit under the board.
+    const GRAPH_WIDTH: usize = 40;
 
-        if let Some(field) = field {
-            let mut anim_board = board.clone();
-            anim_board[field] = Some(player.color());
+    let eval_graph = matches.get_flag("eval-graph");
+    let mut evaluations = Vec::new();
+    let footer = |evaluations: &[i32], comment: Option<&String>| {
+        let graph = eval_graph.then(|| {
+            let recent = &evaluations[evaluations.len().saturating_sub(GRAPH_WIDTH)..];
+            format!(
+                "Eval {} ({:+})",
+                sparkline(recent),
+                recent.last().copied().unwrap_or(0)
+            )
+        });
+        let lines: Vec<String> = graph.into_iter().chain(comment.cloned()).collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    };
+    let engine = Engine::new();
+
+    // The valid moves are counted from the masks the game keeps up to date.
+    let header = |board: &TrackedBoard| {
+        format!(
+            "Mobility: White {} / Black {}",
+            board.mobility(Color::White),
+            board.mobility(Color::Black)
+        )
+    };
 
-            let mut captures = board
-                .add_piece(field, player.color())
-                .unwrap_or_else(|err| {
-                    panic!("Failed to add piece `{}`: {}", field.to_string(), err);
-                });
+    // Hide the board between the turns of two humans sharing a device,
+    // so the legal move hints aren't revealed to the wrong player.
+    let privacy_screen = matches.get_flag("privacy") && matches!(opponent, Opponent::Human);
+
+    // Remarks on the moves of watched games, see `commentary`.
+    let commentary = matches.get_flag("commentary");
+    let mut comment = None;
+
+    let mut request = None;
+    let mut resigned = None;
+    let mut invalid_move = None;
+    'game: while game.status() == GameStatus::InProgress {
+        let player = match game.current_player() {
+            Color::White => &player_white,
+            Color::Black => &player_black,
+        };
+
+        if privacy_screen {
+            clear_screen();
+            println!(
+                "Pass the device to {} {}, then press <Enter>.",
+                player.color(),
+                player.name().bold()
+            );
+            input::read_line();
+        }
+
+        if let Some(message) = settings.refresh() {
+            comment = Some(message);
+        }
+        let options = DisplayOptions {
+            title: Some(header(game.tracked_board())),
+            bold_title: false,
+            footer: footer(&evaluations, comment.as_ref()),
+            hints: if settings.hints {
+                Hints::Color
+            } else {
+                Hints::Off
+            },
+            theme: settings.theme.clone(),
+            clear_screen: !pipe,
+            ..player.redraw_options()
+        };
+
+        if !pipe {
+            redraw_board(game.board(), &options);
+        }
+
+        let start = Instant::now();
+        let before = game.board().clone();
+        let mut attempts = 0;
+        let (field, mut captures) = loop {
+            attempts += 1;
+            let played = player.turn(&before, &options);
+            if player.request() == Some(Request::Undo) {
+                let placed = |game: &rules::Game| game.history().iter().flatten().count();
+                let placed_before = placed(&game);
+                comment = Some(take_back(&mut game, player.color()));
+                // The evaluations of a resumed game start after the saved moves.
+                evaluations.truncate(
+                    evaluations.len() - (placed_before - placed(&game)).min(evaluations.len()),
+                );
+                record.truncate(game.history().len());
+                send_state(&game);
+                continue 'game;
+            }
+            if player.request() == Some(Request::Reload) {
+                comment = Some(settings.reload());
+                continue 'game;
+            }
+            if player.request() == Some(Request::ForfeitTurn) {
+                game.forfeit_turn()
+                    .expect("the game goes on while players take turns");
+                break (None, Vec::new());
+            }
+            if let Some(player_request) = player.request() {
+                request = Some((player.color(), player_request));
+                break 'game;
+            }
+            if played == Move::Resign {
+                resigned = Some(player.color());
+                break 'game;
+            }
+            // Passing with a valid move is an invalid move like any other.
+            match game.play_move(played.field()) {
+                Ok(captures) => break (played.field(), captures),
+                // A human may have made a typo, anything else would choose the same move again.
+                Err(error) if player.is_human() && attempts < MAX_INVALID_MOVES => {
+                    println!("Invalid move {played}: {error}");
+                }
+                Err(error) => {
+                    invalid_move = Some((player.color(), format!("{played}: {error}")));
+                    break 'game;
+                }
+            }
+        };
+        record.push(field, start.elapsed());
+        if let Some(note) = player.note() {
+            record.set_comment(game.history().len(), &note);
+        }
+
+        // Keep the game for a crash report in case the rest of the turn panics.
+        crash::record(&record.to_archive(), game.board(), game.current_player());
+        send_state(&game);
+        if pipe {
+            log::played(
+                game.history().len(),
+                player.color(),
+                field,
+                game.board(),
+                engine
+                    .eval(game.board(), Color::White)
+                    .clamp(-MAX_GRAPH_EVAL, MAX_GRAPH_EVAL),
+                record
+                    .moves
+                    .last()
+                    .and_then(|recorded| recorded.time)
+                    .unwrap_or_default(),
+            );
+        }
+
+        let commentary_thread =
+            commentary.then(|| commentary::spawn(&before, player.color(), field));
+
+        if let Some(field) = field.filter(|_| !pipe) {
+            let mut anim_board = before;
+            anim_board.set(field, Some(player.color()));
 
             captures.sort_by_key(|capture| {
                 usize::wrapping_sub(field.0, capture.0).wrapping_pow(2)
                     + usize::wrapping_sub(field.1, capture.1).wrapping_pow(2)
             });
 
-            animate_by(&anim_board, &captures, animation_speed, &Default::default());
-        } else {
-            continue;
+            let time_per_flip = match &playback {
+                Some(playback) => settings.animation_speed.div_f64(playback.speed_factor()),
+                None => settings.animation_speed,
+            };
+            evaluations.push(
+                engine
+                    .eval(game.board(), Color::White)
+                    .clamp(-MAX_GRAPH_EVAL, MAX_GRAPH_EVAL),
+            );
+
+            animate_by(
+                &anim_board,
+                &captures,
+                time_per_flip,
+                &DisplayOptions {
+                    title: Some(header(game.tracked_board())),
+                    bold_title: false,
+                    footer: footer(&evaluations, None),
+                    theme: settings.theme.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // A failed remark is left out instead of ending the game.
+        if let Some(Ok(remark)) = commentary_thread.map(thread::JoinHandle::join) {
+            println!("{remark}");
+            comment = Some(remark);
+        }
+
+        if let Some(playback) = &mut playback {
+            playback.wait();
+        } else if matches!(opponent, Opponent::Demo | Opponent::Watch) {
+            thread::sleep(Duration::from_millis(
+                *matches.get_one::<u64>("watch-delay").unwrap(),
+            ));
         }
     }
+    crash::forget();
+
+    // A game loaded from JSON is saved back to its file, unless `--save` names another.
+    let json_file = match matches.get_one::<String>("save") {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => matches
+            .get_one::<PathBuf>("load")
+            .filter(|_| resumed.is_some())
+            .cloned(),
+    };
+
+    // Saves the game with the time left for the side to move, if it was adjourned.
+    let save = |termination: Option<&str>, clock: Option<Duration>| {
+        let mut finished = record.clone();
+        finished.result = Game::result_of(game.board());
+        finished.termination = termination.map(str::to_owned);
+        if let Some(clock) = clock {
+            finished.set_header("Clock", format!("{:.1}", clock.as_secs_f64()));
+        }
+        // The search statistics of the bots, e.g. `WhiteStats`, to compare engines later.
+        for player in [&player_white, &player_black] {
+            if let Some(statistics) = player.statistics() {
+                finished.set_header(
+                    &format!("{:?}Stats", player.color()),
+                    statistics.to_string(),
+                );
+            }
+        }
+        let mut saved = finished.to_archive();
+        for tag in matches.get_many::<String>("tag").unwrap_or_default() {
+            saved.add_tag(tag);
+        }
+
+        let path = match (&json_file, &resumed) {
+            (Some(path), _) => game_file::write(path, &saved).map(|()| path.clone()),
+            (None, Some((path, _))) => games::replace(path, &saved).map(|()| path.clone()),
+            (None, None) => games::save(&saved),
+        }
+        .map_err(GameError::Save)?;
+        println!("Saved the game to {}", path.display());
+        Ok(path)
+    };
+
+    let loser = match request {
+        Some((_, Request::NewGame)) => return play(opponent, matches, None),
+        Some((_, Request::Adjourn(time_left))) => {
+            let path = save(Some("adjourned"), time_left)?;
+            let option = if json_file.is_some() {
+                "load"
+            } else {
+                "resume"
+            };
+            if pipe {
+                log::result(game.board(), &game.status(), Some("adjournment"));
+            }
+            println!(
+                "Continue the game with the same options and `--{option} {}`.",
+                path.display()
+            );
+            return Ok(GameResult {
+                status: game.status(),
+                termination: Some("adjourned"),
+            });
+        }
+        Some((_, Request::Quit)) => {
+            if json_file.is_some() || input::confirm("Save the game before quitting?") {
+                save(Some("abandoned"), None)?;
+            }
+            if pipe {
+                log::result(game.board(), &game.status(), Some("abandonment"));
+            }
+            return Ok(GameResult {
+                status: game.status(),
+                termination: Some("abandoned"),
+            });
+        }
+        Some((color, Request::Forfeit)) => Some(color),
+        Some((_, Request::Undo)) => unreachable!("takebacks don't end the game"),
+        Some((_, Request::Reload)) => unreachable!("reloading the settings doesn't end the game"),
+        Some((_, Request::ForfeitTurn)) => unreachable!("a forfeited turn doesn't end the game"),
+        None => resigned.or(invalid_move.as_ref().map(|(color, _)| *color)),
+    };
 
     // board.sort();
 
@@ -87,20 +538,83 @@ pub fn run(opponent: &Opponent, matches: &ArgMatches) {
     //     },
     // );
 
-    animate_results(board.clone(), animation_speed, &Default::default());
+    if !pipe {
+        animate_results(
+            game.board().clone(),
+            settings.animation_speed,
+            &DisplayOptions {
+                theme: settings.theme,
+                ..Default::default()
+            },
+        );
+
+        println!(
+            "{}: {} pieces",
+            player_white.color(),
+            game.board().count_pieces(Color::White)
+        );
+        println!(
+            "{}: {} pieces",
+            player_black.color(),
+            game.board().count_pieces(Color::Black)
+        );
+    }
+
+    for player in [&player_white, &player_black] {
+        if let Some(statistics) = player.statistics() {
+            println!("{} {}: {statistics}", player.color(), player.name());
+        }
+    }
+
+    let termination = match request {
+        Some((_, Request::Forfeit)) => Some("time forfeit"),
+        _ if resigned.is_some() => Some("resignation"),
+        _ => invalid_move.is_some().then_some("invalid move"),
+    };
+    // A resumed game was saved before, so it is kept up to date.
+    if matches.contains_id("save") || resumed.is_some() {
+        save(termination, None)?;
+    }
+
+    let status = match loser {
+        Some(color) => GameStatus::Win(color.other()),
+        None => game.status(),
+    };
+    let webhook = matches
+        .get_one::<String>("webhook")
+        .cloned()
+        .or_else(|| env::var("REVERSI_WEBHOOK").ok())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = webhook {
+        record.result = Game::result_of(game.board());
+        record.termination = termination.map(str::to_owned);
+        let payload = webhook::payload(&record, &game, &status);
+        // The game is over either way, so a failed request only gets a warning.
+        if let Err(error) = webhook::send(&url, &payload) {
+            eprintln!("{error}");
+        }
+    }
 
-    println!(
-        "{}: {} pieces",
-        player_white.color(),
-        board.count_pieces(Color::White)
-    );
-    println!(
-        "{}: {} pieces",
-        player_black.color(),
-        board.count_pieces(Color::Black)
-    );
+    if pipe {
+        log::result(game.board(), &status, termination);
+        return Ok(GameResult {
+            status,
+            termination,
+        });
+    }
+
+    match (request, resigned, &invalid_move) {
+        (Some((color, Request::Forfeit)), _, _) => {
+            println!("\n{color} ran out of time and forfeits the game.")
+        }
+        (_, Some(color), _) => println!("\n{color} resigns."),
+        (_, _, Some((color, error))) => {
+            println!("\n{color} chose an invalid move ({error}) and forfeits the game.")
+        }
+        _ => {}
+    }
 
-    match board.status() {
+    match status {
         GameStatus::Win(Color::White) => {
             println!("\n{}, {}", player_white.name(), "you won!".bold().green());
         }
@@ -110,4 +624,29 @@ pub fn run(opponent: &Opponent, matches: &ArgMatches) {
         GameStatus::Draw => println!("{}", "Draw!".yellow()),
         _ => unreachable!(),
     }
+
+    Ok(GameResult {
+        status,
+        termination,
+    })
+}
+
+/// Take back the moves up to and including the last one of `color`, so it's their turn again.
+/// Returns what was taken back to show under the board.
+fn take_back(game: &mut rules::Game, color: Color) -> String {
+    let mut taken_back = game.clone();
+    let mut moves = Vec::new();
+    while let Some(played) = taken_back.undo() {
+        moves.push(played);
+        if taken_back.current_player() == color {
+            *game = taken_back;
+            let moves: Vec<String> = moves
+                .iter()
+                .rev()
+                .map(|played| played.map_or_else(|| "pass".to_owned(), |field| field.to_string()))
+                .collect();
+            return format!("{color} takes back {}", moves.join(" "));
+        }
+    }
+    format!("{color} has no move to take back")
 }
@@ -0,0 +1,237 @@
+//! A read-only view of the games for the web: the moves as Server-Sent Events, and a page
+//! showing them.
+//!
+//! `GET /events` streams the positions as JSON. A checkpoint, an event without a name, has the
+//! whole position, see `rpc::state`, with the names of the players. It is sent first, at the
+//! start of each game, every `CHECKPOINT_PLIES` plies and at the end. In between, a `move`
+//! event has only the `ply` number, the `move` (`null` for a pass) and the `hash` of the
+//! position after it, see `Position::position_hash`, to check that the spectator is still in
+//! sync, also about whose turn it is after a pass.
+//! Every other path gets the page.
+
+use crate::rpc::{self, json::Json};
+use reversi_game::{
+    reversi::{GameStatus, Notation},
+    rules::Game,
+    Field,
+};
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+/// A page that draws the board of the events.
+const PAGE: &str = r##"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Reversi</title></head>
+<body style="font-family: sans-serif">
+<h1 id="players">Waiting for a game...</h1>
+<pre id="board" style="font-size: 2em; line-height: 1.2"></pre>
+<p id="status"></p>
+<script>
+const glyphs = { "X": "⚫", "O": "⚪", ".": "🟩" };
+// The keys of the position hash: SplitMix64 seeded with 0, White's and Black's for each field,
+// and the last one for Black to move.
+const keys = [];
+for (let state = 0n, mask = (1n << 64n) - 1n; keys.length < 129; ) {
+  state = (state + 0x9e3779b97f4a7c15n) & mask;
+  let key = ((state ^ (state >> 30n)) * 0xbf58476d1ce4e5b9n) & mask;
+  key = ((key ^ (key >> 27n)) * 0x94d049bb133111ebn) & mask;
+  keys.push(key ^ (key >> 31n));
+}
+const hash = (state) => {
+  let hash = state.to_move === "black" ? keys[128] : 0n;
+  state.board.forEach((row, y) => [...row].forEach((c, x) => {
+    if (c !== ".") hash ^= keys[2 * (8 * y + x) + (c === "O" ? 0 : 1)];
+  }));
+  return hash.toString(16).padStart(16, "0");
+};
+
+// Play a move like `d3`, or pass with `null`, for the side to move.
+const play = (state, move) => {
+  const [own, other] = state.to_move === "white" ? ["O", "X"] : ["X", "O"];
+  const board = state.board.map((row) => [...row]);
+  if (move !== null) {
+    const x = move.charCodeAt(0) - 97, y = 8 - Number(move.slice(1));
+    board[y][x] = own;
+    for (const [dx, dy] of [[1, 0], [-1, 0], [0, 1], [0, -1], [1, 1], [1, -1], [-1, 1], [-1, -1]]) {
+      const line = [];
+      let [cx, cy] = [x + dx, y + dy];
+      for (; board[cy]?.[cx] === other; cx += dx, cy += dy) line.push([cx, cy]);
+      if (board[cy]?.[cx] === own) line.forEach(([lx, ly]) => board[ly][lx] = own);
+    }
+  }
+  const count = (c) => board.flat().filter((field) => field === c).length;
+  return {
+    ...state,
+    board: board.map((row) => row.join("")),
+    to_move: state.to_move === "white" ? "black" : "white",
+    discs: { white: count("O"), black: count("X") },
+    moves: [...state.moves, move],
+  };
+};
+
+let game;
+const show = () => {
+  const state = game.state;
+  document.getElementById("players").textContent = `⚪ ${game.white} vs ⚫ ${game.black}`;
+  document.getElementById("board").textContent =
+    state.board.map((row) => [...row].map((c) => glyphs[c]).join("")).join("\n");
+  document.getElementById("status").textContent =
+    `⚪ ${state.discs.white} ⚫ ${state.discs.black}, ` +
+    (state.status === "in_progress" ? `${state.to_move} to move` : state.status.replace("_", " "));
+};
+const connect = () => {
+  const events = new EventSource("/events");
+  events.onmessage = (event) => {
+    game = JSON.parse(event.data);
+    show();
+  };
+  events.addEventListener("move", (event) => {
+    const next = JSON.parse(event.data);
+    const state = game && game.state.moves.length + 1 === next.ply && play(game.state, next.move);
+    if (!state || hash(state) !== next.hash) {
+      // Out of sync: reconnect to get a checkpoint.
+      events.close();
+      connect();
+      return;
+    }
+    game.state = state;
+    show();
+  });
+};
+connect();
+</script>
+</body>
+</html>
+"##;
+
+/// The plies between two checkpoints with the whole position, see the module documentation.
+const CHECKPOINT_PLIES: usize = 10;
+
+/// How long a write to a spectator may block before the spectator is dropped, so one that
+/// stopped reading doesn't hold up the game.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The names of White and Black and the moves of a game.
+type Sent = (String, String, Vec<Option<Field>>);
+
+/// The spectators and the last position sent, so new spectators see the board at once.
+#[derive(Default)]
+pub struct Broadcast {
+    spectators: Mutex<Vec<TcpStream>>,
+    /// The last position as a checkpoint.
+    last: Mutex<Option<String>>,
+    /// The players and the moves of the last position sent, to send only the next move.
+    sent: Mutex<Option<Sent>>,
+}
+
+static BROADCAST: OnceLock<Broadcast> = OnceLock::new();
+
+/// Listen for spectators on `address`, e.g. `127.0.0.1:8080`.
+///
+/// The broadcast lasts as long as the program, so later games are shown on the same address.
+pub fn start(address: &str) -> io::Result<&'static Broadcast> {
+    if let Some(broadcast) = BROADCAST.get() {
+        return Ok(broadcast);
+    }
+    let listener = TcpListener::bind(address)?;
+    let broadcast = BROADCAST.get_or_init(Broadcast::default);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A slow request must not keep the others waiting, and failed ones only concern
+            // that spectator.
+            thread::spawn(move || {
+                let _ = broadcast.answer(stream);
+            });
+        }
+    });
+    Ok(broadcast)
+}
+
+impl Broadcast {
+    /// Answer a request, keeping the stream of `/events` to send positions to.
+    fn answer(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        // Skip the headers.
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+
+        let path = request.split_whitespace().nth(1).unwrap_or("/");
+        if path != "/events" {
+            return write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{PAGE}",
+                PAGE.len()
+            );
+        }
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nAccess-Control-Allow-Origin: *\r\n\r\n"
+        )?;
+        if let Some(last) = &*self.last.lock().unwrap() {
+            write!(stream, "data: {last}\n\n")?;
+        }
+        self.spectators.lock().unwrap().push(stream);
+        Ok(())
+    }
+
+    /// Send the position of `game` between `white` and `black` to all spectators, dropping
+    /// those that left: only the last move if they saw the one before, otherwise a checkpoint.
+    pub fn send(&self, white: &str, black: &str, game: &Game) {
+        let checkpoint = Json::object([
+            ("white", white.into()),
+            ("black", black.into()),
+            ("state", rpc::state(game)),
+        ])
+        .to_string();
+
+        let history = game.history();
+        let mut sent = self.sent.lock().unwrap();
+        let next_move = matches!(
+            &*sent,
+            Some((sent_white, sent_black, moves))
+                if sent_white == white
+                    && sent_black == black
+                    && history.len() == moves.len() + 1
+                    && history.starts_with(moves)
+        );
+        let event = match history.last() {
+            Some(played)
+                if next_move
+                    && !history.len().is_multiple_of(CHECKPOINT_PLIES)
+                    && game.status() == GameStatus::InProgress =>
+            {
+                let played = played.map(|field| Notation::Standard.format(field));
+                let data = Json::object([
+                    ("ply", history.len().into()),
+                    ("move", played.into()),
+                    (
+                        "hash",
+                        format!("{:016x}", game.position().position_hash()).into(),
+                    ),
+                ]);
+                format!("event: move\ndata: {data}\n\n")
+            }
+            _ => format!("data: {checkpoint}\n\n"),
+        };
+        *sent = Some((white.to_owned(), black.to_owned(), history.to_vec()));
+
+        self.spectators
+            .lock()
+            .unwrap()
+            .retain_mut(|stream| stream.write_all(event.as_bytes()).is_ok());
+        *self.last.lock().unwrap() = Some(checkpoint);
+    }
+}
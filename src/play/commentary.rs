@@ -0,0 +1,93 @@
+//! Short remarks on the moves of a watched game, computed while the move is animated.
+
+use reversi_game::{
+    engine::{from_white_view, to_white_view, Engine, Search, SearchLimits},
+    reversi::*,
+};
+
+use std::thread::{self, JoinHandle};
+
+/// The depth of the search behind the remarks, kept shallow so it is done before the next move.
+const DEPTH: u8 = 3;
+/// Evaluations beyond this are clamped, so a won position doesn't make the swing meaningless.
+const MAX_EVAL: i32 = 64;
+/// Mobility changes smaller than this aren't worth a remark.
+const MOBILITY_CHANGE: isize = 3;
+
+const CORNERS: [Field; 4] = [Field(0, 0), Field(7, 0), Field(0, 7), Field(7, 7)];
+
+/// The evaluation of a position with `color` to move, from `perspective`'s point of view.
+fn evaluate(engine: &Engine, board: &Board, depth: u8, color: Color, perspective: Color) -> i32 {
+    engine.clear_hash();
+    let evaluation = engine
+        .best_move(board, color, SearchLimits::depth(depth))
+        .1
+        .clamp(-MAX_EVAL, MAX_EVAL);
+    from_white_view(to_white_view(evaluation, color), perspective)
+}
+
+/// The empty corner next to an X-square (the field diagonally adjacent to a corner).
+fn x_square_corner(board: &Board, field: Field) -> Option<Field> {
+    CORNERS.into_iter().find(|corner| {
+        corner.0.abs_diff(field.0) == 1
+            && corner.1.abs_diff(field.1) == 1
+            && board[*corner].is_none()
+    })
+}
+
+/// Describe the move `played` by `color` from `before`, e.g.
+/// `⚫ c4 grabs mobility (⚪ 7 → 3 moves), eval swings +4`.
+pub fn comment(before: &Board, color: Color, played: Option<Field>) -> String {
+    let Some(field) = played else {
+        return format!("{color} has to pass");
+    };
+
+    let mut after = before.clone();
+    let flips = after.add_piece(field, color).map_or(0, |flips| flips.len());
+
+    let engine = Engine::with_hash_size(1);
+    // Search the position after the move one ply less deep, so both searches look
+    // equally far ahead and the best move doesn't change the evaluation.
+    let swing = evaluate(&engine, &after, DEPTH - 1, color.other(), color)
+        - evaluate(&engine, before, DEPTH, color, color);
+
+    let mut remarks = Vec::new();
+    if CORNERS.contains(&field) {
+        remarks.push("takes the corner".to_owned());
+    } else if let Some(corner) = x_square_corner(before, field) {
+        remarks.push(format!("risks the corner {corner}"));
+    }
+
+    let opponent = color.other();
+    let mobility_change = after.mobility(opponent) as isize - before.mobility(opponent) as isize;
+    if mobility_change <= -MOBILITY_CHANGE {
+        remarks.push(format!(
+            "grabs mobility ({opponent} {} → {} moves)",
+            before.mobility(opponent),
+            after.mobility(opponent)
+        ));
+    } else if mobility_change >= MOBILITY_CHANGE {
+        remarks.push(format!(
+            "opens up the board for {opponent} ({} → {} moves)",
+            before.mobility(opponent),
+            after.mobility(opponent)
+        ));
+    }
+
+    if flips >= 6 {
+        remarks.push(format!("flips {flips} discs"));
+    }
+
+    remarks.push(match swing {
+        0 => "eval unchanged".to_owned(),
+        swing => format!("eval swings {swing:+}"),
+    });
+
+    format!("{color} {field} {}", remarks.join(", "))
+}
+
+/// Comment on a move on a background thread.
+pub fn spawn(before: &Board, color: Color, played: Option<Field>) -> JoinHandle<String> {
+    let before = before.clone();
+    thread::spawn(move || comment(&before, color, played))
+}
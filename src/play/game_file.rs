@@ -0,0 +1,155 @@
+//! Games kept in JSON files with `--save FILE` and `--load FILE`, to continue them later or
+//! read them with other programs:
+//!
+//! ```json
+//! {"version":1,"white":"Player 1","black":"Player 2","to_move":"white",
+//!  "board":"......../......../......../...OX.../...XO.../..XO..../......../........",
+//!  "start":null,"moves":["d3","c3"],"headers":{"Date":"2026.10.16","Result":"*"},"comments":{}}
+//! ```
+//!
+//! `board` and `to_move` are the position after the moves, and `start` the position they were
+//! played from like in a `Position` header, or `null` for the initial position. A pass is
+//! written as `null`. The other headers and the comments are those of an archive.
+
+use crate::rpc::json::Json;
+use reversi_game::{archive::Game, reversi::*, rules};
+
+use std::{fs, io, path::Path};
+
+/// The version of the file format written by `write`.
+pub const VERSION: u64 = 1;
+
+/// The name of `color` in the file.
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+/// The moves of `saved` played from its start.
+fn replay(saved: &Game) -> Result<rules::Game, String> {
+    let mut game = rules::Game::from_position(saved.start()?);
+    for (index, &played) in saved.moves.iter().enumerate() {
+        game.replay_move(played)
+            .map_err(|error| format!("Move {}: {error}", index + 1))?;
+    }
+    Ok(game)
+}
+
+/// Write `saved` to the JSON file `path`.
+pub fn write(path: &Path, saved: &Game) -> io::Result<()> {
+    let game = replay(saved).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    let text = |name| Json::from(saved.header(name).unwrap_or("?"));
+    let json = Json::object([
+        ("version", VERSION.into()),
+        ("white", text("White")),
+        ("black", text("Black")),
+        ("to_move", color_name(game.current_player()).into()),
+        ("board", game.board().to_ascii().replace('\n', "/").into()),
+        ("start", saved.header("Position").into()),
+        (
+            "moves",
+            Json::Array(
+                saved
+                    .moves
+                    .iter()
+                    .map(|played| played.map(|field| Notation::Standard.format(field)).into())
+                    .collect(),
+            ),
+        ),
+        (
+            "headers",
+            Json::Object(
+                saved
+                    .headers
+                    .iter()
+                    .filter(|(name, _)| !["White", "Black", "Position"].contains(&name.as_str()))
+                    .map(|(name, value)| (name.clone(), value.as_str().into()))
+                    .collect(),
+            ),
+        ),
+        (
+            "comments",
+            Json::Object(
+                saved
+                    .comments
+                    .iter()
+                    .map(|(number, comment)| (number.to_string(), comment.as_str().into()))
+                    .collect(),
+            ),
+        ),
+    ]);
+    fs::write(path, format!("{json}\n"))
+}
+
+/// The game in progress of the JSON file `path`, checking that the moves lead to its board.
+pub fn load(path: &Path) -> Result<Game, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let json: Json = contents
+        .parse()
+        .map_err(|error| format!("{}: {error}", path.display()))?;
+    from_json(&json).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+fn from_json(json: &Json) -> Result<Game, String> {
+    match json.get("version").and_then(Json::as_u64) {
+        Some(VERSION) => {}
+        Some(version) => return Err(format!("Unknown version {version}")),
+        None => return Err("Not a saved game".to_owned()),
+    }
+    let string = |json: Option<&Json>, name: &str| match json {
+        Some(Json::String(value)) => Ok(value.clone()),
+        _ => Err(format!("`{name}` must be a string")),
+    };
+    let object = |name: &str| match json.get(name) {
+        Some(Json::Object(entries)) => Ok(entries.clone()),
+        None => Ok(Vec::new()),
+        _ => Err(format!("`{name}` must be an object")),
+    };
+
+    let mut saved = Game::new();
+    saved.set_header("White", string(json.get("white"), "white")?);
+    saved.set_header("Black", string(json.get("black"), "black")?);
+    for (name, value) in object("headers")? {
+        saved.set_header(&name, string(Some(&value), &name)?);
+    }
+    match json.get("start") {
+        None | Some(Json::Null) => {}
+        start => saved.set_header("Position", string(start, "start")?),
+    }
+    let Some(Json::Array(moves)) = json.get("moves") else {
+        return Err("`moves` must be a list".to_owned());
+    };
+    for (index, played) in moves.iter().enumerate() {
+        saved.moves.push(match played {
+            Json::Null => None,
+            Json::String(field) => Some(
+                Notation::Standard
+                    .parse(field)
+                    .map_err(|error| format!("Move {} ({field}): {error}", index + 1))?,
+            ),
+            _ => return Err(format!("Move {} must be a field or null", index + 1)),
+        });
+    }
+    for (number, comment) in object("comments")? {
+        let number = number
+            .parse()
+            .map_err(|_| format!("`{number}` is not the number of a move"))?;
+        saved.set_comment(number, &string(Some(&comment), "comments")?);
+    }
+
+    let game = replay(&saved)?;
+    let board = string(json.get("board"), "board")?;
+    let to_move = string(json.get("to_move"), "to_move")?;
+    if board != game.board().to_ascii().replace('\n', "/")
+        || to_move != color_name(game.current_player())
+    {
+        return Err("The board and the side to move don't match the moves".to_owned());
+    }
+    if game.status() != GameStatus::InProgress {
+        return Err("The game is over".to_owned());
+    }
+    Ok(saved)
+}
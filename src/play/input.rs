@@ -0,0 +1,165 @@
+//! Reading lines from the standard input, optionally with a timeout.
+//!
+//! Lines with a timeout are read by a single background thread, so a prompt that timed out
+//! doesn't swallow the line typed for the next one. Once it runs, it reads all input.
+//!
+//! With the `line-editing` feature, `edit_line` lets the user edit the line on a terminal with
+//! rustyline (see `editor`): the arrow keys move the cursor and recall earlier lines, and tab
+//! completes words. Lines with a timeout are read without it.
+
+#[cfg(feature = "line-editing")]
+mod editor;
+
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Mutex, MutexGuard, OnceLock,
+    },
+    thread,
+    time::Instant,
+};
+
+#[cfg(feature = "line-editing")]
+use std::io::IsTerminal;
+
+/// Puts the terminal into raw mode for as long as it lives.
+pub struct RawMode {
+    previous_settings: String,
+}
+
+impl RawMode {
+    /// Switch to raw mode with the given `stty` settings,
+    /// or return `None` if the standard input is not a terminal.
+    pub fn enable(settings: &[&str]) -> Option<Self> {
+        let stty = |args: &[&str]| {
+            Command::new("stty")
+                .args(args)
+                .stdin(Stdio::inherit())
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+        };
+
+        let previous_settings = String::from_utf8(stty(&["-g"])?.stdout).ok()?;
+        stty(settings)?;
+
+        Some(RawMode {
+            previous_settings: previous_settings.trim().to_owned(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty")
+            .arg(&self.previous_settings)
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+/// The bytes of the standard input, once the background thread reads it, see `bytes`.
+static BYTES: OnceLock<Mutex<Receiver<u8>>> = OnceLock::new();
+
+/// The bytes of the standard input, as read by the single background thread.
+pub(super) fn bytes() -> MutexGuard<'static, Receiver<u8>> {
+    BYTES
+        .get_or_init(|| {
+            let (sender, bytes) = mpsc::channel();
+            thread::spawn(move || {
+                let mut buffer = [0; 256];
+                while let Ok(read @ 1..) = io::stdin().lock().read(&mut buffer) {
+                    if buffer[..read]
+                        .iter()
+                        .any(|&byte| sender.send(byte).is_err())
+                    {
+                        break;
+                    }
+                }
+            });
+            Mutex::new(bytes)
+        })
+        .lock()
+        .unwrap()
+}
+
+/// The next byte of the input, waiting until `deadline` at the latest.
+pub(super) fn next_byte(
+    bytes: &Receiver<u8>,
+    deadline: Option<Instant>,
+) -> Result<u8, RecvTimeoutError> {
+    match deadline {
+        Some(deadline) => bytes.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        None => bytes.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
+
+/// Read a line up to `deadline`, as typed in the terminal's own line editing.
+///
+/// Fails with `Timeout` if the line wasn't entered in time,
+/// and with `Disconnected` if the input ended before anything was entered.
+fn read_line_until(deadline: Option<Instant>) -> Result<String, RecvTimeoutError> {
+    let bytes = bytes();
+    let mut line = Vec::new();
+    loop {
+        match next_byte(&bytes, deadline) {
+            Ok(b'\n') => break,
+            Ok(byte) => line.push(byte),
+            Err(RecvTimeoutError::Disconnected) if !line.is_empty() => break,
+            Err(error) => return Err(error),
+        }
+    }
+    let line = String::from_utf8_lossy(&line);
+    Ok(line.strip_suffix('\r').unwrap_or(&line).to_owned())
+}
+
+/// Read a line, without the trailing newline. Returns an empty line at the end of the input.
+pub fn read_line() -> String {
+    edit_line("", &[], None).unwrap_or_default()
+}
+
+/// Show `prompt` and read a line with line editing, history and tab completion of `completions`,
+/// which are whole lines, e.g. `play d3`.
+///
+/// Fails with `Timeout` if the line wasn't entered before `deadline`, and with `Disconnected`
+/// at the end of the input or when Ctrl-D is pressed on an empty line.
+/// Without a terminal or the `line-editing` feature this reads a plain line.
+pub fn edit_line(
+    prompt: &str,
+    completions: &[String],
+    deadline: Option<Instant>,
+) -> Result<String, RecvTimeoutError> {
+    edit_line_with_tab(prompt, completions, deadline, None)
+}
+
+/// Like `edit_line`, but <Tab> on an empty line returns `empty_tab` at once instead of listing
+/// all completions, e.g. to switch to the next board.
+#[cfg_attr(not(feature = "line-editing"), allow(unused_variables))]
+pub fn edit_line_with_tab(
+    prompt: &str,
+    completions: &[String],
+    deadline: Option<Instant>,
+    empty_tab: Option<&str>,
+) -> Result<String, RecvTimeoutError> {
+    // Rustyline can't stop waiting for a line at a deadline, and it can't share the input
+    // with the background thread once that reads it.
+    #[cfg(feature = "line-editing")]
+    if deadline.is_none() && BYTES.get().is_none() && io::stdin().is_terminal() {
+        if let Some(line) = editor::edit_line(prompt, completions, empty_tab) {
+            return line;
+        }
+    }
+
+    print!("{prompt}");
+    io::stdout().flush().unwrap();
+    read_line_until(deadline)
+}
+
+/// Ask a yes-or-no `question`, defaulting to no.
+pub fn confirm(question: &str) -> bool {
+    let answers = ["yes", "no"].map(str::to_owned);
+    edit_line(&format!("{question} [y/N] "), &answers, None)
+        .is_ok_and(|answer| matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
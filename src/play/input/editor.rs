@@ -0,0 +1,125 @@
+//! Line editing on a terminal with rustyline, see `input::edit_line`.
+
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Cmd, CompletionType, ConditionalEventHandler,
+    Config, Context, Editor, Event, EventContext, EventHandler, Helper, KeyCode, KeyEvent,
+    Modifiers, RepeatCount,
+};
+
+use std::{
+    process,
+    sync::{mpsc::RecvTimeoutError, Arc, Mutex, MutexGuard},
+};
+
+/// The lines entered so far, oldest first. Each prompt has an editor of its own, which starts
+/// with them.
+fn history() -> MutexGuard<'static, Vec<String>> {
+    static HISTORY: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    HISTORY.lock().unwrap()
+}
+
+/// Completes whole lines like `play d3` one word at a time.
+struct Completions(Vec<String>);
+
+impl Completer for Completions {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let prefix = line[..pos].to_ascii_lowercase();
+        let word_start = prefix.rfind(' ').map_or(0, |space| space + 1);
+        let matches = self
+            .0
+            .iter()
+            .filter(|completion| completion.starts_with(&prefix))
+            .map(|completion| completion[word_start..].to_owned())
+            .collect();
+        Ok((word_start, matches))
+    }
+}
+
+impl Hinter for Completions {
+    type Hint = String;
+}
+
+impl Highlighter for Completions {}
+
+impl Validator for Completions {}
+
+impl Helper for Completions {}
+
+/// A key that enters `line` at once, e.g. `pause` for Ctrl-Z, and only on an empty line if
+/// `only_empty` is set.
+struct Shortcut {
+    line: String,
+    only_empty: bool,
+    /// Where the line is left for `edit_line`, which only gets the edited one from rustyline.
+    entered: Arc<Mutex<Option<String>>>,
+}
+
+impl ConditionalEventHandler for Shortcut {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, context: &EventContext) -> Option<Cmd> {
+        if self.only_empty && !context.line().is_empty() {
+            return None;
+        }
+        *self.entered.lock().unwrap() = Some(self.line.clone());
+        Some(Cmd::AcceptLine)
+    }
+}
+
+/// Show `prompt` and read a line with line editing, history and tab completion of
+/// `completions`, where <Tab> on an empty line enters `empty_tab`, see
+/// `input::edit_line_with_tab`.
+///
+/// Returns `None` if the terminal can't be edited on.
+pub fn edit_line(
+    prompt: &str,
+    completions: &[String],
+    empty_tab: Option<&str>,
+) -> Option<Result<String, RecvTimeoutError>> {
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor = Editor::<Completions, DefaultHistory>::with_config(config).ok()?;
+    editor.set_helper(Some(Completions(completions.to_vec())));
+    for line in history().iter() {
+        editor.add_history_entry(line.as_str()).ok()?;
+    }
+
+    let entered = Arc::new(Mutex::new(None));
+    let shortcut = |line: &str, only_empty| {
+        EventHandler::Conditional(Box::new(Shortcut {
+            line: line.to_owned(),
+            only_empty,
+            entered: Arc::clone(&entered),
+        }))
+    };
+    // Rustyline would stop the program, so Ctrl-Z pauses the game instead.
+    editor.bind_sequence(KeyEvent::ctrl('Z'), shortcut("pause", false));
+    if let Some(line) = empty_tab {
+        editor.bind_sequence(
+            KeyEvent(KeyCode::Tab, Modifiers::NONE),
+            shortcut(line, true),
+        );
+    }
+
+    let line = match editor.readline(prompt) {
+        Ok(line) => line,
+        Err(ReadlineError::Interrupted) => process::exit(130),
+        Err(_) => return Some(Err(RecvTimeoutError::Disconnected)),
+    };
+    if let Some(line) = entered.lock().unwrap().take() {
+        return Some(Ok(line));
+    }
+
+    let mut history = history();
+    if !line.trim().is_empty() && history.last() != Some(&line) {
+        history.push(line.clone());
+    }
+    Some(Ok(line))
+}
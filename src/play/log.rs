@@ -0,0 +1,79 @@
+//! A plain record of a game, one line per move, for when the output isn't a terminal, e.g.
+//! `reversi --watch | tee game.log`. The lines have no colors, emoji or cursor movements.
+
+use crate::games;
+use reversi_game::reversi::*;
+
+use std::{
+    io::{self, IsTerminal},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Whether the game is logged instead of drawn, as the output goes to a file or a pipe.
+pub fn enabled() -> bool {
+    !io::stdout().is_terminal()
+}
+
+/// The current time in UTC, like `2026-10-16 14:03:27`.
+fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs() % 86400);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        games::today().replace('.', "-"),
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// The name of `color`, as the emoji of its `Display` depend on the font.
+fn name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
+
+/// Log the players at the start of the game.
+pub fn start(white: &str, black: &str) {
+    println!("{} start White: {white}, Black: {black}", timestamp());
+}
+
+/// Log the `ply`th move, `played` by `color`, with the discs after it, the evaluation for
+/// White and the time it took.
+pub fn played(
+    ply: usize,
+    color: Color,
+    played: Option<Field>,
+    board: &Board,
+    evaluation: i32,
+    time: Duration,
+) {
+    println!(
+        "{} {ply:>3} {} {:<4} discs {:>2}-{:<2} eval {evaluation:+} time {:.1}s",
+        timestamp(),
+        name(color),
+        played.map_or("pass".to_owned(), |field| Notation::Standard.format(field)),
+        board.count_pieces(Color::White),
+        board.count_pieces(Color::Black),
+        time.as_secs_f64()
+    );
+}
+
+/// Log the end of the game, with why it ended early if it did.
+pub fn result(board: &Board, status: &GameStatus, termination: Option<&str>) {
+    let outcome = match status {
+        GameStatus::Win(color) => format!("{} wins", name(*color)),
+        GameStatus::Draw => "draw".to_owned(),
+        GameStatus::InProgress => "unfinished".to_owned(),
+    };
+    println!(
+        "{} result {}-{} {outcome}{}",
+        timestamp(),
+        board.count_pieces(Color::White),
+        board.count_pieces(Color::Black),
+        termination.map_or(String::new(), |termination| format!(" by {termination}"))
+    );
+}
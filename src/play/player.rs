@@ -1,14 +1,63 @@
+pub mod fallback;
+pub mod fn_player;
 pub mod human_player;
 pub mod minimax_bot;
 
-pub use human_player::HumanPlayer;
-pub use minimax_bot::MinimaxBot;
+pub use fallback::Fallback;
+pub use fn_player::FnPlayer;
+pub use human_player::{HumanPlayer, TimeoutAction};
+pub use minimax_bot::{Adaptive, MinimaxBot};
 
-use reversi_game::reversi::*;
+use reversi_game::{engine::SearchStatistics, reversi::*};
+
+use std::time::Duration;
+
+/// Something a player asked for instead of a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    /// Lose the game because the time ran out.
+    Forfeit,
+    /// Lose the turn because the time ran out, see `rules::Game::forfeit_turn`.
+    ForfeitTurn,
+    /// Abandon the game and start a new one.
+    NewGame,
+    /// Leave the game.
+    Quit,
+    /// Save the game to continue it later, with the time that was left for the move.
+    Adjourn(Option<Duration>),
+    /// Take back the player's last move and the moves after it.
+    Undo,
+    /// Read the settings file again and redraw the board, see `settings`.
+    Reload,
+}
 
 pub trait Player {
-    fn turn(&self, board: &Board) -> Option<Field>;
+    /// Choose a move, redrawing the board with `options` if needed.
+    ///
+    /// After a `request` the move is ignored.
+    fn turn(&self, board: &Board, options: &DisplayOptions) -> Move;
     fn color(&self) -> Color;
     fn name(&self) -> String;
     fn redraw_options(&self) -> DisplayOptions;
+
+    /// Statistics about the searches of a player backed by an engine.
+    fn statistics(&self) -> Option<SearchStatistics> {
+        None
+    }
+
+    /// Whether the player is a person, who is asked again after choosing an invalid move.
+    fn is_human(&self) -> bool {
+        false
+    }
+
+    /// A remark on the last move, e.g. why it isn't the best one, kept as its comment in the
+    /// saved game.
+    fn note(&self) -> Option<String> {
+        None
+    }
+
+    /// What the player asked for instead of a move during their last turn, if anything.
+    fn request(&self) -> Option<Request> {
+        None
+    }
 }
@@ -0,0 +1,56 @@
+use super::{Player, Request};
+use reversi_game::{engine::SearchStatistics, reversi::*};
+
+/// A player that lets another player move whenever it passes although it could move,
+/// e.g. a human whose time ran out with `TimeoutAction::Pass`.
+pub struct Fallback {
+    primary: Box<dyn Player>,
+    fallback: Box<dyn Player>,
+}
+
+impl Fallback {
+    /// Combine two players of the same color.
+    pub fn new(primary: Box<dyn Player>, fallback: Box<dyn Player>) -> Self {
+        debug_assert_eq!(primary.color(), fallback.color());
+        Fallback { primary, fallback }
+    }
+}
+
+impl Player for Fallback {
+    fn name(&self) -> String {
+        self.primary.name()
+    }
+
+    fn color(&self) -> Color {
+        self.primary.color()
+    }
+
+    fn turn(&self, board: &Board, options: &DisplayOptions) -> Move {
+        match self.primary.turn(board, options) {
+            Move::Pass
+                if self.primary.request().is_none()
+                    && !board.valid_moves(self.color()).is_empty() =>
+            {
+                println!("{} moves instead.", self.fallback.name());
+                self.fallback.turn(board, &self.fallback.redraw_options())
+            }
+            played => played,
+        }
+    }
+
+    fn redraw_options(&self) -> DisplayOptions {
+        self.primary.redraw_options()
+    }
+
+    fn statistics(&self) -> Option<SearchStatistics> {
+        self.fallback.statistics()
+    }
+
+    fn is_human(&self) -> bool {
+        self.primary.is_human()
+    }
+
+    fn request(&self) -> Option<Request> {
+        self.primary.request()
+    }
+}
@@ -0,0 +1,43 @@
+use super::Player;
+use reversi_game::reversi::*;
+
+use reversi_game::board::display::style::Colorize;
+
+/// A player whose moves are chosen by a closure, e.g. for quick experiments.
+///
+/// The closure returns `None` to pass, the player never resigns.
+pub struct FnPlayer<F> {
+    color: Color,
+    name: String,
+    choose: F,
+}
+
+impl<F: Fn(&Board, Color) -> Option<Field>> FnPlayer<F> {
+    pub fn new(color: Color, name: String, choose: F) -> Self {
+        FnPlayer {
+            color,
+            name,
+            choose,
+        }
+    }
+}
+
+impl<F: Fn(&Board, Color) -> Option<Field>> Player for FnPlayer<F> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn color(&self) -> Color {
+        self.color
+    }
+
+    fn turn(&self, board: &Board, options: &DisplayOptions) -> Move {
+        redraw_board(board, options);
+        println!("{} {}\n", self.color, self.name.bold());
+        (self.choose)(board, self.color).into()
+    }
+
+    fn redraw_options(&self) -> DisplayOptions {
+        Default::default()
+    }
+}
@@ -1,18 +1,159 @@
-use super::Player;
+use super::{Player, Request};
+use crate::play::input;
 use reversi_game::reversi::*;
 
-use std::io::{self, Write};
+use std::{
+    cell::Cell,
+    sync::mpsc::RecvTimeoutError,
+    thread,
+    time::{Duration, Instant},
+};
 
-use colored::Colorize;
+use rand::seq::SliceRandom;
+use reversi_game::board::display::{clear_screen, style::Colorize};
+
+/// What happens when a human doesn't enter a move in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutAction {
+    /// Skip the move.
+    Pass,
+    /// Play a random valid move.
+    Random,
+    /// Lose the game.
+    Forfeit,
+}
 
 pub struct HumanPlayer {
     color: Color,
     name: String,
+    timeout: Option<(Duration, TimeoutAction)>,
+    /// The time for the next move if it isn't the whole timeout, e.g. in a resumed game.
+    time_left: Cell<Option<Duration>>,
+    confirm: bool,
+    request: Cell<Option<Request>>,
 }
 
 impl HumanPlayer {
     pub fn new(color: Color, name: String) -> Self {
-        HumanPlayer { color, name }
+        HumanPlayer {
+            color,
+            name,
+            timeout: None,
+            time_left: Cell::new(None),
+            confirm: true,
+            request: Cell::new(None),
+        }
+    }
+
+    /// Give the player `timeout` to enter each move, after which `action` is taken.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration, action: TimeoutAction) -> Self {
+        self.timeout = Some((timeout, action));
+        self
+    }
+
+    /// Give the player only `time_left` for the next move.
+    #[must_use]
+    pub fn with_time_left(self, time_left: Duration) -> Self {
+        self.time_left.set(Some(time_left));
+        self
+    }
+
+    /// Whether to ask before resigning, abandoning or quitting the game.
+    #[must_use]
+    pub fn with_confirmations(mut self, confirm: bool) -> Self {
+        self.confirm = confirm;
+        self
+    }
+
+    /// Handle `input` if it is a command instead of a field, pausing the clock running until
+    /// `deadline` on `pause`.
+    ///
+    /// Returns `None` if it isn't a command, otherwise the move ending the turn, if any.
+    fn command(
+        &self,
+        input: &str,
+        deadline: &Cell<Option<Instant>>,
+        board: &Board,
+        options: &DisplayOptions,
+    ) -> Option<Option<Move>> {
+        let time_left = || {
+            deadline
+                .get()
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+        };
+        let (request, question) = match input.trim().to_lowercase().as_str() {
+            "resign" => {
+                let resign = !self.confirm || input::confirm("Really resign?");
+                return Some(resign.then_some(Move::Resign));
+            }
+            "new" => (Request::NewGame, "Abandon this game and start a new one?"),
+            "quit" => (Request::Quit, "Really quit?"),
+            "adjourn" => (
+                Request::Adjourn(time_left()),
+                "Save the game and continue it later?",
+            ),
+            "pause" => {
+                self.pause(deadline, board, options);
+                return Some(None);
+            }
+            // The clock goes on where it was after the board is redrawn.
+            "reload" => {
+                self.time_left.set(time_left());
+                self.request.set(Some(Request::Reload));
+                return Some(Some(Move::Pass));
+            }
+            // Nothing is lost, so there is nothing to confirm.
+            "undo" => {
+                self.request.set(Some(Request::Undo));
+                return Some(Some(Move::Pass));
+            }
+            "help" => {
+                println!(
+                    "Enter a field like {}, or one of the commands undo, pause, reload, adjourn, resign, new and quit.",
+                    Field(3, 5)
+                );
+                return Some(None);
+            }
+            _ => return None,
+        };
+
+        if self.confirm && !input::confirm(question) {
+            return Some(None);
+        }
+        self.request.set(Some(request));
+        Some(Some(Move::Pass))
+    }
+
+    /// Hide the board and stop the clock until the player continues.
+    fn pause(&self, deadline: &Cell<Option<Instant>>, board: &Board, options: &DisplayOptions) {
+        let paused = Instant::now();
+        clear_screen();
+        println!("{}", "The game is paused.".bold());
+        let _ = input::edit_line("Press <Enter> to continue.", &[], None);
+        deadline.set(deadline.get().map(|deadline| deadline + paused.elapsed()));
+
+        redraw_board(board, options);
+        println!("{} {}", self.color(), self.name.bold());
+    }
+
+    fn time_up(&self, board: &Board, action: TimeoutAction) -> Move {
+        println!("\n{}", "Time is up!".bold().yellow());
+        match action {
+            TimeoutAction::Pass => {
+                self.request.set(Some(Request::ForfeitTurn));
+                Move::Pass
+            }
+            TimeoutAction::Random => board
+                .valid_moves(self.color())
+                .choose(&mut rand::thread_rng())
+                .copied()
+                .into(),
+            TimeoutAction::Forfeit => {
+                self.request.set(Some(Request::Forfeit));
+                Move::Pass
+            }
+        }
     }
 }
 
@@ -25,28 +166,76 @@ impl Player for HumanPlayer {
         self.color
     }
 
-    fn turn(&self, board: &Board) -> Option<Field> {
-        redraw_board(
-            board,
-            &DisplayOptions {
-                color: Some(self.color),
-                ..Default::default()
-            },
-        );
+    fn turn(&self, board: &Board, options: &DisplayOptions) -> Move {
+        self.request.set(None);
+        redraw_board(board, options);
 
         println!("{} {}", self.color(), self.name.bold());
 
-        if board.valid_moves(self.color()).is_empty() {
-            println!("You have no valid moves. Press <Enter> to pass.");
-            io::stdin().read_line(&mut String::new()).unwrap();
-            None?;
+        let deadline = Cell::new(self.timeout.map(|(timeout, _)| {
+            Instant::now() + self.time_left.take().unwrap_or(timeout).min(timeout)
+        }));
+        let prompt = |text: &str| match deadline.get() {
+            Some(deadline) => format!(
+                "{text} ({}s left): ",
+                deadline
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .ceil()
+            ),
+            None => format!("{text}: "),
+        };
+
+        let commands = [
+            "undo", "pause", "reload", "adjourn", "resign", "new", "quit", "help",
+        ]
+        .map(str::to_owned);
+
+        while board.valid_moves(self.color()).is_empty() {
+            let input = input::edit_line(
+                &prompt("You have no valid moves. Press <Enter> to pass"),
+                &commands,
+                deadline.get(),
+            )
+            .unwrap_or_default();
+            match self.command(&input, &deadline, board, options) {
+                Some(Some(played)) => return played,
+                Some(None) => {}
+                None => return Move::Pass,
+            }
         }
 
+        let completions: Vec<String> = board
+            .valid_moves(self.color())
+            .iter()
+            .map(ToString::to_string)
+            .chain(commands)
+            .collect();
         let field = loop {
-            let mut input = String::new();
-            print!("Enter a field: ");
-            io::stdout().flush().unwrap();
-            io::stdin().read_line(&mut input).unwrap();
+            let input =
+                match input::edit_line(&prompt("Enter a field"), &completions, deadline.get()) {
+                    Ok(input) => input,
+                    Err(RecvTimeoutError::Timeout) => {
+                        return self.time_up(board, self.timeout.unwrap().1);
+                    }
+                    // Nothing will be entered anymore, so just let the time run out.
+                    Err(RecvTimeoutError::Disconnected) => match deadline.get() {
+                        Some(deadline) => {
+                            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                            return self.time_up(board, self.timeout.unwrap().1);
+                        }
+                        None => {
+                            self.request.set(Some(Request::Quit));
+                            return Move::Pass;
+                        }
+                    },
+                };
+
+            match self.command(&input, &deadline, board, options) {
+                Some(Some(played)) => return played,
+                Some(None) => continue,
+                None => {}
+            }
 
             match input.trim().parse() {
                 Ok(field) => match board.move_validity(field, self.color()) {
@@ -63,7 +252,7 @@ impl Player for HumanPlayer {
             };
         };
 
-        Some(field)
+        Move::Place(field)
     }
 
     fn redraw_options(&self) -> DisplayOptions {
@@ -72,4 +261,12 @@ impl Player for HumanPlayer {
             ..Default::default()
         }
     }
+
+    fn is_human(&self) -> bool {
+        true
+    }
+
+    fn request(&self) -> Option<Request> {
+        self.request.get()
+    }
 }
@@ -1,208 +1,275 @@
 use super::Player;
-use reversi_game::reversi::*;
+use crate::play::input;
+use reversi_game::{
+    engine::{Engine, Search, SearchLimits, SearchStatistics, Strength, TimeManager, Weights},
+    reversi::*,
+};
 
 use std::{
+    cell::{Cell, RefCell},
     io::{self, Write},
-    ops::Sub,
+    time::{Duration, Instant},
 };
 
-use colored::Colorize;
+use rand::seq::SliceRandom;
+use reversi_game::board::display::style::Colorize;
+#[cfg(feature = "spinner")]
 use spinners::{Spinner, Spinners};
 
-/// A strategy for the minimax bot.
-#[derive(Debug, Clone, Copy)]
-pub enum MinimaxStrategy {
-    /// Minimize the board evaluation.
-    Minimize,
-    /// Maximize the board evaluation.
-    Maximize,
+/// How many discs worse than the best move a move may be to be picked for variety.
+const VARIETY_MARGIN: i64 = 2;
+
+/// How an adaptive bot gives away its lead, see `MinimaxBot::with_adaptive`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adaptive {
+    /// The lead in discs the bot keeps, playing the best moves below it.
+    pub lead: i32,
+    /// How many discs worse than the best move a move may be.
+    pub max_loss: i32,
 }
 
-impl MinimaxStrategy {
-    /// Get the opposite of this strategy.
-    ///
-    /// # Examples
-    /// ```
-    /// use reversi::player::minimax_bot::MinimaxStrategy;
-    /// let min = MinimaxStrategy::Minimize;
-    /// let max = MinimaxStrategy::Maximize;
-    ///
-    /// assert_eq!(min.opposite(), max);
-    /// assert_eq!(max.opposite(), min);
-    /// ```
-    fn other(&self) -> MinimaxStrategy {
-        match self {
-            MinimaxStrategy::Minimize => MinimaxStrategy::Maximize,
-            MinimaxStrategy::Maximize => MinimaxStrategy::Minimize,
+/// A `MinimaxBot` is a player that plays using the minimax algorithm.
+pub struct MinimaxBot {
+    color: Color,
+    depth: Cell<u8>,
+    /// Shares the bot's thinking time between the moves, if it chooses its depth itself.
+    time_manager: RefCell<Option<TimeManager>>,
+    /// The time for each move, searching one move deeper at a time until it is up.
+    move_time: Option<Duration>,
+    /// The best move and evaluation of each depth completed in the last turn with `move_time`.
+    depths: RefCell<Vec<(Option<Field>, i32)>>,
+    /// The searched positions per second and the effective branching factor of the last search.
+    calibration: Cell<Option<(f64, f64)>>,
+    /// The number of plies from the start in which the bot varies its moves.
+    variety: u8,
+    /// The rating the bot's strength is limited to.
+    elo: Option<u16>,
+    adaptive: Option<Adaptive>,
+    /// Why the bot didn't play the best move in its last turn.
+    note: RefCell<Option<String>>,
+    engine: Engine,
+    confirm_moves: bool,
+    /// Whether the bot plays without drawing the board or reporting on its searches.
+    quiet: bool,
+    statistics: RefCell<SearchStatistics>,
+}
+
+impl MinimaxBot {
+    /// Create a new `MinimaxBot` with the given color and depth.
+    pub fn new(color: Color, depth: u8) -> Self {
+        MinimaxBot {
+            color,
+            depth: Cell::new(depth),
+            time_manager: RefCell::new(None),
+            move_time: None,
+            depths: RefCell::default(),
+            calibration: Cell::new(None),
+            variety: 0,
+            elo: None,
+            adaptive: None,
+            note: RefCell::default(),
+            engine: Engine::new(),
+            confirm_moves: true,
+            quiet: false,
+            statistics: RefCell::default(),
         }
     }
 
-    /// Get the most suboptimal evaluation for this strategy.
-    ///
-    /// # Examples
-    /// ```
-    /// use reversi::player::minimax_bot::MinimaxStrategy;
-    ///
-    /// let min = MinimaxStrategy::Minimize;
-    /// let max = MinimaxStrategy::Maximize;
+    /// Don't wait for <Enter> after each move, e.g. when two bots play each other.
+    #[must_use]
+    pub fn without_confirmation(mut self) -> Self {
+        self.confirm_moves = false;
+        self
+    }
+
+    /// Play without drawing the board, reporting on the searches or waiting for <Enter>,
+    /// e.g. when the game is logged line by line instead.
+    #[must_use]
+    pub fn quiet(mut self) -> Self {
+        self.quiet = true;
+        self.confirm_moves = false;
+        self
+    }
+
+    /// Choose the depth for each move so that the bot thinks about `time` per move on average,
+    /// more on critical moves and less on easy ones, see `TimeManager`.
     ///
-    /// assert_eq!(min.worst_value()), i32::MAX);
-    /// assert_eq!(max.worst_value()), i32::MIN);
-    /// ```
-    fn worst_value(&self) -> i32 {
-        match self {
-            MinimaxStrategy::Minimize => i32::MAX,
-            MinimaxStrategy::Maximize => i32::MIN,
-        }
+    /// The first move is searched with the depth given in `new` to measure the speed of the engine.
+    #[must_use]
+    pub fn with_think_time(self, time: Duration) -> Self {
+        self.time_manager.replace(Some(TimeManager::new(time)));
+        self
     }
-}
 
-/// A color can be turned into a `MinimaxStrategy`.
-impl From<Color> for MinimaxStrategy {
-    fn from(color: Color) -> Self {
-        match color {
-            Color::White => MinimaxStrategy::Maximize,
-            Color::Black => MinimaxStrategy::Minimize,
-        }
+    /// Search depth 1, 2, 3, ... until `time` is up and play the best move of the deepest
+    /// search that was completed, see `Engine::deepen`. The depth given in `new` is ignored.
+    #[must_use]
+    pub fn with_move_time(mut self, time: Duration) -> Self {
+        self.move_time = Some(time);
+        self
     }
-}
 
-impl From<MinimaxStrategy> for Color {
-    fn from(strategy: MinimaxStrategy) -> Self {
-        match strategy {
-            MinimaxStrategy::Minimize => Color::Black,
-            MinimaxStrategy::Maximize => Color::White,
-        }
+    /// Evaluate positions with `weights` instead of the default ones.
+    #[must_use]
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.engine.set_weights(weights);
+        self
     }
-}
 
-/// A `MinimaxBot` is a player that plays using the minimax algorithm.
-pub struct MinimaxBot {
-    color: Color,
-    depth: u8,
-}
+    /// Play roughly as strong as a player rated `elo`, see `Strength`.
+    #[must_use]
+    pub fn with_strength(mut self, elo: u16) -> Self {
+        let strength = Strength::from_elo(elo);
+        self.depth.set(strength.depth);
+        self.engine.limit_strength(strength);
+        self.elo = Some(elo);
+        self
+    }
 
-impl MinimaxBot {
-    /// Create a new `MinimaxBot` with the given color and depth.
-    pub fn new(color: Color, depth: u8) -> Self {
-        MinimaxBot { color, depth }
+    /// Pick randomly among the moves almost as good as the best one for the first `plies` plies,
+    /// so repeated games against the bot don't all follow the same line.
+    #[must_use]
+    pub fn with_variety(mut self, plies: u8) -> Self {
+        self.variety = plies;
+        self
     }
 
-    /// Evaluate a given board.
-    /// This is the evaluation function used by the minimax algorithm.
-    ///
-    /// # Examples
-    /// ```
-    /// # use reversi::player::minimax_bot::MinimaxBot;
-    /// # use reversi::reversi::*;
-    /// # use std::str::FromStr;
-    ///
-    /// let mut board = Board::new();
-    /// let bot = MinimaxBot::new(Color::White, 1);
-    ///
-    /// assert_eq!(bot.eval(&board), 0);
-    ///
-    /// board.add_piece(Field::from_str("d3").unwrap(), Color::White);
-    /// assert_eq!(bot.eval(&board), +3);
-    ///
-    /// board.add_piece(Field::from_str("e3").unwrap(), Color::Black);
-    /// assert_eq!(bot.eval(&board), 0);
-    ///
-    /// board.add_piece(Field::from_str("f5").unwrap(), Color::White);
-    /// assert_eq!(bot.eval(&board), +5);
-    /// ```
-    pub fn eval(&self, board: &Board) -> i32 {
-        match board.status() {
-            GameStatus::Win(color) => match color {
-                Color::White => i32::MAX,
-                Color::Black => i32::MIN,
-            },
-            GameStatus::Draw => 0,
-            GameStatus::InProgress => i32::sub(
-                board.count_pieces(Color::White) as i32,
-                board.count_pieces(Color::Black) as i32,
-            ),
-        }
+    /// Keep games close for learners: when the bot is further ahead than `adaptive.lead`,
+    /// it plays worse moves that keep that lead instead of the best one.
+    #[must_use]
+    pub fn with_adaptive(mut self, adaptive: Adaptive) -> Self {
+        self.adaptive = Some(adaptive);
+        self
     }
 
-    /// Find the best move using the minimax algorithm.
-    /// This is the function used by the `MinimaxBot` to find the best move.
-    ///
-    /// # Arguments
-    /// * `board` - The board to evaluate.
-    /// * `depth` - The depth of the search. This is the number of moves to look ahead.
-    /// * `strategy` - The strategy to use.
-    ///
-    /// # Examples
-    /// ```
-    /// # use reversi::player::minimax_bot::MinimaxBot;
-    /// # use reversi::reversi::*;
-    ///
-    /// let mut board = Board::new();
-    /// let bot = MinimaxBot::new(Color::White, 1);
-    ///
-    /// assert!(board.valid_moves().contains(bot.minimax(&board, 2, MinimaxStrategy::Maximize)));
-    /// ```
-    ///
-    /// ```
-    /// # use reversi::player::minimax_bot::MinimaxBot;
-    /// # use reversi::reversi::*;
-    ///
-    /// let mut board = Board::new();
-    /// let bot1 = MinimaxBot::new(Color::White, 1);
-    /// let bot2 = MinimaxBot::new(Color::Black, 3);
-    ///
-    /// let mut counter = 0;
-    /// while board.status() == GameStatus::InProgress {
-    ///    counter += 1;
-    ///    if (counter % 2) == 0 {
-    ///    board.add_piece(bot1.minimax(&board, 1, MinimaxStrategy::Maximize), Color::White);
-    ///    } else {
-    ///    board.add_piece(bot2.minimax(&board, 3, MinimaxStrategy::Minimize), Color::Black);
-    ///    }
-    /// }
-    ///
-    /// assert_eq!(board.status(), GameStatus::Win(Color::Black));
-    /// ```
-    pub fn minimax(
+    /// Every valid move with its evaluation by a search of `depth`.
+    fn evaluate_moves(&self, board: &Board, depth: u8) -> Vec<(Field, i32)> {
+        board
+            .valid_moves(self.color)
+            .into_iter()
+            .map(|field| {
+                let board = board.with_move(field, self.color).unwrap();
+                let limits = SearchLimits::depth(depth - 1);
+                (
+                    field,
+                    -self.engine.best_move(&board, self.color.other(), limits).1,
+                )
+            })
+            .collect()
+    }
+
+    /// Search every move and pick one of those within `VARIETY_MARGIN` of the best,
+    /// the better ones more likely.
+    fn varied_move(&self, board: &Board, depth: u8) -> (Option<Field>, i32) {
+        let candidates = self.evaluate_moves(board, depth);
+
+        let Some(best) = candidates.iter().map(|&(_, evaluation)| evaluation).max() else {
+            return self
+                .engine
+                .best_move(board, self.color, SearchLimits::depth(depth));
+        };
+
+        candidates
+            .choose_weighted(&mut rand::thread_rng(), |&(_, evaluation)| {
+                (VARIETY_MARGIN + 1 - (i64::from(best) - i64::from(evaluation))).max(0)
+            })
+            .map(|&(field, evaluation)| (Some(field), evaluation))
+            .unwrap()
+    }
+
+    /// The worst move that keeps the lead of `adaptive` and loses at most `adaptive.max_loss`
+    /// discs against the best move, which is played if the bot isn't that far ahead.
+    /// Returns the move, its evaluation and a note if it isn't the best move.
+    fn adaptive_move(
         &self,
         board: &Board,
         depth: u8,
-        strategy: MinimaxStrategy,
-    ) -> (Option<Field>, i32) {
-        if depth == 0 || board.status() != GameStatus::InProgress {
-            return (None, self.eval(board));
-        }
+        adaptive: Adaptive,
+    ) -> (Option<Field>, i32, Option<String>) {
+        let candidates = self.evaluate_moves(board, depth);
+        let Some(&(best_field, best)) =
+            candidates.iter().max_by_key(|&&(_, evaluation)| evaluation)
+        else {
+            let (field, evaluation) =
+                self.engine
+                    .best_move(board, self.color, SearchLimits::depth(depth));
+            return (field, evaluation, None);
+        };
+
+        let floor = adaptive.lead.max(best - adaptive.max_loss);
+        let (field, evaluation) = candidates
+            .into_iter()
+            .filter(|&(_, evaluation)| evaluation >= floor)
+            .min_by_key(|&(_, evaluation)| evaluation)
+            .unwrap_or((best_field, best));
+        let note = (evaluation < best).then(|| {
+            format!(
+                "Adaptive: {field} ({evaluation:+}) instead of {best_field} ({best:+}) to keep a lead of {:+}",
+                adaptive.lead
+            )
+        });
+        (Some(field), evaluation, note)
+    }
 
-        let mut best_choice = (None, strategy.worst_value());
+    /// The deepest search expected to finish within the think time.
+    ///
+    /// The depth grows by at most one per move, so a misjudged position doesn't take minutes.
+    fn choose_depth(&self, board: &Board, think_time: Duration) -> u8 {
+        let last_depth = self.depth.get();
+        let Some((speed, branching)) = self.calibration.get() else {
+            return last_depth;
+        };
 
-        for field in board.valid_moves(strategy.into()) {
-            let mut board = board.clone();
-            board.add_piece(field, strategy.into()).unwrap();
+        let budget = speed * think_time.as_secs_f64();
+        // Searching deeper than the end of the game is pointless.
+        let discs = board.count_pieces(Color::White) + board.count_pieces(Color::Black);
+        let max_depth = u8::try_from(64 - discs).unwrap_or(u8::MAX).max(1);
 
-            let (_, evaluation) = self.minimax(&board, depth - 1, strategy.other());
+        let mut depth = 1;
+        while depth < max_depth
+            && depth <= last_depth
+            && branching.powi(i32::from(depth) + 1) <= budget
+        {
+            depth += 1;
+        }
+        depth
+    }
 
-            match strategy {
-                MinimaxStrategy::Minimize => {
-                    if evaluation <= best_choice.1 {
-                        best_choice = (Some(field), evaluation);
-                    }
-                }
-                MinimaxStrategy::Maximize => {
-                    if evaluation >= best_choice.1 {
-                        best_choice = (Some(field), evaluation);
-                    }
-                }
-            }
+    /// Remember how fast the last search was for choosing the next depth.
+    #[allow(clippy::cast_precision_loss)]
+    fn calibrate(&self, depth: u8, elapsed: Duration) {
+        let nodes = self.engine.nodes() as f64;
+        // Too short searches say nothing about the speed of the engine.
+        if elapsed < Duration::from_millis(10) && self.calibration.get().is_some() {
+            return;
         }
+        let speed = nodes / elapsed.as_secs_f64().max(0.001);
+        let branching = nodes.powf(1.0 / f64::from(depth)).max(1.5);
+        self.calibration.set(Some((speed, branching)));
+    }
 
-        best_choice
+    /// The engine the bot searches with.
+    pub fn engine(&self) -> &Engine {
+        &self.engine
     }
 }
 
 impl Player for MinimaxBot {
     fn name(&self) -> String {
-        format!("Minimax Bot (depth {})", self.depth,)
+        let think_time = self
+            .time_manager
+            .borrow()
+            .as_ref()
+            .map(TimeManager::per_move);
+        let strength = match (think_time.or(self.move_time), self.elo) {
+            (Some(time), _) => format!("{}s per move", time.as_secs_f64()),
+            (None, Some(elo)) => format!("Elo {elo}"),
+            (None, None) => format!("depth {}", self.depth.get()),
+        };
+        match self.adaptive {
+            Some(_) => format!("Minimax Bot ({strength}, adaptive)"),
+            None => format!("Minimax Bot ({strength})"),
+        }
     }
 
     fn color(&self) -> Color {
@@ -211,33 +278,143 @@ impl Player for MinimaxBot {
 
     /// Make a move using the minimax algorithm interactively.
     /// The interactive part of this includes displaying a spinner while the bot is thinking.
-    fn turn(&self, board: &Board) -> Option<Field> {
-        redraw_board(board, &Default::default());
+    fn turn(&self, board: &Board, options: &DisplayOptions) -> Move {
+        if !self.quiet {
+            redraw_board(board, options);
+            println!("{} {}\n", self.color(), self.name().bold());
+        }
+
+        // Keep the positions searched in earlier turns that can still be reached.
+        self.engine.prune_hash(board);
 
-        println!("{} {}\n", self.color(), self.name().bold());
+        #[cfg(feature = "spinner")]
+        let spinner = (!self.quiet).then(|| Spinner::new(Spinners::Dots8Bit, "Thinking".into()));
+        #[cfg(not(feature = "spinner"))]
+        if !self.quiet {
+            println!("Thinking...");
+        }
 
-        let mut sp = Spinner::new(Spinners::Dots8Bit, "Thinking".into());
-        let best_move = self.minimax(board, self.depth, self.color.into());
-        sp.stop();
+        let budget = self
+            .time_manager
+            .borrow()
+            .as_ref()
+            .map(|time_manager| time_manager.budget(board, self.color));
+        let mut depth = match budget {
+            // The only valid move is played at once, without lowering the depth of the next ones.
+            Some(budget) if budget.is_zero() => 1,
+            Some(budget) => {
+                self.depth.set(self.choose_depth(board, budget));
+                self.depth.get()
+            }
+            None => self.depth.get(),
+        };
 
-        if let Some(field) = best_move.0 {
+        let start = Instant::now();
+        // The discs placed in the classic opening count as part of the opening, too.
+        let plies =
+            (board.count_pieces(Color::White) + board.count_pieces(Color::Black)).saturating_sub(4);
+        let varied = plies < usize::from(self.variety);
+        let mut note = None;
+        let best_move = match self.adaptive {
+            _ if varied => self.varied_move(board, depth),
+            Some(adaptive) => {
+                let (field, evaluation, deviation) = self.adaptive_move(board, depth, adaptive);
+                note = deviation;
+                (field, evaluation)
+            }
+            None => match self.move_time {
+                Some(time) => {
+                    let empty =
+                        64 - board.count_pieces(Color::White) - board.count_pieces(Color::Black);
+                    let max_depth = u8::try_from(empty).unwrap_or(u8::MAX).max(1);
+                    let depths = self.engine.deepen(board, self.color, time, max_depth);
+                    depth = u8::try_from(depths.len()).unwrap_or(u8::MAX);
+                    let best_move = *depths.last().unwrap();
+                    self.depths.replace(depths);
+                    best_move
+                }
+                None => self
+                    .engine
+                    .best_move(board, self.color, SearchLimits::depth(depth)),
+            },
+        };
+        let elapsed = start.elapsed();
+        if let Some(time_manager) = self.time_manager.borrow_mut().as_mut() {
+            self.calibrate(depth, elapsed);
+            time_manager.record(elapsed, best_move.1);
+        }
+        self.statistics
+            .borrow_mut()
+            .record(&self.engine, depth, elapsed);
+
+        #[cfg(feature = "spinner")]
+        if let Some(mut spinner) = spinner {
+            spinner.stop();
+            // Erase the spinner line.
+            print!("\x1b[2K\r");
+        }
+        if self.quiet {
+            self.note.replace(note);
+            return best_move.0.into();
+        }
+
+        if let Some(budget) = budget.or(self.move_time) {
             println!(
-                "\x1b[2K\rThe bot plays {} ({:+})",
-                field.to_string(),
-                best_move.1
+                "Searched depth {depth} in {:.1}s of {:.1}s",
+                elapsed.as_secs_f64(),
+                budget.as_secs_f64()
             );
+        }
+        if self.move_time.is_some() {
+            let moves: Vec<String> = self
+                .depths
+                .borrow()
+                .iter()
+                .map(|(field, _)| field.map_or("pass".to_owned(), |field| field.to_string()))
+                .collect();
+            println!("Best move by depth: {}", moves.join(" "));
+        }
+        if let Some(field) = best_move.0 {
+            print!("The bot plays {field} ({:+})", best_move.1);
+            if varied {
+                print!(", varying the opening");
+            }
+            println!();
+            if let Some(note) = &note {
+                println!("{note}");
+            }
         } else {
-            println!("\x1b[2K\rThe bot has no valid moves. It passes.");
+            println!("The bot has no valid moves. It passes.");
         }
 
-        print!("Press <Enter> to continue ");
-        io::stdout().flush().unwrap();
-        io::stdin().read_line(&mut String::new()).unwrap();
+        let (used, limit) = self.engine.hash_usage();
+        #[allow(clippy::cast_precision_loss)]
+        let to_megabytes = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+        println!(
+            "Hash: {:.1} / {:.0} MB",
+            to_megabytes(used),
+            to_megabytes(limit)
+        );
 
-        best_move.0
+        if self.confirm_moves {
+            print!("Press <Enter> to continue ");
+            io::stdout().flush().unwrap();
+            input::read_line();
+        }
+
+        self.note.replace(note);
+        best_move.0.into()
     }
 
     fn redraw_options(&self) -> DisplayOptions {
         Default::default()
     }
+
+    fn statistics(&self) -> Option<SearchStatistics> {
+        Some(self.statistics.borrow().clone())
+    }
+
+    fn note(&self) -> Option<String> {
+        self.note.borrow().clone()
+    }
 }
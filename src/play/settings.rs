@@ -0,0 +1,163 @@
+//! The display settings of a game, which can be changed in a settings file while it goes on.
+//!
+//! The file has `key = value` lines like a theme file: `theme` (a built-in theme or a theme
+//! file), `animation` (`slow`, `medium`, `fast` or `off`), `hints` (`on` or `off`) and `nice`
+//! (`on` or `off`, see `--nice`). `nice` is only read when the program starts.
+
+use crate::parse_theme;
+use reversi_game::board::Theme;
+
+use std::{env, fs, path::PathBuf, time::Duration, time::SystemTime};
+
+use clap::{parser::ValueSource, ArgMatches};
+
+/// The settings file: `$REVERSI_CONFIG`, or `reversi/settings` in the user's config directory.
+pub fn path() -> PathBuf {
+    if let Some(path) = env::var_os("REVERSI_CONFIG") {
+        return path.into();
+    }
+
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+    config.join("reversi").join("settings")
+}
+
+/// The time a flipped disc takes to turn over at the speed called `name`.
+fn animation_speed(name: &str) -> Result<Duration, String> {
+    match name {
+        "slow" => Ok(Duration::from_millis(800)),
+        "medium" => Ok(Duration::from_millis(300)),
+        "fast" => Ok(Duration::from_millis(100)),
+        "off" => Ok(Duration::ZERO),
+        other => Err(format!(
+            "Unknown animation `{other}`, use slow, medium, fast or off"
+        )),
+    }
+}
+
+#[derive(Clone)]
+pub struct Settings {
+    pub theme: Theme,
+    /// The time a flipped disc takes to turn over.
+    pub animation_speed: Duration,
+    /// Whether the valid moves are marked on the board.
+    pub hints: bool,
+    /// Whether the engine runs in the low-power mode, see `engine::throttle`.
+    pub nice: bool,
+    /// When the settings file was changed before it was read last.
+    modified: Option<SystemTime>,
+}
+
+impl Settings {
+    /// The settings of the options in `matches`, with the settings file for those not given on
+    /// the command line.
+    pub fn new(matches: &ArgMatches) -> Self {
+        let mut settings = Settings {
+            theme: matches
+                .get_one::<Theme>("theme")
+                .cloned()
+                .unwrap_or_default(),
+            animation_speed: if matches.get_flag("no-animation") {
+                Duration::ZERO
+            } else {
+                animation_speed(matches.get_one::<String>("animation-speed").unwrap()).unwrap()
+            },
+            hints: !matches.get_flag("no-hints"),
+            nice: matches.get_flag("nice"),
+            modified: None,
+        };
+
+        let given = |id| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let mut file = settings.clone();
+        if let Err(error) = file.read() {
+            eprintln!("{error}");
+        }
+        if !given("theme") {
+            settings.theme = file.theme;
+        }
+        if !given("animation-speed") && !given("no-animation") {
+            settings.animation_speed = file.animation_speed;
+        }
+        if !given("no-hints") {
+            settings.hints = file.hints;
+        }
+        settings.nice |= file.nice;
+        settings.modified = file.modified;
+        settings
+    }
+
+    /// Apply the settings of the file, keeping the others.
+    fn read(&mut self) -> Result<(), String> {
+        let path = path();
+        self.modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(());
+        };
+
+        let mut read = self.clone();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |error| format!("{}:{}: {error}", path.display(), index + 1);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("Expected `key = value`".to_owned()))?;
+            match (key.trim(), value.trim()) {
+                ("theme", value) => read.theme = parse_theme(value).map_err(invalid)?,
+                ("animation", value) => {
+                    read.animation_speed = animation_speed(value).map_err(invalid)?;
+                }
+                ("hints", "on") => read.hints = true,
+                ("hints", "off") => read.hints = false,
+                ("nice", "on") => read.nice = true,
+                ("nice", "off") => read.nice = false,
+                ("hints" | "nice", other) => {
+                    return Err(invalid(format!("Use on or off, not `{other}`")));
+                }
+                (other, _) => return Err(invalid(format!("Unknown setting `{other}`"))),
+            }
+        }
+        *self = Settings {
+            modified: self.modified,
+            ..read
+        };
+        Ok(())
+    }
+
+    /// Whether `--nice` or the settings file turn on the low-power mode. An invalid file is
+    /// reported when a game starts.
+    pub fn nice(matches: &ArgMatches) -> bool {
+        let mut file = Settings {
+            theme: Theme::default(),
+            animation_speed: Duration::ZERO,
+            hints: true,
+            nice: false,
+            modified: None,
+        };
+        matches.get_flag("nice") || (file.read().is_ok() && file.nice)
+    }
+
+    /// Read the settings file again, e.g. after a theme file it names was changed.
+    ///
+    /// Returns a message for the player, with the error if the file is invalid.
+    pub fn reload(&mut self) -> String {
+        match self.read() {
+            Ok(()) => format!("Reloaded the settings from {}", path().display()),
+            Err(error) => format!("Kept the settings: {error}"),
+        }
+    }
+
+    /// Reload the settings if the file was changed since it was read last, see `reload`.
+    pub fn refresh(&mut self) -> Option<String> {
+        let modified = fs::metadata(path())
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        (modified != self.modified).then(|| self.reload())
+    }
+}
@@ -0,0 +1,140 @@
+use super::input::{self, RawMode};
+
+use std::{
+    io::{self, Write},
+    sync::mpsc::RecvTimeoutError,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A key that controls the playback of a watched game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackKey {
+    /// Pause or resume the playback.
+    Pause,
+    /// Play faster.
+    Faster,
+    /// Play slower.
+    Slower,
+    /// Play a single move, then pause.
+    Step,
+}
+
+impl PlaybackKey {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b' ' => Some(PlaybackKey::Pause),
+            b'+' | b'=' => Some(PlaybackKey::Faster),
+            b'-' | b'_' => Some(PlaybackKey::Slower),
+            b's' | b'S' => Some(PlaybackKey::Step),
+            _ => None,
+        }
+    }
+}
+
+/// Controls the pace of a bot-vs-bot game with single key presses.
+///
+/// Keys are read from the input's background thread, so waiting between moves
+/// can react to them immediately.
+pub struct Playback {
+    initial_delay: Duration,
+    delay: Duration,
+    paused: bool,
+    stepping: bool,
+    _raw_mode: Option<RawMode>,
+}
+
+impl Playback {
+    const MIN_DELAY: Duration = Duration::from_millis(100);
+    const MAX_DELAY: Duration = Duration::from_secs(8);
+
+    /// Start listening for playback keys, waiting `delay` between moves.
+    pub fn new(delay: Duration) -> Self {
+        let raw_mode = RawMode::enable(&["-icanon", "-echo", "min", "1"]);
+
+        Playback {
+            initial_delay: delay,
+            delay,
+            paused: false,
+            stepping: false,
+            _raw_mode: raw_mode,
+        }
+    }
+
+    /// The time between two moves.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// How much faster than the initial speed the playback runs, e.g. `2.0`.
+    pub fn speed_factor(&self) -> f64 {
+        self.initial_delay.as_secs_f64() / self.delay.as_secs_f64()
+    }
+
+    /// A line describing the controls and the current state.
+    pub fn status_line(&self) -> String {
+        format!(
+            "{} | [space] pause/resume  [+/-] speed (x{:.2})  [s] step",
+            if self.paused { "Paused " } else { "Playing" },
+            self.speed_factor()
+        )
+    }
+
+    fn print_status_line(&self) {
+        print!("\r{}", self.status_line());
+        io::stdout().flush().unwrap();
+    }
+
+    fn handle(&mut self, key: PlaybackKey) {
+        match key {
+            PlaybackKey::Pause => {
+                self.paused = !self.paused;
+                self.stepping = false;
+            }
+            PlaybackKey::Faster => self.delay = (self.delay / 2).max(Self::MIN_DELAY),
+            PlaybackKey::Slower => self.delay = (self.delay * 2).min(Self::MAX_DELAY),
+            PlaybackKey::Step => {
+                self.paused = true;
+                self.stepping = true;
+            }
+        }
+    }
+
+    /// Wait until the next move should be played.
+    ///
+    /// This waits for the current delay, or until the playback is resumed
+    /// or stepped if it is paused.
+    pub fn wait(&mut self) {
+        let mut deadline = Instant::now() + self.delay;
+        let bytes = input::bytes();
+        self.print_status_line();
+
+        loop {
+            if self.stepping {
+                self.stepping = false;
+                break;
+            }
+
+            let byte = input::next_byte(&bytes, (!self.paused).then_some(deadline));
+
+            match byte.map(PlaybackKey::from_byte) {
+                Ok(None) => {}
+                Ok(Some(key)) => {
+                    let previous_delay = self.delay;
+                    self.handle(key);
+                    deadline = deadline - previous_delay + self.delay;
+                    self.print_status_line();
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                // Without input, the playback can't be paused anymore.
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.paused = false;
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    break;
+                }
+            }
+        }
+
+        println!();
+    }
+}
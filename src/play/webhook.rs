@@ -0,0 +1,120 @@
+//! The result of each game sent to a webhook with `--webhook URL` (or `$REVERSI_WEBHOOK`), e.g.
+//! to feed a club's leaderboard from the games of its members. It is a `POST` of JSON like
+//!
+//! ```json
+//! {"white":"Player 1","black":"Minimax Bot (depth 3)","date":"2026.10.16","score":[36,28],
+//!  "winner":"white","termination":null,"variant":"standard","start":null,
+//!  "transcript":"D3C5F6...","moves":["d3","c5","f6",...]}
+//! ```
+//!
+//! `winner` is `null` for a draw, `termination` tells why a game ended early, like the
+//! `Termination` header of a saved game, and `start` is the position it started from or `null`
+//! for the initial position. `transcript` leaves out the passes; in `moves` they are `null`.
+//! Only `http://` URLs are supported.
+
+use crate::rpc::json::Json;
+use reversi_game::{
+    record::GameRecord,
+    reversi::*,
+    rules::{Game, Variant},
+};
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// How long connecting, sending and waiting for the answer may each take.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The result of the `record` of `game`, see the module documentation.
+pub fn payload(record: &GameRecord, game: &Game, status: &GameStatus) -> Json {
+    let board = game.board();
+    let winner = match status {
+        GameStatus::Win(Color::White) => Some("white"),
+        GameStatus::Win(Color::Black) => Some("black"),
+        _ => None,
+    };
+    Json::object([
+        ("white", record.white.as_str().into()),
+        ("black", record.black.as_str().into()),
+        ("date", record.date.clone().into()),
+        (
+            "score",
+            vec![
+                board.count_pieces(Color::White),
+                board.count_pieces(Color::Black),
+            ]
+            .into(),
+        ),
+        ("winner", winner.into()),
+        ("termination", record.termination.clone().into()),
+        ("variant", Variant::of(game.start()).to_string().into()),
+        ("start", record.to_archive().header("Position").into()),
+        ("transcript", game.to_transcript().into()),
+        (
+            "moves",
+            record
+                .moves
+                .iter()
+                .map(|recorded| {
+                    recorded
+                        .played
+                        .map(|field| Notation::Standard.format(field))
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+    ])
+}
+
+/// The host with the port and the path of an `http://` URL.
+fn split_url(url: &str) -> Result<(&str, &str), String> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        format!("Only http:// webhooks are supported, not {url}; use a local relay for https")
+    })?;
+    Ok(match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    })
+}
+
+/// `POST` `payload` to `url`, returning an error unless the server answers with a 2xx status.
+pub fn send(url: &str, payload: &Json) -> Result<(), String> {
+    let (host, path) = split_url(url)?;
+    let failed = |error: std::io::Error| format!("Failed to send the result to {url}: {error}");
+
+    let address = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{host}:80")
+    };
+    let address = address
+        .to_socket_addrs()
+        .map_err(failed)?
+        .next()
+        .ok_or_else(|| format!("{host} has no address"))?;
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT).map_err(failed)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(failed)?;
+    stream.set_write_timeout(Some(TIMEOUT)).map_err(failed)?;
+
+    let body = payload.to_string();
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\nUser-Agent: reversi/{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        env!("CARGO_PKG_VERSION"),
+        body.len()
+    )
+    .map_err(failed)?;
+
+    let mut status = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status)
+        .map_err(failed)?;
+    let status = status.trim();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(format!("{url} answered `{status}`")),
+    }
+}
@@ -0,0 +1,327 @@
+//! Quizzes on a player's own mistakes: a saved game is replayed up to each move on which they
+//! lost discs according to its analysis by `reversi analyze-batch`, and with the move they
+//! played hidden, they have to find a better one. The scores are kept to show the progress.
+
+use crate::{
+    games::{self, batch},
+    play::input,
+};
+use reversi_game::{archive::Game, engine::Engine, reversi::*};
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+/// The number of earlier scores shown after a quiz.
+const HISTORY: usize = 10;
+
+/// A move of the player that lost discs.
+struct Mistake {
+    /// The number of the move, starting at 1.
+    number: usize,
+    before: Board,
+    color: Color,
+    played: Field,
+    best: Field,
+    loss: i32,
+}
+
+/// The file the scores of the quizzes are appended to, next to the saved games:
+/// one `date game found positions` line per quiz.
+pub fn results_path() -> PathBuf {
+    games::directory().join("quizzes")
+}
+
+/// The better moves and the discs lost by number of the move, from the `Analysis` header of
+/// `game` or else of its copy among the `annotated` games, as written by `reversi analyze-batch`.
+fn analysis_of(
+    path: &Path,
+    game: &Game,
+    annotated: &[(PathBuf, Game)],
+) -> BTreeMap<usize, (Field, i32)> {
+    let own = game.analysis();
+    if !own.is_empty() {
+        return own;
+    }
+    annotated
+        .iter()
+        .find(|(copy, other)| {
+            copy.file_name() == path.file_name()
+                && other.moves == game.moves
+                && ["White", "Black", "Date"]
+                    .iter()
+                    .all(|&name| other.header(name) == game.header(name))
+        })
+        .map(|(_, copy)| copy.analysis())
+        .unwrap_or_default()
+}
+
+/// The moves of `name` in `game` that lost at least `min_loss` discs.
+fn mistakes(
+    game: &Game,
+    analysis: &BTreeMap<usize, (Field, i32)>,
+    name: &str,
+    min_loss: i32,
+) -> Result<Vec<Mistake>, String> {
+    let mut mistakes = Vec::new();
+    for (&number, &(best, loss)) in analysis {
+        let color = game.color_of(number);
+        if loss < min_loss || game.header(&format!("{color:?}")) != Some(name) {
+            continue;
+        }
+        let Some(Some(played)) = game.moves.get(number - 1).copied() else {
+            continue;
+        };
+        mistakes.push(Mistake {
+            number,
+            before: game.board_after(number - 1)?,
+            color,
+            played,
+            best,
+            loss,
+        });
+    }
+    Ok(mistakes)
+}
+
+/// The `number`th saved game, or the last one of `name` with mistakes, with its number and
+/// its mistakes.
+fn choose(
+    number: Option<usize>,
+    name: &str,
+    min_loss: i32,
+) -> Result<(usize, Vec<Mistake>), String> {
+    let saved = games::load_all().map_err(|error| format!("Failed to load the games: {error}"))?;
+    let annotated = games::load_dir(&games::directory().join("annotated"))
+        .map_err(|error| format!("Failed to load the annotated games: {error}"))?;
+    let with_mistakes = |number: usize| -> Result<Vec<Mistake>, String> {
+        let (path, game) = &saved[number - 1];
+        mistakes(game, &analysis_of(path, game, &annotated), name, min_loss)
+    };
+
+    match number {
+        Some(number) => {
+            if number == 0 || number > saved.len() {
+                return Err(format!(
+                    "There is no game {number}, see `reversi games list`"
+                ));
+            }
+            let found = with_mistakes(number)?;
+            if found.is_empty() {
+                return Err(format!(
+                    "Game {number} has no analyzed moves of {name} that lost {min_loss} or more discs. Run `reversi analyze-batch` on the games directory first."
+                ));
+            }
+            Ok((number, found))
+        }
+        None => {
+            for number in (1..=saved.len()).rev() {
+                let found = with_mistakes(number)?;
+                if !found.is_empty() {
+                    return Ok((number, found));
+                }
+            }
+            Err(format!(
+                "None of the games of {name} has analyzed moves that lost {min_loss} or more discs. Run `reversi analyze-batch` on the games directory first."
+            ))
+        }
+    }
+}
+
+/// How a position of the quiz ended.
+enum Outcome {
+    /// A better move than the one played was found, `true` if it was the best one.
+    Found(bool),
+    Missed,
+    Quit,
+}
+
+fn ask(engine: &Engine, mistake: &Mistake, depth: u8, title: &str) -> Result<Outcome, String> {
+    let Mistake {
+        before,
+        color,
+        played,
+        best,
+        loss,
+        ..
+    } = mistake;
+    let completions: Vec<String> = before
+        .valid_moves(*color)
+        .iter()
+        .map(ToString::to_string)
+        .chain(["skip", "quit"].map(str::to_owned))
+        .collect();
+    let mut message = format!(
+        "{color} to move. Your move here lost {loss} discs, find a better one (or `skip`)."
+    );
+
+    loop {
+        redraw_board(
+            before,
+            &DisplayOptions {
+                color: Some(*color),
+                title: Some(title.to_owned()),
+                ..Default::default()
+            },
+        );
+        println!("{message}");
+
+        let Ok(line) = input::edit_line("> ", &completions, None) else {
+            return Ok(Outcome::Quit);
+        };
+        let guess = match line.trim().to_lowercase().as_str() {
+            "" => continue,
+            "quit" => return Ok(Outcome::Quit),
+            "skip" => {
+                println!("You played {played}, {best} was {loss} discs better.");
+                let _ = input::edit_line("Press <Enter> for the next position.", &[], None);
+                return Ok(Outcome::Missed);
+            }
+            line => match line.parse::<Field>() {
+                Ok(field) => field,
+                Err(error) => {
+                    message = format!("Invalid input: {error}");
+                    continue;
+                }
+            },
+        };
+        if !before.is_valid(guess, *color) {
+            message = format!("{guess} isn't a valid move.");
+            continue;
+        }
+        if guess == *played {
+            message = format!("{guess} is what you played, try another move.");
+            continue;
+        }
+
+        let guess_loss = if guess == *best {
+            0
+        } else {
+            batch::judge(engine, before, *color, guess, depth)?.map_or(0, |(_, loss)| loss)
+        };
+        let outcome = if guess_loss == 0 {
+            println!(
+                "{} {guess} is the best move, you played {played} and lost {loss} discs.",
+                "Correct!".bold().green()
+            );
+            Outcome::Found(true)
+        } else if guess_loss < *loss {
+            println!(
+                "{} {guess} loses only {guess_loss} discs instead of {loss} with {played}, but {best} is the best move.",
+                "Better!".bold().yellow()
+            );
+            Outcome::Found(false)
+        } else {
+            println!(
+                "{} {guess} loses {guess_loss} discs, you played {played} and lost {loss}; {best} is the best move.",
+                "Not better.".bold().red()
+            );
+            Outcome::Missed
+        };
+        let _ = input::edit_line("Press <Enter> for the next position.", &[], None);
+        return Ok(outcome);
+    }
+}
+
+/// Append the score of a quiz on game `number` to the results, see `results_path`.
+fn record(number: usize, found: usize, positions: usize) -> io::Result<()> {
+    let path = results_path();
+    fs::create_dir_all(path.parent().unwrap_or(&path))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{} {number} {found} {positions}", games::today())
+}
+
+/// The found and all positions of the earlier quizzes, oldest first.
+fn history() -> io::Result<Vec<(usize, usize)>> {
+    let results = match fs::read_to_string(results_path()) {
+        Ok(results) => results,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    Ok(results
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                [_, _, found, positions] => Some((found.parse().ok()?, positions.parse().ok()?)),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+/// The share of `scores` that was found, in percent.
+#[allow(clippy::cast_precision_loss)]
+fn rate(scores: &[(usize, usize)]) -> f64 {
+    let found: usize = scores.iter().map(|(found, _)| found).sum();
+    let positions: usize = scores.iter().map(|(_, positions)| positions).sum();
+    found as f64 * 100.0 / positions.max(1) as f64
+}
+
+/// The earlier scores and how this one compares to them.
+fn progress(earlier: &[(usize, usize)], found: usize, positions: usize) -> String {
+    if earlier.is_empty() {
+        return "This was your first quiz, its score is kept to show your progress.".to_owned();
+    }
+    let recent = &earlier[earlier.len().saturating_sub(HISTORY)..];
+    let scores: Vec<String> = recent
+        .iter()
+        .map(|(found, positions)| format!("{found}/{positions}"))
+        .collect();
+    let (before, now) = (rate(earlier), rate(&[(found, positions)]));
+    let trend = if now > before {
+        "up from"
+    } else if now < before {
+        "down from"
+    } else {
+        "the same as"
+    };
+    format!(
+        "Earlier quizzes: {}\nYou found {now:.0}% of the better moves, {trend} {before:.0}% before.",
+        scores.join(", ")
+    )
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let name = matches.get_one::<String>("name").unwrap();
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    let min_loss = i32::from(*matches.get_one::<u8>("min-loss").unwrap());
+
+    let (number, mistakes) = choose(matches.get_one::<usize>("game").copied(), name, min_loss)?;
+
+    let engine = Engine::new();
+    let mut found = 0;
+    let mut best = 0;
+    let mut asked = 0;
+    for (index, mistake) in mistakes.iter().enumerate() {
+        let title = format!(
+            "Quiz {}/{}: game {number}, move {}",
+            index + 1,
+            mistakes.len(),
+            mistake.number
+        );
+        match ask(&engine, mistake, depth, &title)? {
+            Outcome::Found(was_best) => {
+                found += 1;
+                best += usize::from(was_best);
+            }
+            Outcome::Missed => {}
+            Outcome::Quit => break,
+        }
+        asked += 1;
+    }
+    if asked == 0 {
+        return Ok(());
+    }
+
+    println!("Found a better move in {found} of {asked} positions, the best one in {best}.");
+    let earlier = history().unwrap_or_default();
+    println!("{}", progress(&earlier, found, asked));
+    record(number, found, asked).map_err(|error| format!("Failed to save the score: {error}"))
+}
@@ -1,6 +1,15 @@
+pub mod archive;
 pub mod board;
+#[cfg(feature = "bot-bridge")]
+pub mod bot_bridge;
+pub mod engine;
+pub mod ggf;
+pub mod record;
+pub mod rules;
+pub mod wthor;
 
 pub use board::*;
+pub use rules::Game;
 
 use std::fmt;
 
@@ -37,3 +46,43 @@ impl fmt::Display for Color {
         }
     }
 }
+
+/// What a player does in their turn.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Move {
+    /// Put a disc on the field.
+    Place(Field),
+    /// Let the opponent move, which is only valid without a valid move, see
+    /// `rules::Position::must_pass`.
+    Pass,
+    /// Give up the game.
+    Resign,
+}
+
+impl Move {
+    /// The field of a `Place`.
+    #[must_use]
+    pub fn field(self) -> Option<Field> {
+        match self {
+            Move::Place(field) => Some(field),
+            Move::Pass | Move::Resign => None,
+        }
+    }
+}
+
+/// A field to place a disc on, or `None` to pass, as in the move history.
+impl From<Option<Field>> for Move {
+    fn from(played: Option<Field>) -> Self {
+        played.map_or(Move::Pass, Move::Place)
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Move::Place(field) => write!(f, "{field}"),
+            Move::Pass => write!(f, "pass"),
+            Move::Resign => write!(f, "resign"),
+        }
+    }
+}
@@ -0,0 +1,521 @@
+//! A text format for collections of games, modelled after chess PGN.
+//!
+//! Each game starts with its headers, one `[Key "Value"]` per line, followed by the moves.
+//! Move numbers (`1.`) are optional, a pass is written as `--`, comments in braces follow
+//! the move they belong to, and the game ends with its result: the final disc counts
+//! `White-Black`, or `*` if the game was not finished.
+//!
+//! Archives start with the version of their format in a `% version` line. Like in PGN, other
+//! lines starting with `%` are ignored, and archives without a version are read as version 1.
+//!
+//! ```text
+//! % version 1
+//! [White "Player 1"]
+//! [Black "Minimax Bot (depth 3)"]
+//! [Date "2026.10.15"]
+//! [Result "*"]
+//!
+//! 1. d3 c5 {should have taken the corner} 2. f6 *
+//! ```
+
+use crate::reversi::{rules::Position, Board, Color, Field, GameStatus, Notation};
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// The version of the archive format written by `write`.
+pub const VERSION: u32 = 1;
+
+/// The headers written first, in this order, if a game has them.
+pub const STANDARD_HEADERS: [&str; 6] =
+    ["White", "Black", "Date", "Result", "TimeControl", "Variant"];
+
+/// A recorded game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Game {
+    /// Pairs of header names and values, in order.
+    pub headers: Vec<(String, String)>,
+    /// The moves from the start position, see `start`. `None` is a pass.
+    pub moves: Vec<Option<Field>>,
+    /// Comments by the number of moves played before them,
+    /// so `1` is a comment on the first move and `0` one on the whole game.
+    pub comments: BTreeMap<usize, String>,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value of a header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set a header, replacing its previous value.
+    pub fn set_header(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.headers.iter_mut().find(|(key, _)| key == name) {
+            Some((_, previous)) => *previous = value,
+            None => self.headers.push((name.to_owned(), value)),
+        }
+    }
+
+    /// The position the game started from: the one in the `Position` header, written like
+    /// `Position`'s `Display` with `/` between the lines, or the initial board with White
+    /// to move.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{archive::Game, rules::Position, Color};
+    /// let mut game = Game::new();
+    /// assert_eq!(game.start(), Ok(Position::new()));
+    ///
+    /// let mut position = Position::new();
+    /// position.forfeit_turn().unwrap();
+    /// game.set_start(&position);
+    /// assert_eq!(game.start(), Ok(position));
+    /// assert_eq!(game.color_of(1), Color::Black);
+    /// ```
+    pub fn start(&self) -> Result<Position, String> {
+        match self.header("Position") {
+            Some(position) => position
+                .parse()
+                .map_err(|error| format!("Invalid start position: {error}")),
+            None => Ok(Position::new()),
+        }
+    }
+
+    /// Start the game from `position` instead of the initial board.
+    pub fn set_start(&mut self, position: &Position) {
+        if *position == Position::new() {
+            self.headers.retain(|(key, _)| key != "Position");
+        } else {
+            self.set_header("Position", position.to_string().replace('\n', "/"));
+        }
+    }
+
+    /// Play the moves from the start position.
+    pub fn replay(&self) -> Result<Board, String> {
+        self.board_after(self.moves.len())
+    }
+
+    /// Play the first `count` moves from the start position.
+    pub fn board_after(&self, count: usize) -> Result<Board, String> {
+        let start = self.start()?;
+        let mut board = start.board().clone();
+        let mut color = start.to_move();
+
+        for (index, &played) in self.moves.iter().take(count).enumerate() {
+            if let Some(field) = played {
+                board
+                    .add_piece(field, color)
+                    .map_err(|error| format!("Move {} ({field}): {error}", index + 1))?;
+            }
+            color = color.other();
+        }
+
+        Ok(board)
+    }
+
+    /// The result of a game on `board`, as written in the `Result` header.
+    pub fn result_of(board: &Board) -> String {
+        if board.status() == GameStatus::InProgress {
+            "*".to_owned()
+        } else {
+            format!(
+                "{}-{}",
+                board.count_pieces(Color::White),
+                board.count_pieces(Color::Black)
+            )
+        }
+    }
+
+    /// The color that played the `number`th move, starting at 1.
+    pub fn color_of(&self, number: usize) -> Color {
+        let first = self
+            .start()
+            .map_or(Color::White, |position| position.to_move());
+        if number % 2 == 1 {
+            first
+        } else {
+            first.other()
+        }
+    }
+
+    /// The time each move took, from the `MoveTimes` header of space-separated seconds.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::archive::Game;
+    /// # use std::time::Duration;
+    /// let mut game = Game::new();
+    /// game.set_move_times(&[Duration::from_millis(1240), Duration::from_secs(3)]);
+    /// assert_eq!(game.header("MoveTimes"), Some("1.2 3.0"));
+    /// assert_eq!(game.move_times(), vec![Duration::from_millis(1200), Duration::from_secs(3)]);
+    /// ```
+    pub fn move_times(&self) -> Vec<Duration> {
+        self.header("MoveTimes")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map_while(|seconds| seconds.parse().ok().map(Duration::from_secs_f64))
+            .collect()
+    }
+
+    /// Record the time each move took in the `MoveTimes` header, rounded to tenths of a second.
+    pub fn set_move_times(&mut self, times: &[Duration]) {
+        let times: Vec<String> = times
+            .iter()
+            .map(|time| format!("{:.1}", time.as_secs_f64()))
+            .collect();
+        self.set_header("MoveTimes", times.join(" "));
+    }
+
+    /// The moves that lost discs according to an analysis, by their number: the better move
+    /// and how many discs it is better, from the `Analysis` header of space-separated
+    /// `number:field:loss` entries.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{archive::Game, Field};
+    /// # use std::collections::BTreeMap;
+    /// let mut game = Game::new();
+    /// game.set_analysis(&BTreeMap::from([(5, (Field(2, 4), 6)), (12, (Field(7, 7), 10))]));
+    /// assert_eq!(game.header("Analysis"), Some("5:c4:6 12:h1:10"));
+    /// assert_eq!(game.analysis()[&12], (Field(7, 7), 10));
+    /// ```
+    pub fn analysis(&self) -> BTreeMap<usize, (Field, i32)> {
+        self.header("Analysis")
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|entry| {
+                let mut parts = entry.split(':');
+                let number = parts.next()?.parse().ok()?;
+                let best = Notation::Standard.parse(parts.next()?).ok()?;
+                let loss = parts.next()?.parse().ok()?;
+                Some((number, (best, loss)))
+            })
+            .collect()
+    }
+
+    /// Record the moves that lost discs in the `Analysis` header, see `analysis`.
+    pub fn set_analysis(&mut self, analysis: &BTreeMap<usize, (Field, i32)>) {
+        let entries: Vec<String> = analysis
+            .iter()
+            .map(|(number, &(best, loss))| {
+                format!("{number}:{}:{loss}", Notation::Standard.format(best))
+            })
+            .collect();
+        self.set_header("Analysis", entries.join(" "));
+    }
+
+    /// The labels of the game, from the `Tags` header of space-separated tags.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::archive::Game;
+    /// let mut game = Game::new();
+    /// game.add_tag("training");
+    /// game.add_tag("ffo test");
+    /// game.add_tag("training");
+    /// assert_eq!(game.header("Tags"), Some("training ffo-test"));
+    /// assert!(game.has_tag("ffo-test"));
+    /// ```
+    pub fn tags(&self) -> Vec<&str> {
+        self.header("Tags")
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect()
+    }
+
+    /// Whether the game is labelled with `tag`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().contains(&tag)
+    }
+
+    /// Label the game with `tag`, with whitespace in it replaced by dashes.
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.split_whitespace().collect::<Vec<_>>().join("-");
+        if tag.is_empty() || self.has_tag(&tag) {
+            return;
+        }
+        let mut tags = self.tags().join(" ");
+        if !tags.is_empty() {
+            tags.push(' ');
+        }
+        tags.push_str(&tag);
+        self.set_header("Tags", tags);
+    }
+
+    /// Set the comment on the `number`th move, or remove it if `comment` is empty.
+    pub fn set_comment(&mut self, number: usize, comment: &str) {
+        // Braces would end the comment early.
+        let comment = comment.replace(['{', '}'], "");
+        let comment = comment.trim();
+        if comment.is_empty() {
+            self.comments.remove(&number);
+        } else {
+            self.comments.insert(number, comment.to_owned());
+        }
+    }
+
+    /// A checksum of the game as written in an archive, apart from its `Checksum` header:
+    /// the 64-bit FNV-1a hash of the text, as 16 hexadecimal digits. It catches files that
+    /// were damaged or edited by hand, but anyone can compute it, so it proves nothing about
+    /// who wrote the game.
+    pub fn checksum(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.headers.retain(|(key, _)| key != "Checksum");
+        // Reading the game adds the result of the movetext as a header if it is missing.
+        if unsigned.header("Result").is_none() {
+            unsigned.set_header("Result", "*");
+        }
+        let hash = unsigned
+            .to_string()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("{hash:016x}")
+    }
+
+    /// Write the `checksum` into the `Checksum` header, after the game changed.
+    pub fn sign(&mut self) {
+        let checksum = self.checksum();
+        self.set_header("Checksum", checksum);
+    }
+
+    /// Check that the game wasn't changed since it was `sign`ed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{archive::Game, Field};
+    /// let mut game = Game::new();
+    /// game.moves.push(Some(Field(2, 4)));
+    /// assert!(game.verify().is_err());
+    ///
+    /// game.sign();
+    /// assert_eq!(game.verify(), Ok(()));
+    ///
+    /// game.moves.push(None);
+    /// assert!(game.verify().is_err());
+    /// ```
+    pub fn verify(&self) -> Result<(), String> {
+        match self.header("Checksum") {
+            Some(checksum) if checksum == self.checksum() => Ok(()),
+            Some(_) => Err("The checksum doesn't match, the game was changed".to_owned()),
+            None => Err("The game has no checksum".to_owned()),
+        }
+    }
+
+    fn movetext(&self) -> String {
+        let mut tokens = Vec::new();
+        let comment = |number| {
+            self.comments
+                .get(&number)
+                .map(|comment| format!("{{{comment}}}"))
+        };
+
+        tokens.extend(comment(0));
+        for (index, played) in self.moves.iter().enumerate() {
+            if index % 2 == 0 {
+                tokens.push(format!("{}.", index / 2 + 1));
+            }
+            tokens.push(played.map_or("--".to_owned(), |field| Notation::Standard.format(field)));
+            tokens.extend(comment(index + 1));
+        }
+        tokens.push(self.header("Result").unwrap_or("*").to_owned());
+
+        // Wrap the moves like PGN does, at 80 columns.
+        let mut lines = vec![String::new()];
+        for token in tokens {
+            let line = lines.last_mut().unwrap();
+            if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+                lines.push(token);
+            } else {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&token);
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let standard = STANDARD_HEADERS
+            .iter()
+            .filter_map(|&name| self.header(name).map(|value| (name, value)));
+        let other = self
+            .headers
+            .iter()
+            .filter(|(key, _)| !STANDARD_HEADERS.contains(&key.as_str()))
+            .map(|(key, value)| (key.as_str(), value.as_str()));
+
+        for (key, value) in standard.chain(other) {
+            writeln!(
+                f,
+                "[{key} \"{}\"]",
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", self.movetext())
+    }
+}
+
+fn parse_header(line: &str) -> Result<(String, String), String> {
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .ok_or_else(|| format!("Invalid header `{line}`"))?;
+    let (key, value) = inner
+        .split_once(' ')
+        .ok_or_else(|| format!("Invalid header `{line}`"))?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| format!("Header value must be quoted: `{line}`"))?;
+
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' {
+            chars.next().unwrap_or(c)
+        } else {
+            c
+        });
+    }
+    Ok((key.to_owned(), unescaped))
+}
+
+fn is_result(token: &str) -> bool {
+    token == "*"
+        || token.split_once('-').is_some_and(|(white, black)| {
+            [white, black]
+                .iter()
+                .all(|count| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Whether an archive of format `version` can be read.
+fn check_version(version: &str) -> Result<(), String> {
+    match version.parse::<u32>() {
+        Ok(version) if (1..=VERSION).contains(&version) => Ok(()),
+        Ok(version) if version > VERSION => Err(format!(
+            "The archive has format version {version}, which needs a newer version of reversi"
+        )),
+        _ => Err(format!("Invalid archive version `{version}`")),
+    }
+}
+
+/// Parse all games of an archive.
+pub fn parse(archive: &str) -> Result<Vec<Game>, String> {
+    let mut games = Vec::new();
+    let mut game = Game::new();
+    let mut in_moves = false;
+    let mut comment: Option<String> = None;
+
+    for (number, line) in archive
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+    {
+        let context = |error: String| format!("line {number}: {error}");
+
+        if let Some(escape) = line.strip_prefix('%').filter(|_| comment.is_none()) {
+            if let Some(version) = escape.trim().strip_prefix("version") {
+                check_version(version.trim()).map_err(context)?;
+            }
+            continue;
+        }
+
+        if line.starts_with('[') && comment.is_none() {
+            if in_moves {
+                return Err(context("Headers must come before the moves".to_owned()));
+            }
+            game.headers.push(parse_header(line).map_err(context)?);
+            continue;
+        }
+
+        let is_move_number = |token: &str| {
+            token
+                .strip_suffix('.')
+                .is_some_and(|number| number.chars().all(|c| c.is_ascii_digit()))
+        };
+
+        let mut rest = line;
+        while !rest.is_empty() {
+            if let Some(text) = &mut comment {
+                // Comments may span several lines.
+                if let Some((end, after)) = rest.split_once('}') {
+                    text.push_str(end);
+                    game.set_comment(game.moves.len(), &std::mem::take(text));
+                    comment = None;
+                    rest = after.trim_start();
+                } else {
+                    text.push_str(rest);
+                    text.push(' ');
+                    rest = "";
+                }
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix('{') {
+                in_moves = true;
+                comment = Some(String::new());
+                rest = after;
+                continue;
+            }
+
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '{')
+                .unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = rest[end..].trim_start();
+
+            if is_move_number(token) {
+                continue;
+            }
+            in_moves = true;
+            if is_result(token) {
+                if game.header("Result").is_none() {
+                    game.set_header("Result", token);
+                }
+                games.push(std::mem::take(&mut game));
+                in_moves = false;
+            } else if token == "--" || token == "pass" {
+                game.moves.push(None);
+            } else {
+                let field = Notation::Standard
+                    .parse(token)
+                    .map_err(|error| context(format!("Invalid move `{token}`: {error}")))?;
+                game.moves.push(Some(field));
+            }
+        }
+    }
+
+    if comment.is_some() {
+        return Err("Unterminated comment".to_owned());
+    }
+    if !game.headers.is_empty() || !game.moves.is_empty() {
+        return Err("The last game is missing its result".to_owned());
+    }
+
+    Ok(games)
+}
+
+/// Write games as an archive of the current `VERSION`.
+pub fn write(games: &[Game]) -> String {
+    let games = games
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("% version {VERSION}\n{games}")
+}
@@ -1,16 +1,29 @@
 #![allow(clippy::module_name_repetitions)]
 
 pub mod display;
+pub mod notation;
+pub mod report;
+pub mod symmetry;
+pub mod text_direction;
+pub mod tracked;
 
-pub use display::{animate_between, animate_by, animate_results, redraw_board, DisplayOptions};
+pub use display::{
+    animate_between, animate_by, animate_results, redraw_board, region_marks, DisplayOptions,
+    Hints, Layout, Theme,
+};
+pub use notation::Notation;
+pub use report::{Direction, MoveReport};
+pub use symmetry::Symmetry;
+pub use text_direction::TextDirection;
+pub use tracked::TrackedBoard;
 
 use crate::reversi::Color;
 
 use std::{
     cmp::Ordering::{Equal, Greater, Less},
     error::Error,
-    fmt,
-    ops::{Deref, DerefMut, Index, IndexMut, Not},
+    fmt::{self, Write as _},
+    ops::Index,
     str::FromStr,
 };
 
@@ -27,10 +40,82 @@ impl Field {
     /// assert!(Field(7, 5).in_bounds());
     /// assert!(!Field(3, 8).in_bounds());
     /// ```
-    pub fn in_bounds(&self) -> bool {
+    pub const fn in_bounds(&self) -> bool {
         self.0 < 8 && self.1 < 8
     }
 
+    /// The bit of the field in a bitboard, see `Board::bitboard`.
+    ///
+    /// # Panics
+    /// If the field is out of bounds, see `index`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Field;
+    /// assert_eq!(Field(3, 4).bit(), 1 << 35);
+    /// ```
+    pub const fn bit(&self) -> u64 {
+        1 << self.index()
+    }
+
+    /// The number of the field's bit in a bitboard, `8 * y + x`.
+    ///
+    /// # Panics
+    /// If the field is out of bounds, which would otherwise name another field or none.
+    pub const fn index(&self) -> usize {
+        assert!(self.in_bounds(), "the field is out of bounds");
+        8 * self.1 + self.0
+    }
+
+    /// The field of the bit `index` in a bitboard, see `index`.
+    ///
+    /// # Panics
+    /// If `index` is 64 or more.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Field;
+    /// assert_eq!(Field::from_index(35), Field(3, 4));
+    /// assert_eq!(Field::from_index(Field(6, 1).index()), Field(6, 1));
+    /// ```
+    pub const fn from_index(index: usize) -> Self {
+        assert!(index < 64, "a board has 64 fields");
+        Field(index % 8, index / 8)
+    }
+
+    /// The fields whose bits are set in `mask`, in the order of the bits, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color, Field};
+    /// let moves = Board::new().valid_moves_mask(Color::White);
+    /// assert_eq!(Field::in_mask(moves).count(), 4);
+    /// assert_eq!(Field::in_mask(moves).next(), Some(Field(4, 2)));
+    /// ```
+    pub fn in_mask(mut mask: u64) -> impl Iterator<Item = Field> {
+        std::iter::from_fn(move || {
+            let index = mask.trailing_zeros() as usize;
+            mask &= mask.wrapping_sub(1);
+            (index < 64).then(|| Field::from_index(index))
+        })
+    }
+
+    /// The field `dx` columns to the right and `dy` rows down, if it is on the board.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Field;
+    /// assert_eq!(Field(3, 4).offset(-1, 2), Some(Field(2, 6)));
+    /// assert_eq!(Field(0, 4).offset(-1, 0), None);
+    /// assert_eq!(Field(7, 7).offset(0, 1), None);
+    /// ```
+    pub const fn offset(&self, dx: isize, dy: isize) -> Option<Field> {
+        match (self.0.checked_add_signed(dx), self.1.checked_add_signed(dy)) {
+            (Some(x), Some(y)) if x < 8 && y < 8 => Some(Field(x, y)),
+            _ => None,
+        }
+    }
+
     /// Return all possible fields that are in bounds.
     ///
     /// # Examples
@@ -49,45 +134,59 @@ impl Field {
             .valid_moves(Color::White)
             .get(index)
             .ok_or(PlaceError::OutOfBounds)
-            .map(|&field| field)
+            .copied()
     }
 
-    pub fn neighbors(&self) -> Vec<Self> {
-        let mut neighbors = Vec::new();
-
-        for delta_x in [-1_i8, 0, 1] {
-            for delta_y in [-1_i8, 0, 1] {
-                let (x, y) = (self.0 as i8 + delta_x, self.1 as i8 + delta_y);
-                let (x, y) = (x.try_into(), y.try_into());
-
-                let (x, y) = match (x, y) {
-                    (Ok(x), Ok(y)) => (x, y),
-                    _ => continue,
-                };
+    /// The up to eight fields around this one, also diagonally, from a precomputed table.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Field;
+    /// assert_eq!(Field(0, 0).neighbors(), [Field(0, 1), Field(1, 0), Field(1, 1)]);
+    /// assert_eq!(Field(3, 4).neighbors().len(), 8);
+    /// ```
+    pub fn neighbors(&self) -> &'static [Self] {
+        let (fields, count) = &NEIGHBORS[8 * self.1 + self.0];
+        &fields[..*count]
+    }
+}
 
-                let neighbor = Field(x, y);
-                if neighbor.in_bounds() {
-                    neighbors.push(neighbor);
+/// The fields of `Field::neighbors` for each field in the order `8 * y + x`,
+/// with the number of neighbors.
+static NEIGHBORS: [([Field; 8], usize); 64] = {
+    let mut neighbors = [([Field(0, 0); 8], 0); 64];
+    let mut index = 0;
+    while index < 64 {
+        let field = Field(index % 8, index / 8);
+        let (fields, count) = &mut neighbors[index];
+        let mut dx = -1;
+        while dx <= 1 {
+            let mut dy = -1;
+            while dy <= 1 {
+                if let (Some(neighbor), false) = (field.offset(dx, dy), dx == 0 && dy == 0) {
+                    fields[*count] = neighbor;
+                    *count += 1;
                 }
+                dy += 1;
             }
+            dx += 1;
         }
-
-        neighbors
+        index += 1;
     }
-}
+    neighbors
+};
 
-impl ToString for Field {
-    fn to_string(&self) -> String {
-        assert!(self.in_bounds());
-        ('a'..='h').nth(self.0).unwrap().to_string() + &(8 - self.1).to_string()
+/// Writes the field in the current `Notation`.
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Notation::current().format(*self))
     }
 }
 
 impl FromStr for Field {
     type Err = PlaceError;
 
-    /// Parse a field from a string.
-    /// The string must be in the format `a8` or `h1`.
+    /// Parse a field in the current `Notation`, e.g. `a8` or `h1`.
     ///
     /// # Examples
     /// ```
@@ -100,23 +199,7 @@ impl FromStr for Field {
     /// assert_eq!(field2, Field(7, 7));
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
-        let x = chars.next().ok_or(PlaceError::InvalidLength)?;
-        let y = chars
-            .next()
-            .ok_or(PlaceError::InvalidLength)
-            .map(|c| c.to_digit(10).ok_or(PlaceError::InvalidNumber))?;
-        let y: usize = y?.try_into().map_err(|_| PlaceError::InvalidNumber)?;
-        if chars.next().is_some() {
-            Err(PlaceError::InvalidLength)
-        } else {
-            Ok(Self(
-                ('a'..='h')
-                    .position(|c| c == x)
-                    .ok_or(PlaceError::OutOfBounds)?,
-                usize::checked_sub(8, y).ok_or(PlaceError::OutOfBounds)?,
-            ))
-        }
+        Notation::current().parse(s)
     }
 }
 
@@ -127,6 +210,11 @@ pub enum PlaceError {
     Occupied,
     OutOfBounds,
     CapturesNone,
+    /// The first four discs of the classic Reversi opening go on the center fields.
+    OutsideCenter,
+    /// The side to move has a valid move, so it can't pass.
+    CannotPass,
+    GameOver,
 }
 
 impl fmt::Display for PlaceError {
@@ -137,12 +225,26 @@ impl fmt::Display for PlaceError {
             PlaceError::Occupied => write!(f, "Field is already occupied"),
             PlaceError::OutOfBounds => write!(f, "Field is out of bounds"),
             PlaceError::CapturesNone => write!(f, "Field captures no pieces"),
+            PlaceError::OutsideCenter => write!(f, "The first four discs go on the center fields"),
+            PlaceError::CannotPass => write!(f, "Passing is only allowed without a valid move"),
+            PlaceError::GameOver => write!(f, "The game is over"),
         }
     }
 }
 
 impl Error for PlaceError {}
 
+/// How a field differs from one board to another, see `Board::diff`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FieldChange {
+    /// A disc of the color is on a field that was empty.
+    Added(Color),
+    /// The disc of the color is gone.
+    Removed(Color),
+    /// The disc was turned over to the color.
+    Flipped(Color),
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum GameStatus {
     InProgress,
@@ -150,8 +252,39 @@ pub enum GameStatus {
     Draw,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-pub struct Board(pub [[Option<Color>; 8]; 8]);
+/// The discs of each color as a bitboard, see `Board::bitboard`.
+#[derive(Eq, PartialEq, Clone, Hash)]
+pub struct Board {
+    white: u64,
+    black: u64,
+}
+
+/// The `index`th output of `SplitMix64` seeded with 0, counting from 0.
+const fn split_mix_64(index: u64) -> u64 {
+    let mut key = 0x9e37_79b9_7f4a_7c15_u64.wrapping_mul(index + 1);
+    key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    key ^ (key >> 31)
+}
+
+/// The keys of `Board::position_hash`: the `SplitMix64` sequence seeded with 0,
+/// two per field in the order `8 * y + x`, White's first.
+const POSITION_KEYS: [[u64; 2]; 64] = {
+    let mut keys = [[0; 2]; 64];
+    let mut index = 0;
+    while index < 128 {
+        keys[index / 2][index % 2] = split_mix_64(index as u64);
+        index += 1;
+    }
+    keys
+};
+
+/// The key of `Position::position_hash` for Black to move, the output of `SplitMix64` right
+/// after the `POSITION_KEYS`.
+pub(crate) const BLACK_TO_MOVE_KEY: u64 = split_mix_64(128);
+
+/// The four fields in the middle of the board as a bitboard.
+const CENTER: u64 = 1 << 27 | 1 << 28 | 1 << 35 | 1 << 36;
 
 impl Board {
     pub fn new() -> Self {
@@ -159,25 +292,276 @@ impl Board {
 
         for x in 3..=4 {
             for y in 3..=4 {
-                new_board[Field(x, y)] = match (x + y) % 2 {
-                    0 => Some(Color::White),
-                    1 => Some(Color::Black),
-                    _ => unreachable!(),
-                }
+                new_board.set(
+                    Field(x, y),
+                    match (x + y) % 2 {
+                        0 => Some(Color::White),
+                        1 => Some(Color::Black),
+                        _ => unreachable!(),
+                    },
+                );
             }
         }
 
         new_board
     }
 
-    /// Returns a new empty board.
+    /// Returns a new empty board, the start of the classic Reversi opening, see
+    /// `in_placement_phase`.
     pub fn empty() -> Self {
-        Board([[None; 8]; 8])
+        Board { white: 0, black: 0 }
+    }
+
+    /// The discs of `color` as a bitboard: bit `8 * y + x` is set if `Field(x, y)` holds one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color};
+    /// assert_eq!(Board::new().bitboard(Color::Black), 1 << 28 | 1 << 35);
+    /// ```
+    pub fn bitboard(&self, color: Color) -> u64 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    /// The board with the discs of the bitboards `white` and `black`, see `bitboard`.
+    ///
+    /// # Panics
+    /// If a field holds discs of both colors.
+    pub fn from_bitboards(white: u64, black: u64) -> Self {
+        assert_eq!(white & black, 0, "a field can't hold two discs");
+        Board { white, black }
+    }
+
+    /// The board in 16 bytes, to store many positions cheaply: White's bitboard and then
+    /// Black's, each in little-endian order, see `bitboard`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Board;
+    /// let bytes = Board::new().to_bytes();
+    /// assert_eq!(bytes[3..5], [0x08, 0x10]);
+    /// assert_eq!(Board::from_bytes(bytes), Ok(Board::new()));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&self.white.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.black.to_le_bytes());
+        bytes
+    }
+
+    /// The board of bytes written by `to_bytes`, or an error if a field holds discs of both
+    /// colors.
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, String> {
+        let (white, black) = bytes.split_at(8);
+        let white = u64::from_le_bytes(white.try_into().unwrap());
+        let black = u64::from_le_bytes(black.try_into().unwrap());
+        if white & black != 0 {
+            return Err(format!(
+                "Fields hold discs of both colors: {}",
+                Field::in_mask(white & black)
+                    .map(|field| field.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        Ok(Board { white, black })
+    }
+
+    fn bitboard_mut(&mut self, color: Color) -> &mut u64 {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+
+    /// Put a disc of a color on a field or remove it with `None`, without any captures.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Field, Color};
+    /// let mut board = Board::empty();
+    /// board.set(Field(0, 0), Some(Color::White));
+    /// assert_eq!(board[Field(0, 0)], Some(Color::White));
+    /// board.set(Field(0, 0), None);
+    /// assert_eq!(board, Board::empty());
+    /// ```
+    pub fn set(&mut self, field: Field, piece: Option<Color>) {
+        self.white &= !field.bit();
+        self.black &= !field.bit();
+        if let Some(color) = piece {
+            *self.bitboard_mut(color) |= field.bit();
+        }
+    }
+
+    /// The pieces by row, `rows()[y][x]` being the piece on `Field(x, y)`, like the array the
+    /// board was before version 0.4. `Board::from` turns them back into a board.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color};
+    /// let rows = Board::new().rows();
+    /// assert_eq!(rows[3][3], Some(Color::White));
+    /// assert_eq!(rows[3][4], Some(Color::Black));
+    /// assert_eq!(Board::from(rows), Board::new());
+    /// ```
+    pub fn rows(&self) -> [[Option<Color>; 8]; 8] {
+        let mut rows = [[None; 8]; 8];
+        for field in Field::all() {
+            rows[field.1][field.0] = self[field];
+        }
+        rows
+    }
+
+    /// The empty fields as a bitboard.
+    fn empty_fields(&self) -> u64 {
+        !(self.white | self.black)
+    }
+
+    /// The fields that differ on `other`, with how, in the order of their bits, e.g. to find
+    /// where two copies of a game went out of sync.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{board::FieldChange, Board, Color, Field};
+    /// let mut after = Board::new();
+    /// after.add_piece(Field(3, 5), Color::White).unwrap();
+    /// assert_eq!(
+    ///     Board::new().diff(&after),
+    ///     [
+    ///         (Field(3, 4), FieldChange::Flipped(Color::White)),
+    ///         (Field(3, 5), FieldChange::Added(Color::White)),
+    ///     ]
+    /// );
+    /// assert!(after.diff(&after).is_empty());
+    /// ```
+    pub fn diff(&self, other: &Board) -> Vec<(Field, FieldChange)> {
+        let changed = (self.white ^ other.white) | (self.black ^ other.black);
+        Field::in_mask(changed)
+            .map(|field| {
+                let change = match (self[field], other[field]) {
+                    (None, Some(color)) => FieldChange::Added(color),
+                    (Some(color), None) => FieldChange::Removed(color),
+                    (Some(_), Some(color)) => FieldChange::Flipped(color),
+                    (None, None) => unreachable!("the field changed"),
+                };
+                (field, change)
+            })
+            .collect()
+    }
+
+    /// Read a hand-drawn board, e.g. from a book or a forum post: eight rows from the top
+    /// down with one character per field, `X` (or `B`, `*`) for Black, `O` (or `W`) for White
+    /// and `.` (or `-`, `_`) for an empty field. Spaces, row numbers and a line of column
+    /// letters are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::Board;
+    /// let diagram = "
+    ///       a b c d e f g h
+    ///     8 . . . . . . . .
+    ///     7 . . . . . . . .
+    ///     6 . . . . . . . .
+    ///     5 . . . O X . . .
+    ///     4 . . . X O . . .
+    ///     3 . . . . . . . .
+    ///     2 . . . . . . . .
+    ///     1 . . . . . . . .
+    /// ";
+    /// assert_eq!(Board::from_ascii(diagram), Ok(Board::new()));
+    /// assert_eq!(Board::from_ascii(&Board::new().to_ascii()), Ok(Board::new()));
+    /// assert!(Board::from_ascii("........").is_err());
+    /// ```
+    pub fn from_ascii(diagram: &str) -> Result<Self, String> {
+        let is_column_letters = |line: &str| {
+            line.chars()
+                .all(|c| c.is_whitespace() || ('a'..='h').contains(&c))
+        };
+        let rows: Vec<&str> = diagram
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !is_column_letters(line))
+            .collect();
+        if rows.len() != 8 {
+            return Err(format!("Expected 8 rows, got {}", rows.len()));
+        }
+
+        let mut board = Board::empty();
+        for (y, row) in rows.into_iter().enumerate() {
+            let fields: Vec<Option<Color>> = row
+                .chars()
+                .filter(|c| !c.is_whitespace() && !c.is_ascii_digit())
+                .map(|c| match c {
+                    'X' | 'x' | 'B' | '*' => Ok(Some(Color::Black)),
+                    'O' | 'o' | 'W' => Ok(Some(Color::White)),
+                    '.' | '-' | '_' => Ok(None),
+                    other => Err(format!("Invalid field `{other}` in row `{row}`")),
+                })
+                .collect::<Result<_, _>>()?;
+            if fields.len() != 8 {
+                return Err(format!("Expected 8 fields in row `{row}`"));
+            }
+            for (x, piece) in fields.into_iter().enumerate() {
+                board.set(Field(x, y), piece);
+            }
+        }
+        Ok(board)
+    }
+
+    /// The board as eight lines of `X`, `O` and `.` from the top down, see `from_ascii`.
+    pub fn to_ascii(&self) -> String {
+        (0..8)
+            .map(|y| {
+                (0..8)
+                    .map(|x| match self[Field(x, y)] {
+                        Some(Color::Black) => 'X',
+                        Some(Color::White) => 'O',
+                        None => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A 64-bit hash of the discs on the board that is the same in every process, build and
+    /// platform, so two copies of a game can cheaply check that they are still in sync.
+    ///
+    /// This is a Zobrist hash: the XOR of one fixed key per occupied field and disc color.
+    /// The keys are the outputs of `SplitMix64` seeded with 0, two per field in the order
+    /// `8 * y + x`, first for White and then for Black. They will not change; the empty
+    /// board hashes to 0.
+    ///
+    /// Only the discs are hashed, not whose turn it is, which doesn't follow from the discs
+    /// after a pass. To also catch a desync of the side to move, use `Position::position_hash`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Field, Color};
+    /// let mut board = Board::new();
+    /// assert_eq!(board.position_hash(), Board::new().position_hash());
+    /// assert_eq!(Board::empty().position_hash(), 0);
+    ///
+    /// board.add_piece(Field(2, 4), Color::White).unwrap();
+    /// assert_ne!(board.position_hash(), Board::new().position_hash());
+    /// ```
+    pub fn position_hash(&self) -> u64 {
+        (0..64)
+            .flat_map(|index| {
+                [(self.white, 0), (self.black, 1)]
+                    .into_iter()
+                    .filter(move |(bits, _)| bits >> index & 1 == 1)
+                    .map(move |(_, color)| POSITION_KEYS[index][color])
+            })
+            .fold(0, |hash, key| hash ^ key)
     }
 
     /// Flip a piece on the board.
     fn flip(&mut self, field: Field) {
-        self[field] = self[field].map(Color::other);
+        self.set(field, self[field].map(Color::other));
     }
 
     /// Count the amount of pieces of a given color.
@@ -192,12 +576,10 @@ impl Board {
     /// assert_eq!(board.count_pieces(Color::Black), 1);
     /// ```
     pub fn count_pieces(&self, color: Color) -> usize {
-        Field::all()
-            .filter(|&field| self[field] == Some(color))
-            .count()
+        self.bitboard(color).count_ones() as usize
     }
 
-    /// Check whose turn it is.
+    /// Check whose turn it is, guessed from the number of discs, which is wrong after a pass.
     ///
     /// # Examples
     /// ```
@@ -207,8 +589,9 @@ impl Board {
     /// board.add_piece(Field(2, 4), Color::White);
     /// assert_eq!(board.turn(), Color::Black);
     /// ```
+    #[deprecated(note = "passes don't change the discs, use `Game::current_player` instead")]
     pub fn turn(&self) -> Color {
-        match Field::all().filter(|&field| self[field].is_some()).count() % 2 {
+        match (self.white | self.black).count_ones() % 2 {
             0 => Color::White,
             1 => Color::Black,
             _ => unreachable!(),
@@ -227,6 +610,27 @@ impl Board {
         }
     }
 
+    /// Whether the first discs are still being placed, as in the classic Reversi opening:
+    /// starting from an empty board, the players take turns putting a disc on any empty center
+    /// field without capturing, until all four are taken. Boards with discs outside the center
+    /// follow the usual rules.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color, Field};
+    /// let mut board = Board::empty();
+    /// assert!(board.in_placement_phase());
+    /// assert_eq!(board.valid_moves(Color::White).len(), 4);
+    ///
+    /// assert_eq!(board.add_piece(Field(3, 3), Color::White), Ok(vec![]));
+    /// assert!(board.add_piece(Field(0, 0), Color::Black).is_err());
+    /// assert!(!Board::new().in_placement_phase());
+    /// ```
+    pub fn in_placement_phase(&self) -> bool {
+        let discs = self.white | self.black;
+        discs & !CENTER == 0 && discs != CENTER
+    }
+
     /// Check for the game status.
     ///
     /// # Examples
@@ -236,7 +640,14 @@ impl Board {
     /// assert_eq!(board.status(), GameStatus::InProgress);
     /// ```
     pub fn status(&self) -> GameStatus {
-        if Field::all().all(|field| self[field].is_some()).not() {
+        self.status_with_moves(|color| self.valid_moves_mask(color))
+    }
+
+    /// The game status, with the valid moves taken from `moves`, see `TrackedBoard`.
+    fn status_with_moves(&self, moves: impl Fn(Color) -> u64) -> GameStatus {
+        if self.in_placement_phase() {
+            GameStatus::InProgress
+        } else if self.empty_fields() != 0 {
             match (
                 self.count_pieces(Color::White),
                 self.count_pieces(Color::Black),
@@ -244,9 +655,7 @@ impl Board {
                 (0, _) => GameStatus::Win(Color::Black),
                 (_, 0) => GameStatus::Win(Color::White),
                 _ => {
-                    if self.valid_moves(Color::White).is_empty()
-                        && self.valid_moves(Color::Black).is_empty()
-                    {
+                    if moves(Color::White) == 0 && moves(Color::Black) == 0 {
                         self.final_status()
                     } else {
                         GameStatus::InProgress
@@ -258,6 +667,80 @@ impl Board {
         }
     }
 
+    /// The valid moves of `color` as a bitboard, to enumerate or count them without allocating,
+    /// see `Field::in_mask`.
+    ///
+    /// In each direction, the opponent's discs next to `color`'s are followed step by step
+    /// with shifts; the empty fields right behind such a line are moves capturing it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color, Field};
+    /// let board = Board::new();
+    /// let moves = board.valid_moves_mask(Color::Black);
+    /// assert_eq!(moves.count_ones(), 4);
+    /// assert_ne!(moves & Field(3, 2).bit(), 0);
+    /// ```
+    pub fn valid_moves_mask(&self, color: Color) -> u64 {
+        if self.in_placement_phase() {
+            return CENTER & self.empty_fields();
+        }
+        let (own, opponent) = (self.bitboard(color), self.bitboard(color.other()));
+        let mut moves = 0;
+        for direction in Direction::ALL {
+            let mut line = direction.shift(own) & opponent;
+            // A line has at most six discs.
+            for _ in 0..5 {
+                line |= direction.shift(line) & opponent;
+            }
+            moves |= direction.shift(line);
+        }
+        moves & self.empty_fields()
+    }
+
+    /// The discs of the opponent captured by `color` on the empty `field` in `direction`,
+    /// as a bitboard.
+    fn captures(&self, field: Field, color: Color, direction: Direction) -> u64 {
+        let (own, opponent) = (self.bitboard(color), self.bitboard(color.other()));
+        let mut line = 0;
+        let mut next = direction.shift(field.bit());
+        while next & opponent != 0 {
+            line |= next;
+            next = direction.shift(next);
+        }
+        if next & own == 0 {
+            0
+        } else {
+            line
+        }
+    }
+
+    /// Walk from `field` in `direction` and return the opponent's discs `color` would capture
+    /// there, nearest first: the line of them next to the field if a disc of `color` closes
+    /// it, otherwise none. Whether the field itself is empty isn't checked.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::reversi::{*, board::report::Direction};
+    /// let board = Board::new();
+    /// let d3 = "d3".parse().unwrap();
+    /// assert_eq!(board.captures_towards(d3, Color::White, Direction::North), ["d4".parse().unwrap()]);
+    /// assert!(board.captures_towards(d3, Color::White, Direction::East).is_empty());
+    /// assert!(board.captures_towards(Field(8, 0), Color::White, Direction::West).is_empty());
+    /// ```
+    pub fn captures_towards(
+        &self,
+        field: Field,
+        color: Color,
+        direction: Direction,
+    ) -> &'static [Field] {
+        if !field.in_bounds() {
+            return &[];
+        }
+        let line = self.captures(field, color, direction).count_ones() as usize;
+        &direction.ray_fields(field)[..line]
+    }
+
     /// Check if a given move is valid.
     ///
     /// # Returns
@@ -272,47 +755,143 @@ impl Board {
             Err(PlaceError::Occupied)?;
         }
 
-        if field.neighbors().iter().all(|&field| self[field].is_none()) {
-            Err(PlaceError::CapturesNone)?;
+        if self.in_placement_phase() {
+            return if CENTER & field.bit() == 0 {
+                Err(PlaceError::OutsideCenter)
+            } else {
+                Ok(Vec::new())
+            };
         }
 
-        let captured_pieces: Vec<Field> = Field::all()
-            .filter(|&other| self[other] == Some(color)) // needs to be the same color
-            .filter_map(|other| Board::line_between((field, other))) // a line between the two
-            // fields has to exist
-            .filter(|line| line.iter().all(|&field| self[field] == Some(color.other())))
-            .flatten()
-            .collect();
+        // The opponent's discs next to the field in a direction are captured if they are
+        // followed by a disc of `color`.
+        let mut captured_pieces = Vec::new();
+        for direction in Direction::ALL {
+            captured_pieces.extend_from_slice(self.captures_towards(field, color, direction));
+        }
 
         if captured_pieces.is_empty() {
             Err(PlaceError::CapturesNone)?;
         }
 
-        for piece in &captured_pieces {
-            let mut counter = 0;
-            for other_piece in &captured_pieces {
-                if other_piece == piece {
-                    counter += 1;
-                }
-            }
-            assert!(counter == 1, "Captured pieces are not unique");
-        }
-
         Ok(captured_pieces)
     }
 
     /// Check if a given move is valid.
     pub fn is_valid(&self, field: Field, color: Color) -> bool {
-        self.move_validity(field, color).is_ok()
+        field.in_bounds() && self.valid_moves_mask(color) & field.bit() != 0
+    }
+
+    /// The valid moves of `color` as rows of fields, `map[y][x]` for `Field(x, y)`, for a UI to
+    /// look up every field when it draws the board, see `valid_moves_mask`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color};
+    /// let map = Board::new().legal_move_map(Color::White);
+    /// assert!(map[4][2] && map[5][3]);
+    /// assert_eq!(map.iter().flatten().filter(|&&valid| valid).count(), 4);
+    /// ```
+    pub fn legal_move_map(&self, color: Color) -> [[bool; 8]; 8] {
+        let moves = self.valid_moves_mask(color);
+        std::array::from_fn(|y| std::array::from_fn(|x| moves & Field(x, y).bit() != 0))
     }
 
     /// Return all valid moves a given color can make.
     pub fn valid_moves(&self, color: Color) -> Vec<Field> {
+        let moves = self.valid_moves_mask(color);
         Field::all()
-            .filter(|&field| self.move_validity(field, color).is_ok())
+            .filter(|field| moves & field.bit() != 0)
             .collect()
     }
 
+    /// Return the number of valid moves a given color can make.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::reversi::*;
+    /// let board = Board::new();
+    /// assert_eq!(board.mobility(Color::White), 4);
+    ///
+    /// let mut corner = Board::empty();
+    /// corner.set(Field(0, 0), Some(Color::Black));
+    /// assert_eq!(corner.mobility(Color::Black), 0);
+    /// ```
+    pub fn mobility(&self, color: Color) -> usize {
+        self.valid_moves_mask(color).count_ones() as usize
+    }
+
+    /// Return the number of discs `color` could flip with its next move, counting each disc
+    /// once even if several moves flip it. Evaluations of positions where this is high are
+    /// unreliable, as the next move changes a lot.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::reversi::*;
+    /// assert_eq!(Board::new().volatility(Color::White), 2);
+    /// assert_eq!(Board::empty().volatility(Color::White), 0);
+    /// ```
+    pub fn volatility(&self, color: Color) -> usize {
+        self.volatility_with_moves(color, self.valid_moves_mask(color))
+    }
+
+    /// The volatility with the valid moves of `color` taken from `moves`, see `TrackedBoard`.
+    fn volatility_with_moves(&self, color: Color, moves: u64) -> usize {
+        let mut flippable = 0;
+        for field in Field::in_mask(moves) {
+            for direction in Direction::ALL {
+                flippable |= self.captures(field, color, direction);
+            }
+        }
+        flippable.count_ones() as usize
+    }
+
+    /// Return the regions of empty fields, where fields touching each other (also diagonally)
+    /// belong to the same region. Late in the game, whoever moves last in a region often
+    /// gets to keep its discs, so the regions with an odd number of fields matter most.
+    ///
+    /// The regions are ordered by their first field, which is in `Field::all` order, too.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::reversi::*;
+    /// assert_eq!(Board::new().empty_regions().len(), 1);
+    ///
+    /// let mut board = Board::empty();
+    /// for y in 0..8 {
+    ///     board.set(Field(2, y), Some(Color::White));
+    /// }
+    /// board.set(Field(7, 7), Some(Color::Black));
+    /// let sizes: Vec<usize> = board.empty_regions().iter().map(Vec::len).collect();
+    /// assert_eq!(sizes, [16, 39]);
+    /// ```
+    pub fn empty_regions(&self) -> Vec<Vec<Field>> {
+        let mut seen = [[false; 8]; 8];
+        let mut regions = Vec::new();
+
+        for start in Field::all() {
+            if self[start].is_some() || seen[start.0][start.1] {
+                continue;
+            }
+            seen[start.0][start.1] = true;
+            let mut region = vec![start];
+            let mut index = 0;
+            while let Some(&field) = region.get(index) {
+                for &neighbor in field.neighbors() {
+                    if self[neighbor].is_none() && !seen[neighbor.0][neighbor.1] {
+                        seen[neighbor.0][neighbor.1] = true;
+                        region.push(neighbor);
+                    }
+                }
+                index += 1;
+            }
+            region.sort_by_key(|field| (field.0, field.1));
+            regions.push(region);
+        }
+
+        regions
+    }
+
     /// Add a piece to the board and execute all captures.
     ///
     /// # Returns
@@ -320,89 +899,166 @@ impl Board {
     pub fn add_piece(&mut self, field: Field, color: Color) -> Result<Vec<Field>, PlaceError> {
         let captured_pieces = self.move_validity(field, color)?;
 
-        self.add_piece_unchecked(field, color);
-
-        for &captured_piece in &captured_pieces {
-            self.flip(captured_piece);
-        }
+        let captured = captured_pieces
+            .iter()
+            .fold(0, |captured, field| captured | field.bit());
+        *self.bitboard_mut(color) |= field.bit() | captured;
+        *self.bitboard_mut(color.other()) &= !captured;
 
         Ok(captured_pieces)
     }
 
-    /// Set a field to a color.
-    fn add_piece_unchecked(&mut self, field: Field, color: Color) {
-        self[field] = Some(color);
+    /// The board after `color` placed a piece on `field`, leaving this one unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color, Field, PlaceError};
+    /// let board = Board::new();
+    /// let after = board.with_move(Field(2, 4), Color::White).unwrap();
+    /// assert_eq!(after[Field(3, 4)], Some(Color::White));
+    /// assert_eq!(board[Field(3, 4)], Some(Color::Black));
+    /// assert_eq!(board.with_move(Field(0, 0), Color::White), Err(PlaceError::CapturesNone));
+    /// ```
+    pub fn with_move(&self, field: Field, color: Color) -> Result<Board, PlaceError> {
+        let mut board = self.clone();
+        board.add_piece(field, color)?;
+        Ok(board)
+    }
+
+    pub fn fmt_by_color(&self, f: &mut fmt::Formatter, color: Option<Color>) -> fmt::Result {
+        self.fmt_with_hints(f, color.as_slice())
     }
 
-    /// Calculate a line (horizontal, vertical or diagonal) between two fields.
+    /// Format the board, marking the valid moves of the given colors.
     ///
-    /// # Returns
-    /// A vector of fields that are part of the line, or None if no valid line exists.
-    fn line_between(fields: (Field, Field)) -> Option<Vec<Field>> {
-        let (Field(x1, y1), Field(x2, y2)) = fields;
-
-        let range_x = || x1.min(x2)..=x2.max(x1);
-        let range_y = || y1.min(y2)..=y2.max(y1);
-
-        if x1 == x2 {
-            // Vertical line
-            Some(range_y().map(|y| Field(x1, y)).collect())
-        } else if y1 == y2 {
-            // Horizontal line
-            Some(range_x().map(|x| Field(x, y1)).collect())
-        } else if usize::abs_diff(x1, x2) == usize::abs_diff(y1, y2) {
-            if (x1 > x2 && y1 > y2) || (x1 < x2 && y1 < y2) {
-                // Diagonal line: \
-                Some(
-                    (range_x())
-                        .zip(range_y())
-                        .map(|(x, y)| Field(x, y))
-                        .collect(),
-                )
-            } else {
-                // Diagonal line: /
-                Some(
-                    (range_x())
-                        .zip(range_y().rev())
-                        .map(|(x, y)| Field(x, y))
-                        .collect(),
-                )
-            }
-        } else {
-            // No line
-            None
-        }
-        .and_then(|line: Vec<Field>| if line.len() < 3 { None } else { Some(line) })
-        .map(|line| line[1..line.len() - 1].to_vec())
+    /// If the moves of both colors are marked, each field name is followed by
+    /// the colors (`w`, `b`) that can play there.
+    pub fn fmt_with_hints(&self, f: &mut fmt::Formatter, colors: &[Color]) -> fmt::Result {
+        f.write_str(&self.render(colors, &Theme::default(), None, &[]))
     }
 
-    pub fn fmt_by_color(&self, f: &mut fmt::Formatter, color: Option<Color>) -> fmt::Result {
-        let valid_moves = color.map(|color| self.valid_moves(color));
-        writeln!(f, "╭──{}──╮", "──┬──".repeat(self.len() - 1))?;
-        for y in 0..self.len() {
+    /// Draw the board with the glyphs of `theme`, marking the valid moves of the given colors.
+    ///
+    /// The piece on `flipping` is drawn edge-on, and the empty fields in `marks`
+    /// show their glyph instead of a hint. The board is mirrored in the `current`
+    /// `TextDirection` if it is right to left.
+    pub fn render(
+        &self,
+        colors: &[Color],
+        theme: &Theme,
+        flipping: Option<Field>,
+        marks: &[(Field, String)],
+    ) -> String {
+        let valid_moves: Vec<(Color, [[bool; 8]; 8])> = colors
+            .iter()
+            .map(|&color| (color, self.legal_move_map(color)))
+            .collect();
+
+        let direction = TextDirection::current();
+        let mut out = String::new();
+        let f = &mut out;
+        writeln!(f, "╭──{}──╮", "──┬──".repeat(7)).unwrap();
+        for y in 0..8 {
             if y != 0 {
-                writeln!(f, "├──{}──┤", "──┼──".repeat(self.len() - 1))?;
+                writeln!(f, "├──{}──┤", "──┼──".repeat(7)).unwrap();
             }
-            for x in 0..self.len() {
-                write!(f, "│")?;
-                match self[Field(x, y)] {
-                    Some(color) => write!(f, " {} ", color)?,
-                    None => match valid_moves {
-                        Some(ref moves) if moves.contains(&Field(x, y)) => {
-                            write!(f, " {:2} ", Field(x, y).to_string())?;
-                        }
-                        _ => write!(f, "    ")?,
-                    },
+            for (column, x) in direction.columns().into_iter().enumerate() {
+                f.push('│');
+                let field = Field(x, y);
+                if let Some(color) = self[field] {
+                    let glyph = if flipping == Some(field) {
+                        &theme.edge
+                    } else {
+                        theme.piece(color)
+                    };
+                    write!(f, " {} ", Theme::pad(glyph)).unwrap();
+                } else if let Some((_, mark)) = marks.iter().find(|(marked, _)| *marked == field) {
+                    write!(f, " {} ", Theme::pad(mark)).unwrap();
+                } else {
+                    let markers: String = valid_moves
+                        .iter()
+                        .filter(|(_, map)| map[y][x])
+                        .map(|&(color, _)| char::from(color).to_ascii_lowercase())
+                        .collect();
+                    match (markers.len(), valid_moves.len(), &theme.marker) {
+                        (0, ..) => write!(f, " {} ", Theme::pad(&theme.empty)).unwrap(),
+                        (_, 1, Some(marker)) => write!(f, " {} ", Theme::pad(marker)).unwrap(),
+                        (_, 1, None) => write!(f, " {:2} ", field.to_string()).unwrap(),
+                        _ => write!(f, "{field}{markers:<2}").unwrap(),
+                    }
                 }
-                if x == self.len() - 1 {
-                    write!(f, "│")?;
+                if column == 7 {
+                    f.push('│');
                 }
             }
-            writeln!(f)?;
+            f.push('\n');
         }
-        writeln!(f, "╰──{}──╯", "──┴──".repeat(self.len() - 1))?;
+        writeln!(f, "╰──{}──╯", "──┴──".repeat(7)).unwrap();
 
-        Ok(())
+        direction.align(&out)
+    }
+
+    /// Draw the board with two columns per field and the coordinates at the edges,
+    /// for terminals too narrow for `render`. Right to left, the ranks are on the right.
+    pub fn render_compact(
+        &self,
+        colors: &[Color],
+        theme: &Theme,
+        flipping: Option<Field>,
+        marks: &[(Field, String)],
+    ) -> String {
+        let valid_moves: Vec<[[bool; 8]; 8]> = colors
+            .iter()
+            .map(|&color| self.legal_move_map(color))
+            .collect();
+        let marker = theme.marker.as_deref().unwrap_or("+");
+        let empty = if theme.empty.is_empty() {
+            "."
+        } else {
+            &theme.empty
+        };
+
+        let notation = Notation::current();
+        let direction = TextDirection::current();
+        let files: Vec<String> = direction
+            .columns()
+            .iter()
+            .map(|&x| notation.file(x))
+            .collect();
+        let mut out = match direction {
+            TextDirection::LeftToRight => format!("  {}\n", files.join(" ")),
+            TextDirection::RightToLeft => format!("{}\n", files.join(" ")),
+        };
+        for y in 0..8 {
+            let row: String = direction
+                .columns()
+                .into_iter()
+                .map(|x| {
+                    let field = Field(x, y);
+                    let mark = marks
+                        .iter()
+                        .find(|(marked, _)| *marked == field)
+                        .map(|(_, mark)| mark.as_str());
+                    Theme::pad(match (self[field], mark) {
+                        (Some(_), _) if flipping == Some(field) => &theme.edge,
+                        (Some(color), _) => theme.piece(color),
+                        (None, Some(mark)) => mark,
+                        (None, None) if valid_moves.iter().any(|map| map[y][x]) => marker,
+                        (None, None) => empty,
+                    })
+                })
+                .collect();
+            match direction {
+                TextDirection::LeftToRight => {
+                    writeln!(out, "{} {}", notation.rank(y), row.trim_end()).unwrap();
+                }
+                TextDirection::RightToLeft => {
+                    writeln!(out, "{row}{}", notation.rank(y)).unwrap();
+                }
+            }
+        }
+
+        direction.align(&out)
     }
 
     /// Sorts the board for displaying purposes.
@@ -411,7 +1067,7 @@ impl Board {
             self.count_pieces(Color::White),
             self.count_pieces(Color::Black),
         );
-        let none_count = self.len() * self.len() - white_count - black_count;
+        let none_count = 64 - white_count - black_count;
 
         for (index, field) in Field::all()
             .map(|field| Field(field.1, field.0))
@@ -419,11 +1075,11 @@ impl Board {
             .enumerate()
         {
             if index < white_count {
-                self[field] = Some(Color::White);
+                self.set(field, Some(Color::White));
             } else if index < white_count + none_count {
-                self[field] = None;
+                self.set(field, None);
             } else {
-                self[field] = Some(Color::Black);
+                self.set(field, Some(Color::Black));
             }
         }
     }
@@ -433,13 +1089,31 @@ impl Index<Field> for Board {
     type Output = Option<Color>;
 
     fn index(&self, field: Field) -> &Self::Output {
-        &self.0[field.1][field.0]
+        if self.white & field.bit() != 0 {
+            &Some(Color::White)
+        } else if self.black & field.bit() != 0 {
+            &Some(Color::Black)
+        } else {
+            &None
+        }
+    }
+}
+
+/// A board with the pieces by row, see `Board::rows`.
+impl From<[[Option<Color>; 8]; 8]> for Board {
+    fn from(rows: [[Option<Color>; 8]; 8]) -> Self {
+        let mut board = Board::empty();
+        for field in Field::all() {
+            board.set(field, rows[field.1][field.0]);
+        }
+        board
     }
 }
 
-impl IndexMut<Field> for Board {
-    fn index_mut(&mut self, field: Field) -> &mut Self::Output {
-        &mut self.0[field.1][field.0]
+/// Shows the board like `to_ascii`, with the rows separated by `/`.
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Board({})", self.to_ascii().replace('\n', "/"))
     }
 }
 
@@ -449,6 +1123,7 @@ impl fmt::Display for Board {
         match f.fill() {
             'w' => self.fmt_by_color(f, Some(Color::White))?,
             'b' => self.fmt_by_color(f, Some(Color::Black))?,
+            '*' => self.fmt_with_hints(f, &[Color::White, Color::Black])?,
             _ => self.fmt_by_color(f, None)?,
         }
 
@@ -461,17 +1136,3 @@ impl Default for Board {
         Board::new()
     }
 }
-
-impl Deref for Board {
-    type Target = [[Option<Color>; 8]; 8];
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for Board {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
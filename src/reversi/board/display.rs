@@ -1,20 +1,121 @@
+pub mod style;
+pub mod theme;
+
+pub use theme::Theme;
+
 use crate::reversi::{Board, Color, Field};
+use style::Colorize;
 
-use std::time::Duration;
+use std::{
+    env,
+    process::{Command, Stdio},
+    time::Duration,
+};
 
-use colored::Colorize;
+#[cfg(feature = "animation")]
 use itertools::Itertools;
+#[cfg(feature = "animation")]
 use split_iter::Splittable;
 
+/// Which valid moves are marked on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hints {
+    /// Don't mark any moves.
+    Off,
+    /// Mark the moves of `DisplayOptions::color`.
+    Color,
+    /// Mark the moves of both colors.
+    Both,
+}
+
+/// How the board is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Use the full layout if the terminal is wide enough, the compact one otherwise.
+    Auto,
+    /// A grid with five columns per field.
+    Full,
+    /// Two columns per field, for narrow terminals.
+    Compact,
+}
+
+impl Layout {
+    /// The number of columns needed by the full layout.
+    const FULL_WIDTH: usize = 8 * 5 + 1;
+
+    fn is_compact(self) -> bool {
+        match self {
+            Layout::Auto => terminal_width().is_some_and(|width| width < Self::FULL_WIDTH),
+            Layout::Full => false,
+            Layout::Compact => true,
+        }
+    }
+}
+
+/// Marks showing the size of its empty region on each empty field, for
+/// `DisplayOptions::marks`. See `Board::empty_regions`.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{board::region_marks, Board, Field};
+/// let marks = region_marks(&Board::new());
+/// assert_eq!(marks.len(), 60);
+/// assert!(marks.contains(&(Field(0, 0), "60".to_owned())));
+/// ```
+pub fn region_marks(board: &Board) -> Vec<(Field, String)> {
+    board
+        .empty_regions()
+        .into_iter()
+        .flat_map(|region| {
+            let size = region.len().to_string();
+            region.into_iter().map(move |field| (field, size.clone()))
+        })
+        .collect()
+}
+
+/// The width of the terminal, from `$COLUMNS` or `stty size`.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+    {
+        return Some(columns);
+    }
+
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8(output.stdout)
+        .ok()?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DisplayOptions {
     pub clear_screen: bool,
     pub color: Option<Color>,
+    pub hints: Hints,
+    pub theme: Theme,
+    /// A field whose piece is drawn edge-on, in the middle of being flipped.
+    pub flipping: Option<Field>,
+    /// Glyphs drawn on empty fields instead of the hints, e.g. to point out moves.
+    pub marks: Vec<(Field, String)>,
+    pub layout: Layout,
 
     pub bold_title: bool,
     pub title: Option<String>,
 
+    /// Text shown directly under the board.
+    pub footer: Option<String>,
+
     pub empty_lines: u8,
 }
 
@@ -23,8 +124,14 @@ impl Default for DisplayOptions {
         Self {
             clear_screen: true,
             color: None,
+            hints: Hints::Color,
+            theme: Theme::default(),
+            flipping: None,
+            marks: Vec::new(),
+            layout: Layout::Auto,
             title: None,
             bold_title: true,
+            footer: None,
             empty_lines: 1,
         }
     }
@@ -32,7 +139,7 @@ impl Default for DisplayOptions {
 
 pub fn redraw_board(board: &Board, options: &DisplayOptions) {
     if options.clear_screen {
-        clearscreen::clear().unwrap();
+        clear_screen();
     }
 
     if let Some(title) = &options.title {
@@ -46,15 +153,140 @@ pub fn redraw_board(board: &Board, options: &DisplayOptions) {
         );
     }
 
-    match options.color {
-        None => println!("{}", board),
-        Some(Color::White) => println!("{:w>}", board),
-        Some(Color::Black) => println!("{:b>}", board),
+    let hints = match (options.hints, options.color) {
+        (Hints::Off, _) | (Hints::Color, None) => &[][..],
+        (Hints::Color, Some(color)) => &[color][..],
+        (Hints::Both, _) => &[Color::White, Color::Black][..],
+    };
+    if options.layout.is_compact() {
+        println!(
+            "{}",
+            board.render_compact(hints, &options.theme, options.flipping, &options.marks)
+        );
+    } else {
+        println!(
+            "{}",
+            board.render(hints, &options.theme, options.flipping, &options.marks)
+        );
+    }
+
+    if let Some(footer) = &options.footer {
+        println!("{footer}");
     }
 
     print!("{}", "\n".repeat(options.empty_lines as usize));
 }
 
+/// Render a series of evaluations as a one-line bar chart, centered around zero.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::board::display::sparkline;
+/// assert_eq!(sparkline(&[0, 4, -4, 2]), "▄█▁▆");
+/// assert_eq!(sparkline(&[]), "");
+/// ```
+pub fn sparkline(values: &[i32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values
+        .iter()
+        .map(|value| value.unsigned_abs())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    values
+        .iter()
+        .map(|&value| {
+            // Map -max..=max onto 0..=7, with zero landing on the middle bar.
+            let level = (i64::from(value) + i64::from(max)) * 7 / (2 * i64::from(max));
+            BARS[usize::try_from(level).unwrap()]
+        })
+        .collect()
+}
+
+/// The number of columns `text` takes up in a terminal: emoji take up two in most terminals,
+/// other characters one, and color codes and the marks of the text direction none.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::board::display::text_width;
+/// assert_eq!(text_width("⚪ 12"), 5);
+/// assert_eq!(text_width("\x1b[1mbold\x1b[0m"), 4);
+/// assert_eq!(text_width("\u{200E}│a│"), 3);
+/// ```
+pub fn text_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to its final letter.
+            chars.find(char::is_ascii_alphabetic);
+        } else if matches!(c, '\u{200E}' | '\u{200F}' | '\u{2066}'..='\u{2069}') {
+            // Marks of the text direction, see `TextDirection::align`.
+        } else if u32::from(c) >= 0x1F000 || ('\u{2600}'..='\u{27BF}').contains(&c) {
+            width += 2;
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Lay out blocks of lines, e.g. rendered boards, in a grid with `columns` blocks per row,
+/// padding each block to the width of its widest line.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::board::display::side_by_side;
+/// let blocks = ["a\nbb", "c", "d"].map(str::to_owned);
+/// assert_eq!(side_by_side(&blocks, 2), "a    c\nbb\n\nd");
+/// ```
+pub fn side_by_side(blocks: &[String], columns: usize) -> String {
+    const GAP: usize = 3;
+
+    blocks
+        .chunks(columns.max(1))
+        .map(|row| {
+            let widths: Vec<usize> = row
+                .iter()
+                .map(|block| block.lines().map(text_width).max().unwrap_or(0))
+                .collect();
+            let height = row
+                .iter()
+                .map(|block| block.lines().count())
+                .max()
+                .unwrap_or(0);
+            (0..height)
+                .map(|index| {
+                    let mut line = String::new();
+                    for (block, width) in row.iter().zip(&widths) {
+                        let part = block.lines().nth(index).unwrap_or("");
+                        line += part;
+                        line += &" ".repeat(width - text_width(part) + GAP);
+                    }
+                    line.trim_end().to_owned()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Clear the terminal.
+#[cfg(feature = "clear-screen")]
+pub fn clear_screen() {
+    clearscreen::clear().unwrap();
+}
+
+/// Without the `clear-screen` feature, boards are separated by an empty line instead.
+#[cfg(not(feature = "clear-screen"))]
+pub fn clear_screen() {
+    println!();
+}
+
+#[cfg(feature = "animation")]
 pub fn animate_between(
     board_before: &Board,
     board_after: &Board,
@@ -72,6 +304,18 @@ pub fn animate_between(
     }
 }
 
+/// Without the `animation` feature, only the board after the transition is shown.
+#[cfg(not(feature = "animation"))]
+pub fn animate_between(
+    _board_before: &Board,
+    board_after: &Board,
+    _animation_time: Duration,
+    options: &DisplayOptions,
+) {
+    redraw_board(board_after, options);
+}
+
+#[cfg(feature = "animation")]
 pub fn animate_by(
     initial_board: &Board,
     captures: &[Field],
@@ -86,16 +330,40 @@ pub fn animate_by(
     redraw_board(&anim_board, options);
     sleep(time_per_flip / 2);
 
-    for capture in captures {
+    for &capture in captures {
+        // Show the piece edge-on halfway through the flip.
+        redraw_board(
+            &anim_board,
+            &DisplayOptions {
+                flipping: Some(capture),
+                ..options.clone()
+            },
+        );
         sleep(time_per_flip / 2);
 
-        anim_board.flip(*capture);
+        anim_board.flip(capture);
         redraw_board(&anim_board, options);
 
         sleep(time_per_flip / 2);
     }
 }
 
+/// Without the `animation` feature, the captured pieces are flipped all at once.
+#[cfg(not(feature = "animation"))]
+pub fn animate_by(
+    initial_board: &Board,
+    captures: &[Field],
+    _time_per_flip: Duration,
+    options: &DisplayOptions,
+) {
+    let mut board = initial_board.clone();
+    for &capture in captures {
+        board.flip(capture);
+    }
+    redraw_board(&board, options);
+}
+
+#[cfg(feature = "animation")]
 fn animation_frames(board_before: &Board, board_after: &Board) -> Vec<Board> {
     let mut boards_between = vec![board_before.clone()];
 
@@ -104,7 +372,7 @@ fn animation_frames(board_before: &Board, board_after: &Board) -> Vec<Board> {
     for x in 0..8 {
         for y in 0..8 {
             if board_before[Field(x, y)] != board_after[Field(x, y)] {
-                board_slice[Field(x, y)] = board_after[Field(x, y)];
+                board_slice.set(Field(x, y), board_after[Field(x, y)]);
                 boards_between.push(board_slice.clone());
             }
         }
@@ -113,6 +381,7 @@ fn animation_frames(board_before: &Board, board_after: &Board) -> Vec<Board> {
     boards_between
 }
 
+#[cfg(feature = "animation")]
 pub fn animate_results(mut board: Board, time_per_flip: Duration, options: &DisplayOptions) {
     use std::thread::sleep;
 
@@ -138,8 +407,15 @@ pub fn animate_results(mut board: Board, time_per_flip: Duration, options: &Disp
 
     for (index, color) in display_fields {
         sleep(time_per_flip / 2);
-        anim_board[index] = color;
+        anim_board.set(index, color);
         redraw_board(&anim_board, options);
         sleep(time_per_flip / 2);
     }
 }
+
+/// Without the `animation` feature, the sorted board is shown right away.
+#[cfg(not(feature = "animation"))]
+pub fn animate_results(mut board: Board, _time_per_flip: Duration, options: &DisplayOptions) {
+    board.sort();
+    redraw_board(&board, options);
+}
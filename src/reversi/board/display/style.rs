@@ -0,0 +1,42 @@
+//! Text styling that degrades to plain text when the `color` feature is disabled.
+
+#[cfg(feature = "color")]
+pub use colored::Colorize;
+
+/// A stand-in for [`colored::Colorize`] that leaves the text untouched.
+#[cfg(not(feature = "color"))]
+pub trait Colorize {
+    fn normal(self) -> String;
+    fn bold(self) -> String;
+    fn green(self) -> String;
+    fn yellow(self) -> String;
+    fn red(self) -> String;
+    fn dimmed(self) -> String;
+}
+
+#[cfg(not(feature = "color"))]
+impl Colorize for &str {
+    fn normal(self) -> String {
+        self.to_owned()
+    }
+
+    fn bold(self) -> String {
+        self.to_owned()
+    }
+
+    fn green(self) -> String {
+        self.to_owned()
+    }
+
+    fn yellow(self) -> String {
+        self.to_owned()
+    }
+
+    fn red(self) -> String {
+        self.to_owned()
+    }
+
+    fn dimmed(self) -> String {
+        self.to_owned()
+    }
+}
@@ -0,0 +1,109 @@
+use crate::reversi::Color;
+
+use std::str::FromStr;
+
+/// The glyphs used to draw the board.
+///
+/// Themes can be written as `key = value` lines, e.g.
+/// ```
+/// # use reversi_game::board::Theme;
+/// let theme: Theme = "white = ○\nblack = ●\n# a comment\nempty = ·".parse().unwrap();
+/// assert_eq!(theme.piece(reversi_game::reversi::Color::Black), "●");
+/// assert_eq!(theme.edge, Theme::default().edge);
+/// ```
+/// Keys that are left out keep their default glyph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub white: String,
+    pub black: String,
+    pub empty: String,
+    /// The glyph marking a valid move, `None` to show the name of the field instead.
+    pub marker: Option<String>,
+    /// A piece seen edge-on, shown while it is flipped.
+    pub edge: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            white: "⚪".to_owned(),
+            black: "⚫".to_owned(),
+            empty: String::new(),
+            marker: None,
+            edge: "━━".to_owned(),
+        }
+    }
+}
+
+impl Theme {
+    /// The version of the theme file format. Theme files may give it in a `version` line.
+    pub const VERSION: u32 = 1;
+
+    /// The names of the built-in themes.
+    pub const NAMES: [&'static str; 3] = ["emoji", "circles", "letters"];
+
+    /// One of the built-in themes, see `Theme::NAMES`.
+    pub fn named(name: &str) -> Option<Self> {
+        let theme = |white: &str, black: &str, empty: &str, marker: &str, edge: &str| Theme {
+            white: white.to_owned(),
+            black: black.to_owned(),
+            empty: empty.to_owned(),
+            marker: Some(marker.to_owned()),
+            edge: edge.to_owned(),
+        };
+
+        match name {
+            "emoji" => Some(Theme::default()),
+            "circles" => Some(theme("○", "●", "·", "+", "|")),
+            "letters" => Some(theme("W", "B", ".", "*", "-")),
+            _ => None,
+        }
+    }
+
+    /// The glyph of a piece of the given color.
+    pub fn piece(&self, color: Color) -> &str {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+
+    /// Pad a glyph to the two columns of a field.
+    ///
+    /// Emoji take up two columns in most terminals, other characters one.
+    pub(crate) fn pad(glyph: &str) -> String {
+        let width = super::text_width(glyph);
+        format!("{glyph}{}", " ".repeat(2usize.saturating_sub(width)))
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::default();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected `key = value`, got `{line}`"))?;
+            let value = value.trim().to_owned();
+
+            match key.trim() {
+                "white" => theme.white = value,
+                "black" => theme.black = value,
+                "empty" => theme.empty = value,
+                "marker" => theme.marker = Some(value).filter(|value| !value.is_empty()),
+                "edge" => theme.edge = value,
+                "version" if value == Theme::VERSION.to_string() => {}
+                "version" => return Err(format!("Unsupported theme version `{value}`")),
+                other => return Err(format!("Unknown glyph `{other}`")),
+            }
+        }
+
+        Ok(theme)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::reversi::{Field, PlaceError};
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+/// How fields are written and read, e.g. `d3` or `43`.
+///
+/// `Field`'s `Display` and `FromStr` use the `current` notation, so everything shown to the
+/// user and everything they type follows it. Files and protocols (saved games, CSV, JSON-RPC)
+/// always use `Standard` to stay readable everywhere.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum Notation {
+    /// The files `a` to `h` from left to right and the ranks `8` to `1` from top to bottom.
+    #[default]
+    Standard,
+    /// The files as the numbers `1` to `8`, with the ranks of `Standard`.
+    Numeric,
+    /// The files of `Standard` with the ranks `1` to `8` from top to bottom.
+    ReversedRanks,
+    /// The column and the row counted from `0` at the top left, as in `Field(x, y)`.
+    ZeroIndexed,
+}
+
+/// The notation in use, see `Notation::current`.
+static CURRENT: AtomicU8 = AtomicU8::new(Notation::Standard as u8);
+
+impl Notation {
+    pub const NAMES: [&'static str; 4] = ["standard", "numeric", "reversed", "zero"];
+    const ALL: [Notation; 4] = [
+        Notation::Standard,
+        Notation::Numeric,
+        Notation::ReversedRanks,
+        Notation::ZeroIndexed,
+    ];
+
+    /// The notation used by `Field`'s `Display` and `FromStr`, `Standard` unless changed
+    /// with `make_current`.
+    pub fn current() -> Notation {
+        Notation::ALL[usize::from(CURRENT.load(Ordering::Relaxed))]
+    }
+
+    /// Write and read all fields of the program in this notation.
+    pub fn make_current(self) {
+        CURRENT.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// The label of the `x`th column from the left.
+    pub fn file(self, x: usize) -> String {
+        match self {
+            Notation::Standard | Notation::ReversedRanks => char::from(b'a' + x as u8).to_string(),
+            Notation::Numeric => (x + 1).to_string(),
+            Notation::ZeroIndexed => x.to_string(),
+        }
+    }
+
+    /// The label of the `y`th row from the top.
+    pub fn rank(self, y: usize) -> String {
+        match self {
+            Notation::Standard | Notation::Numeric => (8 - y).to_string(),
+            Notation::ReversedRanks => (y + 1).to_string(),
+            Notation::ZeroIndexed => y.to_string(),
+        }
+    }
+
+    /// Write a field, always with two characters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{board::Notation, Field};
+    /// let field = Field(3, 5);
+    /// assert_eq!(Notation::Standard.format(field), "d3");
+    /// assert_eq!(Notation::Numeric.format(field), "43");
+    /// assert_eq!(Notation::ReversedRanks.format(field), "d6");
+    /// assert_eq!(Notation::ZeroIndexed.format(field), "35");
+    /// ```
+    pub fn format(self, Field(x, y): Field) -> String {
+        assert!(x < 8 && y < 8);
+        format!("{}{}", self.file(x), self.rank(y))
+    }
+
+    /// Read a field written by `format`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{board::Notation, Field};
+    /// assert_eq!(Notation::Numeric.parse("43"), Ok(Field(3, 5)));
+    /// assert!(Notation::Standard.parse("43").is_err());
+    /// ```
+    pub fn parse(self, s: &str) -> Result<Field, PlaceError> {
+        let mut chars = s.chars();
+        let (Some(file), Some(rank), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(PlaceError::InvalidLength);
+        };
+        let position = |label: char, of: fn(Notation, usize) -> String| {
+            (0..8)
+                .find(|&index| of(self, index) == label.to_string())
+                .ok_or(PlaceError::OutOfBounds)
+        };
+        if !rank.is_ascii_digit() {
+            return Err(PlaceError::InvalidNumber);
+        }
+        Ok(Field(
+            position(file, Notation::file)?,
+            position(rank, Notation::rank)?,
+        ))
+    }
+}
+
+impl FromStr for Notation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Notation::NAMES
+            .iter()
+            .position(|&name| name == s)
+            .map(|index| Notation::ALL[index])
+            .ok_or_else(|| {
+                format!(
+                    "Unknown notation `{s}`, expected one of {}",
+                    Notation::NAMES.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Notation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(Notation::NAMES[*self as usize])
+    }
+}
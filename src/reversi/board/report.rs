@@ -0,0 +1,185 @@
+use crate::reversi::{Board, Color, Field, PlaceError};
+
+/// One of the eight directions on the board, as seen by White (rank 8 is north).
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The change of the x and y coordinates of a step in this direction.
+    pub const fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    /// Move every field of a bitboard one step in this direction, dropping those that leave
+    /// the board.
+    pub(crate) const fn shift(self, bits: u64) -> u64 {
+        // The fields of a bitboard without the a file and without the h file.
+        const NOT_A_FILE: u64 = 0xfefe_fefe_fefe_fefe;
+        const NOT_H_FILE: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+        match self {
+            Direction::North => bits >> 8,
+            Direction::NorthEast => (bits >> 7) & NOT_A_FILE,
+            Direction::East => (bits << 1) & NOT_A_FILE,
+            Direction::SouthEast => (bits << 9) & NOT_A_FILE,
+            Direction::South => bits << 8,
+            Direction::SouthWest => (bits << 7) & NOT_H_FILE,
+            Direction::West => (bits >> 1) & NOT_H_FILE,
+            Direction::NorthWest => (bits >> 9) & NOT_H_FILE,
+        }
+    }
+
+    /// The fields from `field` (exclusive) to the edge of the board in this direction.
+    pub fn ray(self, field: Field) -> impl Iterator<Item = Field> {
+        self.ray_fields(field).iter().copied()
+    }
+
+    /// The fields of `ray`, looked up in `RAYS`.
+    pub(crate) fn ray_fields(self, field: Field) -> &'static [Field] {
+        let (fields, length) = &RAYS[8 * field.1 + field.0][self as usize];
+        &fields[..*length]
+    }
+}
+
+/// The fields of `Direction::ray` for each field in the order `8 * y + x` and each direction
+/// in the order of `Direction::ALL`, with the number of fields on the ray.
+static RAYS: [[([Field; 7], usize); 8]; 64] = {
+    let mut rays = [[([Field(0, 0); 7], 0); 8]; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut direction = 0;
+        while direction < 8 {
+            let (dx, dy) = Direction::ALL[direction].offset();
+            let (fields, length) = &mut rays[index][direction];
+            let mut field = Field(index % 8, index / 8);
+            while let Some(next) = field.offset(dx, dy) {
+                fields[*length] = next;
+                *length += 1;
+                field = next;
+            }
+            direction += 1;
+        }
+        index += 1;
+    }
+    rays
+};
+
+/// Everything about a possible move, e.g. for explaining it in a user interface.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MoveReport {
+    pub field: Field,
+    pub color: Color,
+    /// Why the move is invalid, if it is.
+    pub validity: Result<(), PlaceError>,
+    /// The captured discs in each direction, nearest first. Directions without captures are left out.
+    pub captures: Vec<(Direction, Vec<Field>)>,
+    /// The number of valid moves of the player before and after the move.
+    pub mobility: (usize, usize),
+    /// The number of valid moves of the opponent before and after the move.
+    pub opponent_mobility: (usize, usize),
+    /// The discs of the player and of the opponent after the move.
+    pub score: (usize, usize),
+}
+
+impl MoveReport {
+    pub fn is_valid(&self) -> bool {
+        self.validity.is_ok()
+    }
+
+    /// All captured discs.
+    pub fn captured(&self) -> impl Iterator<Item = Field> + '_ {
+        self.captures
+            .iter()
+            .flat_map(|(_, fields)| fields.iter().copied())
+    }
+
+    /// How many more (or fewer) moves the player has after the move.
+    pub fn mobility_change(&self) -> isize {
+        self.mobility.1 as isize - self.mobility.0 as isize
+    }
+
+    /// How many more (or fewer) moves the opponent has after the move.
+    pub fn opponent_mobility_change(&self) -> isize {
+        self.opponent_mobility.1 as isize - self.opponent_mobility.0 as isize
+    }
+}
+
+impl Board {
+    /// Explain the move of `color` on `field` in a single report.
+    ///
+    /// For invalid moves the report describes the unchanged board.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::reversi::{*, board::report::Direction};
+    /// let board = Board::new();
+    /// let report = board.move_report("d3".parse().unwrap(), Color::White);
+    /// assert!(report.is_valid());
+    /// assert_eq!(report.captures, vec![(Direction::North, vec!["d4".parse().unwrap()])]);
+    /// assert_eq!(report.score, (4, 1));
+    /// ```
+    pub fn move_report(&self, field: Field, color: Color) -> MoveReport {
+        let validity = self.move_validity(field, color).map(|_| ());
+        let opponent = color.other();
+
+        let captures = if validity.is_ok() {
+            Direction::ALL
+                .into_iter()
+                .filter_map(|direction| {
+                    let line = self.captures_towards(field, color, direction);
+                    (!line.is_empty()).then(|| (direction, line.to_vec()))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut after = self.clone();
+        if validity.is_ok() {
+            after.set(field, Some(color));
+            for (_, line) in &captures {
+                for &captured in line {
+                    after.set(captured, Some(color));
+                }
+            }
+        }
+
+        MoveReport {
+            field,
+            color,
+            validity,
+            captures,
+            mobility: (self.mobility(color), after.mobility(color)),
+            opponent_mobility: (self.mobility(opponent), after.mobility(opponent)),
+            score: (after.count_pieces(color), after.count_pieces(opponent)),
+        }
+    }
+}
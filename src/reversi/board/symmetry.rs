@@ -0,0 +1,108 @@
+use crate::reversi::{Board, Field};
+
+/// One of the eight symmetries of the square board.
+///
+/// Positions that are transformed into each other by a symmetry are strategically identical.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// Rotate clockwise by 90 degrees.
+    Rotate90,
+    Rotate180,
+    /// Rotate clockwise by 270 degrees.
+    Rotate270,
+    /// Mirror the files, a8 becomes h8.
+    FlipHorizontal,
+    /// Mirror the ranks, a8 becomes a1.
+    FlipVertical,
+    /// Mirror along the a8-h1 diagonal.
+    FlipDiagonal,
+    /// Mirror along the a1-h8 diagonal.
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    /// The symmetry undoing this one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Field, Symmetry};
+    /// for symmetry in Symmetry::ALL {
+    ///     let field = Field(1, 5);
+    ///     assert_eq!(symmetry.inverse().apply(symmetry.apply(field)), field);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Transform a field.
+    pub fn apply(self, Field(x, y): Field) -> Field {
+        match self {
+            Symmetry::Identity => Field(x, y),
+            Symmetry::Rotate90 => Field(7 - y, x),
+            Symmetry::Rotate180 => Field(7 - x, 7 - y),
+            Symmetry::Rotate270 => Field(y, 7 - x),
+            Symmetry::FlipHorizontal => Field(7 - x, y),
+            Symmetry::FlipVertical => Field(x, 7 - y),
+            Symmetry::FlipDiagonal => Field(y, x),
+            Symmetry::FlipAntiDiagonal => Field(7 - y, 7 - x),
+        }
+    }
+}
+
+impl Board {
+    /// Return the board transformed by a symmetry.
+    #[must_use]
+    pub fn transformed(&self, symmetry: Symmetry) -> Board {
+        let mut board = Board::empty();
+        for field in Field::all() {
+            board.set(symmetry.apply(field), self[field]);
+        }
+        board
+    }
+
+    /// Return the canonical representative of all boards that are symmetric to this one,
+    /// together with the symmetry that transforms this board into it.
+    ///
+    /// Symmetric boards have the same canonical board, so it can be used as a key
+    /// to store positions without mirrored or rotated duplicates.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Color, Field, Symmetry};
+    /// let mut board = Board::new();
+    /// board.add_piece(Field(2, 4), Color::White).unwrap();
+    ///
+    /// let mirrored = board.transformed(Symmetry::FlipDiagonal);
+    /// assert_eq!(board.canonical().0, mirrored.canonical().0);
+    /// ```
+    pub fn canonical(&self) -> (Board, Symmetry) {
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| (self.transformed(symmetry), symmetry))
+            .min_by_key(|(board, _)| {
+                // Row by row from the top, empty fields before Black and White.
+                (0..64)
+                    .map(|index| board[Field(index % 8, index / 8)])
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+    }
+}
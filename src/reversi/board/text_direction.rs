@@ -0,0 +1,137 @@
+use std::{
+    env, fmt,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Which way the board is read, e.g. `RightToLeft` for Arabic or Hebrew terminals.
+///
+/// Terminals with bidirectional text support lay out a line right to left once it looks like
+/// right-to-left text, which breaks up the columns of the board. `RightToLeft` draws the board
+/// mirrored instead, with the file `a` on the right, and starts each line with a left-to-right
+/// mark so the terminal keeps the box drawing in place. Fields are named and entered as usual.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum TextDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Whether the board is drawn right to left, see `TextDirection::current`.
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+
+/// The languages written right to left, by their code in `$LANG`.
+const RTL_LANGUAGES: [&str; 10] = ["ar", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur"];
+
+/// Keeps a terminal from reordering the line after it, as it is a strong left-to-right
+/// character of no width.
+const LEFT_TO_RIGHT_MARK: char = '\u{200E}';
+
+impl TextDirection {
+    pub const NAMES: [&'static str; 3] = ["ltr", "rtl", "auto"];
+
+    /// The direction the boards of the program are drawn in, `LeftToRight` unless changed with
+    /// `make_current`.
+    pub fn current() -> TextDirection {
+        if RIGHT_TO_LEFT.load(Ordering::Relaxed) {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+
+    /// Draw all boards of the program in this direction.
+    pub fn make_current(self) {
+        RIGHT_TO_LEFT.store(self == TextDirection::RightToLeft, Ordering::Relaxed);
+    }
+
+    /// The direction of the language of a locale like `he_IL.UTF-8`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::board::TextDirection;
+    /// assert_eq!(TextDirection::of_locale("he_IL.UTF-8"), TextDirection::RightToLeft);
+    /// assert_eq!(TextDirection::of_locale("fa"), TextDirection::RightToLeft);
+    /// assert_eq!(TextDirection::of_locale("de_DE.UTF-8"), TextDirection::LeftToRight);
+    /// ```
+    pub fn of_locale(locale: &str) -> TextDirection {
+        let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+        if RTL_LANGUAGES.contains(&language) {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+
+    /// The direction of the user's locale: `$LC_ALL`, `$LC_MESSAGES` or `$LANG`, whichever
+    /// is set first.
+    pub fn from_locale() -> TextDirection {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+            .map_or(TextDirection::LeftToRight, |locale| {
+                TextDirection::of_locale(&locale)
+            })
+    }
+
+    /// The columns of the board in the order they are drawn, from the left.
+    pub fn columns(self) -> [usize; 8] {
+        let mut columns = [0, 1, 2, 3, 4, 5, 6, 7];
+        if self == TextDirection::RightToLeft {
+            columns.reverse();
+        }
+        columns
+    }
+
+    /// `text` with a left-to-right mark at the start of each line when drawn right to left,
+    /// so that the terminal doesn't reorder it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::board::TextDirection;
+    /// assert_eq!(TextDirection::LeftToRight.align("│a│\n│b│\n"), "│a│\n│b│\n");
+    /// assert_eq!(
+    ///     TextDirection::RightToLeft.align("│a│\n│b│\n"),
+    ///     "\u{200E}│a│\n\u{200E}│b│\n"
+    /// );
+    /// ```
+    pub fn align(self, text: &str) -> String {
+        match self {
+            TextDirection::LeftToRight => text.to_owned(),
+            TextDirection::RightToLeft => {
+                let mut aligned = String::new();
+                for line in text.split_inclusive('\n') {
+                    aligned.push(LEFT_TO_RIGHT_MARK);
+                    aligned.push_str(line);
+                }
+                aligned
+            }
+        }
+    }
+}
+
+impl FromStr for TextDirection {
+    type Err = String;
+
+    /// Parse one of `NAMES`, where `auto` is the direction of the user's locale.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ltr" => Ok(TextDirection::LeftToRight),
+            "rtl" => Ok(TextDirection::RightToLeft),
+            "auto" => Ok(TextDirection::from_locale()),
+            _ => Err(format!(
+                "Unknown direction `{s}`, expected one of {}",
+                TextDirection::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TextDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            TextDirection::LeftToRight => "ltr",
+            TextDirection::RightToLeft => "rtl",
+        })
+    }
+}
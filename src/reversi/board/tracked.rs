@@ -0,0 +1,108 @@
+//! A board that knows the valid moves of both colors without searching for them.
+
+use crate::reversi::{Board, Color, Field, GameStatus, PlaceError};
+
+use std::ops::Deref;
+
+/// A `Board` with the valid moves of both colors, found once after each move instead of on
+/// every question, e.g. when a game asks for its status after each move and the display marks
+/// the moves again, or when the engine asks for the status, the moves and the volatility of
+/// each position it searches.
+///
+/// It can be used wherever a `&Board` can, and changes only through `add_piece`, so the moves
+/// never go stale.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{Board, Color, Field, GameStatus, TrackedBoard};
+/// let mut board = TrackedBoard::new(Board::new());
+/// assert_eq!(board.valid_moves_mask(Color::White), Board::new().valid_moves_mask(Color::White));
+///
+/// board.add_piece(Field(2, 4), Color::White).unwrap();
+/// assert_eq!(board.mobility(Color::Black), 3);
+/// assert_eq!(board.status(), GameStatus::InProgress);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackedBoard {
+    board: Board,
+    /// The valid moves of Black and White, as in `Board::valid_moves_mask`.
+    moves: [u64; 2],
+}
+
+impl TrackedBoard {
+    pub fn new(board: Board) -> Self {
+        let mut tracked = TrackedBoard {
+            board,
+            moves: [0; 2],
+        };
+        tracked.update();
+        tracked
+    }
+
+    /// Find the valid moves after the board changed, for both colors at once.
+    fn update(&mut self) {
+        self.moves = [Color::Black, Color::White].map(|color| self.board.valid_moves_mask(color));
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Place a piece like `Board::add_piece` and update the valid moves of both colors.
+    pub fn add_piece(&mut self, field: Field, color: Color) -> Result<Vec<Field>, PlaceError> {
+        let captured = self.board.add_piece(field, color)?;
+        self.update();
+        Ok(captured)
+    }
+
+    /// The valid moves of `color` as a bitboard, see `Board::valid_moves_mask`.
+    pub fn valid_moves_mask(&self, color: Color) -> u64 {
+        self.moves[color as usize]
+    }
+
+    /// The valid moves of `color`, in `Field::all` order like `Board::valid_moves`.
+    pub fn valid_moves(&self, color: Color) -> Vec<Field> {
+        let moves = self.valid_moves_mask(color);
+        Field::all()
+            .filter(|field| moves & field.bit() != 0)
+            .collect()
+    }
+
+    pub fn is_valid(&self, field: Field, color: Color) -> bool {
+        field.in_bounds() && self.valid_moves_mask(color) & field.bit() != 0
+    }
+
+    /// The number of valid moves of `color`.
+    pub fn mobility(&self, color: Color) -> usize {
+        self.valid_moves_mask(color).count_ones() as usize
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.board
+            .status_with_moves(|color| self.valid_moves_mask(color))
+    }
+
+    /// The number of discs `color` could flip, see `Board::volatility`.
+    pub fn volatility(&self, color: Color) -> usize {
+        self.board
+            .volatility_with_moves(color, self.valid_moves_mask(color))
+    }
+}
+
+impl Deref for TrackedBoard {
+    type Target = Board;
+
+    fn deref(&self) -> &Board {
+        &self.board
+    }
+}
+
+impl From<Board> for TrackedBoard {
+    fn from(board: Board) -> Self {
+        TrackedBoard::new(board)
+    }
+}
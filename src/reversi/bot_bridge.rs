@@ -0,0 +1,263 @@
+//! Text commands for chat bots, so the engine can power games on Discord, IRC and the like.
+//!
+//! The bridge knows nothing about any chat platform: the bot hands it each message with its
+//! channel and author and posts the reply, if there is one. Every channel has at most one game.
+//!
+//! ```
+//! # use reversi_game::bot_bridge::{Bridge, Render};
+//! let mut bridge = Bridge::new(Render::CodeBlock);
+//! assert!(bridge.handle("#reversi", "alice", "!new @bob").is_some());
+//! assert!(bridge.handle("#reversi", "alice", "!move d3").unwrap().contains("bob"));
+//! assert_eq!(bridge.handle("#reversi", "alice", "hello"), None);
+//! ```
+
+use crate::{
+    engine::{Engine, Search, SearchLimits},
+    reversi::{Board, Color, Field, GameStatus, Notation},
+    rules::Position,
+};
+
+use std::collections::HashMap;
+
+/// The depth the engine plays at in games against it.
+pub const ENGINE_DEPTH: u8 = 4;
+
+/// How boards are drawn in replies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Render {
+    /// Letters in a monospaced code block with the coordinates, for Discord, Slack or IRC.
+    CodeBlock,
+    /// A grid of emoji, for platforms without code blocks.
+    Emoji,
+}
+
+impl Render {
+    /// Draw `board`, marking the valid moves of `to_move` in code blocks.
+    pub fn board(self, board: &Board, to_move: Color) -> String {
+        let valid_moves = board.valid_moves(to_move);
+        let notation = Notation::current();
+        let rows = (0..8).map(|y| {
+            let fields = (0..8).map(|x| match (board[Field(x, y)], self) {
+                (Some(Color::Black), Render::CodeBlock) => "X",
+                (Some(Color::White), Render::CodeBlock) => "O",
+                (None, Render::CodeBlock) if valid_moves.contains(&Field(x, y)) => "*",
+                (None, Render::CodeBlock) => ".",
+                (Some(Color::Black), Render::Emoji) => "⚫",
+                (Some(Color::White), Render::Emoji) => "⚪",
+                (None, Render::Emoji) => "🟩",
+            });
+            match self {
+                Render::CodeBlock => format!(
+                    "{} {}",
+                    notation.rank(y),
+                    fields.collect::<Vec<_>>().join(" ")
+                ),
+                Render::Emoji => fields.collect(),
+            }
+        });
+
+        match self {
+            Render::CodeBlock => format!(
+                "```\n  {}\n{}\n```",
+                (0..8)
+                    .map(|x| notation.file(x))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                rows.collect::<Vec<_>>().join("\n")
+            ),
+            Render::Emoji => rows.collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
+/// Who plays a color: a chat user by name or the engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Seat {
+    User(String),
+    Engine,
+}
+
+impl Seat {
+    fn name(&self) -> &str {
+        match self {
+            Seat::User(name) => name,
+            Seat::Engine => "the engine",
+        }
+    }
+}
+
+/// The game of a channel.
+struct ChatGame {
+    position: Position,
+    white: Seat,
+    black: Seat,
+}
+
+impl ChatGame {
+    fn seat(&self, color: Color) -> &Seat {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black,
+        }
+    }
+}
+
+/// The games of all channels.
+pub struct Bridge {
+    games: HashMap<String, ChatGame>,
+    engine: Engine,
+    render: Render,
+}
+
+impl Bridge {
+    pub fn new(render: Render) -> Self {
+        Bridge {
+            games: HashMap::new(),
+            engine: Engine::new(),
+            render,
+        }
+    }
+
+    /// Handle a chat message `author` wrote in `channel`.
+    ///
+    /// # Returns
+    /// The reply to post in the channel, or `None` if the message wasn't a command.
+    pub fn handle(&mut self, channel: &str, author: &str, message: &str) -> Option<String> {
+        let mut words = message.trim().strip_prefix('!')?.split_whitespace();
+        let command = words.next()?.to_lowercase();
+        let argument = words.next();
+
+        Some(match command.as_str() {
+            "new" => self.new_game(channel, author, argument),
+            "move" => match argument {
+                Some(field) => self.play(channel, author, field),
+                None => format!("Which field? E.g. `!move {}`", Field(3, 5)),
+            },
+            "board" => match self.games.get(channel) {
+                Some(game) => self.describe(game),
+                None => "There is no game here. Start one with `!new` or `!new @user`.".to_owned(),
+            },
+            "resign" => self.resign(channel, author),
+            "help" => "`!new` plays the engine, `!new @user` challenges someone, \
+                `!move d3` plays a field, `!board` shows the board and `!resign` gives up."
+                .to_owned(),
+            _ => return None,
+        })
+    }
+
+    /// Start a game of `author` against `opponent` (a mention) or the engine.
+    /// The author plays White and moves first.
+    fn new_game(&mut self, channel: &str, author: &str, opponent: Option<&str>) -> String {
+        if self.games.contains_key(channel) {
+            return "A game is already running here. Finish it or `!resign` first.".to_owned();
+        }
+        let black = match opponent.map(|mention| mention.trim_start_matches('@')) {
+            Some(name) if name == author => return "You can't play yourself.".to_owned(),
+            Some(name) => Seat::User(name.to_owned()),
+            None => Seat::Engine,
+        };
+        self.engine.clear_hash();
+        let game = ChatGame {
+            position: Position::new(),
+            white: Seat::User(author.to_owned()),
+            black,
+        };
+        let reply = format!(
+            "{} {} vs {} {}\n{}",
+            Color::White,
+            game.white.name(),
+            Color::Black,
+            game.black.name(),
+            self.describe(&game)
+        );
+        self.games.insert(channel.to_owned(), game);
+        reply
+    }
+
+    /// Play `field` for `author`, then the engine's replies and any forced passes.
+    fn play(&mut self, channel: &str, author: &str, field: &str) -> String {
+        let Some(game) = self.games.get_mut(channel) else {
+            return "There is no game here. Start one with `!new` or `!new @user`.".to_owned();
+        };
+        if *game.seat(game.position.to_move()) != Seat::User(author.to_owned()) {
+            return format!("It's {}'s turn.", game.seat(game.position.to_move()).name());
+        }
+        let field: Field = match field.parse() {
+            Ok(field) => field,
+            Err(error) => return format!("Invalid field `{field}`: {error}"),
+        };
+        if let Err(error) = game.position.play(Some(field)) {
+            return format!("Invalid move {field}: {error}");
+        }
+
+        let mut notes = Vec::new();
+        while game.position.status() == GameStatus::InProgress {
+            let to_move = game.position.to_move();
+            if game.position.must_pass() {
+                notes.push(format!(
+                    "{to_move} {} has to pass.",
+                    game.seat(to_move).name()
+                ));
+                let _ = game.position.play(None);
+            } else if *game.seat(to_move) == Seat::Engine {
+                let (best_move, _) = self.engine.best_move(
+                    game.position.board(),
+                    to_move,
+                    SearchLimits::depth(ENGINE_DEPTH),
+                );
+                let _ = game.position.play(best_move);
+                if let Some(field) = best_move {
+                    notes.push(format!("{to_move} The engine plays {field}."));
+                }
+            } else {
+                break;
+            }
+        }
+
+        let game = &self.games[channel];
+        let mut reply = notes;
+        reply.push(self.describe(game));
+        if game.position.status() != GameStatus::InProgress {
+            self.games.remove(channel);
+        }
+        reply.join("\n")
+    }
+
+    fn resign(&mut self, channel: &str, author: &str) -> String {
+        let Some(game) = self.games.get(channel) else {
+            return "There is no game here.".to_owned();
+        };
+        let Some(color) = [Color::White, Color::Black]
+            .into_iter()
+            .find(|&color| *game.seat(color) == Seat::User(author.to_owned()))
+        else {
+            return "Only the players can resign.".to_owned();
+        };
+        let winner = game.seat(color.other()).name().to_owned();
+        self.games.remove(channel);
+        format!("{author} resigns, {winner} wins.")
+    }
+
+    /// The board followed by whose turn it is or the result.
+    fn describe(&self, game: &ChatGame) -> String {
+        let board = game.position.board();
+        let to_move = game.position.to_move();
+        let count = |color| format!("{color} {}", board.count_pieces(color));
+        let line = match game.position.status() {
+            GameStatus::InProgress => format!(
+                "{to_move} {} to move ({}, {})",
+                game.seat(to_move).name(),
+                count(Color::White),
+                count(Color::Black)
+            ),
+            GameStatus::Win(color) => format!(
+                "{} wins {}-{}!",
+                game.seat(color).name(),
+                board.count_pieces(color),
+                board.count_pieces(color.other())
+            ),
+            GameStatus::Draw => format!("Draw, {} all.", board.count_pieces(Color::White)),
+        };
+        format!("{}\n{line}", self.render.board(board, to_move))
+    }
+}
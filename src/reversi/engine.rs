@@ -0,0 +1,1020 @@
+pub mod endgame_cache;
+pub mod handle;
+pub mod search_tree;
+pub mod throttle;
+pub mod time_manager;
+pub mod transposition_table;
+pub mod weights;
+
+pub use endgame_cache::EndgameCache;
+pub use handle::EngineHandle;
+pub use search_tree::{Cutoff, SearchTree};
+pub use time_manager::TimeManager;
+pub use transposition_table::TranspositionTable;
+pub use weights::Weights;
+
+use crate::{
+    board::Notation,
+    reversi::{Board, Color, Field, GameStatus, TrackedBoard},
+};
+use transposition_table::Bound;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The score of a won game. All scores are from the view of the side to move, so a lost
+/// game scores `-WIN_SCORE`.
+pub const WIN_SCORE: i32 = i32::MAX;
+
+/// Turn a score from the view of `color` into one from White's view,
+/// e.g. to draw the evaluations of a whole game in one graph.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{engine::to_white_view, Color};
+/// assert_eq!(to_white_view(3, Color::White), 3);
+/// assert_eq!(to_white_view(3, Color::Black), -3);
+/// ```
+pub fn to_white_view(score: i32, color: Color) -> i32 {
+    match color {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+/// Turn a score from White's view into one from the view of `color`.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{engine::from_white_view, Color};
+/// assert_eq!(from_white_view(-3, Color::Black), 3);
+/// ```
+pub fn from_white_view(score: i32, color: Color) -> i32 {
+    to_white_view(score, color)
+}
+
+/// A position as written in the files of the engine's caches: the discs of White and Black
+/// as hexadecimal bitboards and the side to move, `O` for White and `X` for Black.
+fn write_position(board: &Board, color: Color) -> String {
+    let side = match color {
+        Color::White => 'O',
+        Color::Black => 'X',
+    };
+    format!(
+        "{:016x} {:016x} {side}",
+        board.bitboard(Color::White),
+        board.bitboard(Color::Black)
+    )
+}
+
+/// Read a position written by `write_position` from its three words.
+fn parse_position(white: &str, black: &str, color: &str) -> Option<(Board, Color)> {
+    let white = u64::from_str_radix(white, 16).ok()?;
+    let black = u64::from_str_radix(black, 16).ok()?;
+    let color = match color {
+        "O" => Color::White,
+        "X" => Color::Black,
+        _ => return None,
+    };
+    (white & black == 0).then(|| (Board::from_bitboards(white, black), color))
+}
+
+/// A best move as written in the files of the engine's caches, `--` for none.
+fn write_move(best_move: Option<Field>) -> String {
+    best_move.map_or_else(|| "--".to_owned(), |field| Notation::Standard.format(field))
+}
+
+/// Read a best move written by `write_move`, `None` if it is invalid.
+#[allow(clippy::option_option)]
+fn parse_move(best_move: &str) -> Option<Option<Field>> {
+    match best_move {
+        "--" => Some(None),
+        field => Notation::Standard.parse(field).ok().map(Some),
+    }
+}
+
+/// The weighted features of the position from the view of `color`,
+/// or `±WIN_SCORE` for a finished game, where `status` is the status of `board`.
+fn evaluate(board: &Board, status: &GameStatus, color: Color, weights: &Weights) -> i32 {
+    match *status {
+        GameStatus::Win(winner) if winner == color => WIN_SCORE,
+        GameStatus::Win(_) => -WIN_SCORE,
+        GameStatus::Draw => 0,
+        GameStatus::InProgress => weights.evaluate(board, color),
+    }
+}
+
+/// Positions where the side to move could flip at least this many discs are searched one move
+/// deeper when the depth runs out, see `Board::volatility`.
+pub const VOLATILE_DISCS: usize = 14;
+
+/// How far a search may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The number of moves to look ahead.
+    pub depth: u8,
+    /// The number of positions to search, after which only leaves are evaluated.
+    pub nodes: Option<u64>,
+}
+
+impl SearchLimits {
+    /// Search `depth` moves ahead, without a node limit.
+    pub fn depth(depth: u8) -> Self {
+        SearchLimits { depth, nodes: None }
+    }
+}
+
+/// The state of one search, passed down through its nodes, so that searches on the same engine
+/// from several threads (see `EngineHandle`) don't use up each other's node limits or time.
+struct SearchContext<'a> {
+    /// The positions searched so far.
+    nodes: Cell<u64>,
+    /// The number of positions after which only leaves are evaluated, the lower of
+    /// `SearchLimits::nodes` and the node limit of the strength.
+    node_limit: u64,
+    /// The strength of the engine when the search started and the seed of its noise,
+    /// see `Engine::limit_strength`.
+    strength: Option<(Strength, u64)>,
+    /// When the search runs out of time, see `deepen`.
+    deadline: Option<Instant>,
+    /// Whether the deadline has passed.
+    stopped: Cell<bool>,
+    /// The tree the visited positions are recorded in, see `Engine::traced_best_move`.
+    tree: Option<&'a RefCell<SearchTree>>,
+}
+
+impl<'a> SearchContext<'a> {
+    fn new(
+        node_limit: Option<u64>,
+        deadline: Option<Instant>,
+        strength: Option<(Strength, u64)>,
+        tree: Option<&'a RefCell<SearchTree>>,
+    ) -> Self {
+        let strength_limit = strength.map(|(strength, _)| strength.node_limit);
+        SearchContext {
+            nodes: Cell::new(0),
+            node_limit: node_limit
+                .unwrap_or(u64::MAX)
+                .min(strength_limit.unwrap_or(u64::MAX)),
+            strength,
+            deadline,
+            stopped: Cell::new(false),
+            tree,
+        }
+    }
+
+    /// Record a step of the search in the tree, if it is traced.
+    fn record(&self, action: impl FnOnce(&mut SearchTree)) {
+        if let Some(tree) = self.tree {
+            action(&mut tree.borrow_mut());
+        }
+    }
+
+    /// Whether the node limit is reached or the deadline has passed.
+    fn out_of_nodes(&self) -> bool {
+        self.nodes.get() > self.node_limit || self.stopped.get()
+    }
+}
+
+/// Something that finds the best move in a position.
+pub trait Search {
+    /// Find the best move of `to_move` within `limits`.
+    ///
+    /// # Returns
+    /// The best move (`None` if `to_move` has to pass) and its evaluation
+    /// from the view of `to_move`.
+    fn best_move(
+        &self,
+        board: &Board,
+        to_move: Color,
+        limits: SearchLimits,
+    ) -> (Option<Field>, i32);
+}
+
+/// Limits that make the engine play weaker, roughly like a player with a given Elo rating.
+///
+/// Besides a shallower search, weaker engines stop looking at new positions after a number of
+/// nodes and misjudge positions by a few discs, so they make human-like mistakes instead of
+/// playing perfectly within a short horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strength {
+    /// The depth to search.
+    pub depth: u8,
+    /// The number of positions of each search, after which only leaves are evaluated.
+    pub node_limit: u64,
+    /// The maximum number of discs the evaluation of a position is off by.
+    pub noise: i32,
+}
+
+impl Strength {
+    pub const MIN_ELO: u16 = 400;
+    pub const MAX_ELO: u16 = 2000;
+
+    /// The limits for an engine rated `elo`, clamped to `MIN_ELO..=MAX_ELO`.
+    ///
+    /// The ratings are estimates: the weakest engine plays barely better than randomly,
+    /// the strongest one is not limited apart from its depth.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::engine::Strength;
+    /// let club = Strength::from_elo(1200);
+    /// assert!(club.depth < Strength::from_elo(2000).depth);
+    /// assert!(club.noise > Strength::from_elo(2000).noise);
+    /// ```
+    pub fn from_elo(elo: u16) -> Self {
+        let steps = elo.clamp(Self::MIN_ELO, Self::MAX_ELO) - Self::MIN_ELO;
+        let depth = 1 + steps / 300;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let node_limit = (50.0 * 2f64.powf(f64::from(steps) / 150.0)) as u64;
+        Strength {
+            depth: depth as u8,
+            node_limit,
+            noise: i32::from(Self::MAX_ELO - Self::MIN_ELO - steps) / 200,
+        }
+    }
+}
+
+/// The optional components an engine may support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineFeatures {
+    /// Whether the engine plays from an opening book.
+    pub book: bool,
+    /// Whether the engine caches search results in a transposition table.
+    pub transposition_table: bool,
+    /// Whether the engine solves endgames exactly.
+    pub endgame_solver: bool,
+}
+
+/// Identification and capabilities of an engine, meant for tooling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub author: &'static str,
+    /// The rule variants the engine can play.
+    pub variants: Vec<&'static str>,
+    /// The side lengths of the boards the engine can play on.
+    pub board_sizes: Vec<usize>,
+    pub features: EngineFeatures,
+}
+
+impl fmt::Display for EngineInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes_no = |supported: bool| if supported { "yes" } else { "no" };
+
+        writeln!(f, "name: {}", self.name)?;
+        writeln!(f, "version: {}", self.version)?;
+        writeln!(f, "author: {}", self.author)?;
+        writeln!(f, "variants: {}", self.variants.join(", "))?;
+        writeln!(
+            f,
+            "board sizes: {}",
+            self.board_sizes
+                .iter()
+                .map(|size| format!("{size}x{size}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(f, "book: {}", yes_no(self.features.book))?;
+        writeln!(
+            f,
+            "transposition table: {}",
+            yes_no(self.features.transposition_table)
+        )?;
+        write!(
+            f,
+            "endgame solver: {}",
+            yes_no(self.features.endgame_solver)
+        )
+    }
+}
+
+/// Statistics accumulated over several searches, e.g. all moves of a bot in a game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStatistics {
+    pub searches: u32,
+    pub nodes: u64,
+    pub hash_probes: u64,
+    pub hash_hits: u64,
+    /// The sum of the depths of all searches.
+    pub total_depth: u64,
+    pub time: Duration,
+}
+
+impl SearchStatistics {
+    /// Add the search the engine just finished, reading and resetting its counters.
+    pub fn record(&mut self, engine: &Engine, depth: u8, time: Duration) {
+        self.searches += 1;
+        self.nodes += engine.nodes();
+        self.hash_probes += engine.hash_probes.load(Ordering::Relaxed);
+        self.hash_hits += engine.hash_hits.load(Ordering::Relaxed);
+        self.total_depth += u64::from(depth);
+        self.time += time;
+        engine.reset_counters();
+    }
+
+    /// The share of transposition table lookups that found a position, from 0 to 1.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hash_hit_rate(&self) -> f64 {
+        self.hash_hits as f64 / self.hash_probes.max(1) as f64
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_depth(&self) -> f64 {
+        self.total_depth as f64 / f64::from(self.searches.max(1))
+    }
+
+    pub fn average_time(&self) -> Duration {
+        self.time / self.searches.max(1)
+    }
+}
+
+impl fmt::Display for SearchStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} searches, average depth {:.1}, {:.2}s per move, {} nodes, hash hit rate {:.1}%",
+            self.searches,
+            self.average_depth(),
+            self.average_time().as_secs_f64(),
+            self.nodes,
+            self.hash_hit_rate() * 100.0
+        )
+    }
+}
+
+/// A minimax search engine with a transposition table.
+///
+/// The engine can be shared between threads, see `EngineHandle`.
+#[derive(Debug)]
+pub struct Engine {
+    table: Mutex<TranspositionTable>,
+    nodes: AtomicU64,
+    hash_probes: AtomicU64,
+    hash_hits: AtomicU64,
+    strength: Mutex<Option<(Strength, u64)>>,
+    weights: Weights,
+    endgames: Mutex<EndgameCache>,
+}
+
+impl Engine {
+    /// The default size of the transposition table in MB.
+    pub const DEFAULT_HASH_SIZE: usize = 16;
+
+    /// Create a new engine with the default hash size.
+    pub fn new() -> Self {
+        Self::with_hash_size(Self::DEFAULT_HASH_SIZE)
+    }
+
+    /// Create a new engine whose transposition table uses at most `megabytes` MB.
+    pub fn with_hash_size(megabytes: usize) -> Self {
+        Engine {
+            table: Mutex::new(TranspositionTable::new(megabytes)),
+            nodes: AtomicU64::new(0),
+            hash_probes: AtomicU64::new(0),
+            hash_hits: AtomicU64::new(0),
+            strength: Mutex::new(None),
+            weights: Weights::default(),
+            endgames: Mutex::default(),
+        }
+    }
+
+    /// Identify the engine and list its capabilities.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::engine::Engine;
+    /// let info = Engine::info();
+    /// assert!(info.features.transposition_table);
+    /// assert_eq!(info.board_sizes, vec![8]);
+    /// ```
+    pub fn info() -> EngineInfo {
+        EngineInfo {
+            name: "reversi-game minimax",
+            version: env!("CARGO_PKG_VERSION"),
+            author: "Leo Blume",
+            variants: vec!["othello", "classic"],
+            board_sizes: vec![8],
+            features: EngineFeatures {
+                book: false,
+                transposition_table: true,
+                endgame_solver: true,
+            },
+        }
+    }
+
+    /// Change the memory limit of the transposition table to `megabytes` MB.
+    pub fn resize_hash(&self, megabytes: usize) {
+        self.table.lock().unwrap().resize(megabytes);
+    }
+
+    /// Forget all cached search results but the exact ones of `endgame_cache`.
+    pub fn clear_hash(&self) {
+        self.table.lock().unwrap().clear();
+    }
+
+    /// Forget the cached search results that can't be used anymore once `board` is reached
+    /// in a game, keeping the rest for the following searches.
+    pub fn prune_hash(&self, board: &Board) {
+        let discs = board.count_pieces(Color::White) + board.count_pieces(Color::Black);
+        self.table.lock().unwrap().prune(discs);
+    }
+
+    /// The solved endgame positions, which stay valid from game to game and can be kept
+    /// between runs, see `EndgameCache`.
+    pub fn endgame_cache(&self) -> MutexGuard<'_, EndgameCache> {
+        self.endgames.lock().unwrap()
+    }
+
+    /// The cached search results, to continue the analysis of a position in a later run with
+    /// `import_hash`. See `TranspositionTable`'s `Display` for the format.
+    pub fn export_hash(&self) -> String {
+        self.table.lock().unwrap().to_string()
+    }
+
+    /// Cache the search results of `export_hash`, as many as fit into the table.
+    /// Returns the number of positions read.
+    pub fn import_hash(&self, hash: &str) -> Result<usize, String> {
+        self.table.lock().unwrap().read(hash)
+    }
+
+    /// The best line from `board` with `color` to move as far as the cached search results
+    /// know it, e.g. after a search. Passes are left out.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::{Engine, Search, SearchLimits}, Board, Color};
+    /// let engine = Engine::new();
+    /// let (best_move, _) = engine.best_move(&Board::new(), Color::White, SearchLimits::depth(4));
+    /// let line = engine.best_line(&Board::new(), Color::White);
+    /// assert_eq!(line.first().copied(), best_move);
+    /// assert!(line.len() <= 4);
+    /// ```
+    pub fn best_line(&self, board: &Board, color: Color) -> Vec<Field> {
+        let table = self.table.lock().unwrap();
+        let (mut board, mut color) = (board.clone(), color);
+        let mut line = Vec::new();
+        // Entries left from other searches may lead further than the search reached.
+        let mut length = None;
+        loop {
+            if board.valid_moves_mask(color) == 0 {
+                color = color.other();
+            }
+            let Some(entry) = table.get(&board, color) else {
+                break;
+            };
+            let length = *length.get_or_insert(usize::from(entry.depth));
+            let Some(field) = entry.best_move.filter(|_| line.len() < length) else {
+                break;
+            };
+            let Ok(after) = board.with_move(field, color) else {
+                break;
+            };
+            line.push(field);
+            board = after;
+            color = color.other();
+        }
+        line
+    }
+
+    /// The memory used and the memory limit of the transposition table in bytes.
+    pub fn hash_usage(&self) -> (usize, usize) {
+        let table = self.table.lock().unwrap();
+        (table.memory_usage(), table.memory_limit())
+    }
+
+    /// The number of positions searched since the last call to `reset_counters`.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Reset the searched positions and transposition table lookup counters.
+    pub fn reset_counters(&self) {
+        self.nodes.store(0, Ordering::Relaxed);
+        self.hash_probes.store(0, Ordering::Relaxed);
+        self.hash_hits.store(0, Ordering::Relaxed);
+    }
+
+    /// The weights of the evaluation function.
+    pub fn weights(&self) -> Weights {
+        self.weights
+    }
+
+    /// Evaluate positions with other weights, discarding the cached search results.
+    pub fn set_weights(&mut self, weights: Weights) {
+        self.weights = weights;
+        self.clear_hash();
+    }
+
+    /// Play weaker, with the node limit and evaluation noise of `strength`.
+    /// The depth is up to the caller.
+    ///
+    /// The noise is random, but the same for a position during the lifetime of the engine.
+    pub fn limit_strength(&self, strength: Strength) {
+        *self.strength.lock().unwrap() = Some((strength, rand::random()));
+        self.clear_hash();
+    }
+
+    /// Count a node of `search`. Now and then, stop the search at its deadline, and pause in the
+    /// low-power mode, see `throttle`.
+    fn count_node(&self, search: &SearchContext) {
+        // Checking the clock at every node would slow the search down.
+        const CHECK_NODES: u64 = 1024;
+
+        let nodes = search.nodes.get();
+        search.nodes.set(nodes + 1);
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+        if nodes.is_multiple_of(CHECK_NODES) {
+            if search
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                search.stopped.set(true);
+            }
+            throttle::pause();
+        }
+    }
+
+    /// Search one move deeper at a time, from depth 1 up to `max_depth`, until `time` is up,
+    /// and return the best move and evaluation of each depth that was searched completely,
+    /// see `Search::best_move`. The search of a depth that runs out of time is cut short and
+    /// thrown away, but depth 1 is always searched, so there is at least one result.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::Engine, Board, Color};
+    /// # use std::time::Duration;
+    /// let engine = Engine::new();
+    /// let depths = engine.deepen(&Board::new(), Color::White, Duration::from_millis(200), 4);
+    /// assert!((1..=4).contains(&depths.len()));
+    ///
+    /// let depths = engine.deepen(&Board::new(), Color::White, Duration::ZERO, 4);
+    /// assert_eq!(depths.len(), 1);
+    /// ```
+    pub fn deepen(
+        &self,
+        board: &Board,
+        to_move: Color,
+        time: Duration,
+        max_depth: u8,
+    ) -> Vec<(Option<Field>, i32)> {
+        let deadline = Instant::now() + time;
+        let mut depths = vec![self.best_move(board, to_move, SearchLimits::depth(1))];
+        for depth in 2..=max_depth {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let result = self.search(
+                board,
+                to_move,
+                SearchLimits::depth(depth),
+                Some(deadline),
+                None,
+            );
+            // Every search that was cut short ended after the deadline.
+            if Instant::now() >= deadline {
+                break;
+            }
+            depths.push(result);
+        }
+        depths
+    }
+
+    /// Evaluate a given board from the view of `color`, usually the side to move.
+    /// This is the evaluation function used by the minimax algorithm.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::Engine, Board, Color, Field};
+    /// # use std::str::FromStr;
+    /// let mut board = Board::new();
+    /// let engine = Engine::new();
+    ///
+    /// assert_eq!(engine.eval(&board, Color::White), 0);
+    ///
+    /// board.add_piece(Field::from_str("d3").unwrap(), Color::White);
+    /// assert_eq!(engine.eval(&board, Color::Black), -3);
+    ///
+    /// board.add_piece(Field::from_str("c3").unwrap(), Color::Black);
+    /// assert_eq!(engine.eval(&board, Color::White), 0);
+    /// ```
+    pub fn eval(&self, board: &Board, color: Color) -> i32 {
+        let strength = *self.strength.lock().unwrap();
+        self.eval_with_status(board, &board.status(), color, strength)
+    }
+
+    /// Evaluate like `eval`, where `status` is the status of `board` and `strength` the strength
+    /// of the engine with the seed of its noise.
+    fn eval_with_status(
+        &self,
+        board: &Board,
+        status: &GameStatus,
+        color: Color,
+        strength: Option<(Strength, u64)>,
+    ) -> i32 {
+        let evaluation = evaluate(board, status, color, &self.weights);
+        match strength {
+            Some((strength, seed)) if strength.noise > 0 && *status == GameStatus::InProgress => {
+                let mut hasher = DefaultHasher::new();
+                (seed, board).hash(&mut hasher);
+                let spread = 2 * strength.noise.unsigned_abs() + 1;
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                let noise = (hasher.finish() % u64::from(spread)) as i32 - strength.noise;
+                evaluation + noise
+            }
+            _ => evaluation,
+        }
+    }
+
+    /// Evaluate many boards from the view of `color` like `eval`,
+    /// returning the evaluations in the same order.
+    ///
+    /// Repeated boards are evaluated only once, and large batches are split across threads.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::Engine, Board, Color};
+    /// let engine = Engine::new();
+    /// let boards = [Board::new(), Board::empty(), Board::new()];
+    ///
+    /// let evaluations = engine.evaluate_batch(&boards, Color::Black);
+    /// assert_eq!(
+    ///     evaluations,
+    ///     boards.iter().map(|board| engine.eval(board, Color::Black)).collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn evaluate_batch(&self, boards: &[Board], color: Color) -> Vec<i32> {
+        // Batches at least this large are worth the cost of spawning threads.
+        const PARALLEL_BATCH: usize = 1024;
+
+        let mut indices = HashMap::new();
+        let mut distinct = Vec::new();
+        let slots: Vec<usize> = boards
+            .iter()
+            .map(|board| {
+                *indices.entry(board).or_insert_with(|| {
+                    distinct.push(board);
+                    distinct.len() - 1
+                })
+            })
+            .collect();
+
+        let threads = throttle::threads();
+        let evaluations: Vec<i32> = if distinct.len() < PARALLEL_BATCH || threads == 1 {
+            distinct
+                .iter()
+                .map(|board| self.eval(board, color))
+                .collect()
+        } else {
+            thread::scope(|scope| {
+                distinct
+                    .chunks(distinct.len().div_ceil(threads))
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|board| self.eval(board, color))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect()
+            })
+        };
+
+        slots.into_iter().map(|slot| evaluations[slot]).collect()
+    }
+
+    /// Negamax with alpha-beta pruning, evaluating from the view of `color`.
+    /// Evaluations outside of `alpha..beta` are only bounds.
+    ///
+    /// A volatile leaf is searched one move deeper, unless the line was `extended` before,
+    /// so a capture right behind the horizon doesn't go unnoticed.
+    ///
+    /// The valid moves of each position are found once, when it is tracked, and reused for its
+    /// status, its moves and its volatility.
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &self,
+        search: &SearchContext,
+        board: &TrackedBoard,
+        depth: u8,
+        color: Color,
+        mut alpha: i32,
+        beta: i32,
+        extended: bool,
+    ) -> (Option<Field>, i32) {
+        let status = board.status();
+        if depth == 0
+            && !extended
+            && status == GameStatus::InProgress
+            && !search.out_of_nodes()
+            && board.volatility(color) >= VOLATILE_DISCS
+        {
+            return self.negamax(search, board, 1, color, alpha, beta, true);
+        }
+        self.count_node(search);
+
+        if depth == 0 || status != GameStatus::InProgress || search.out_of_nodes() {
+            return (
+                None,
+                self.eval_with_status(board, &status, color, search.strength),
+            );
+        }
+
+        let mut moves = board.valid_moves(color);
+        if moves.is_empty() {
+            // The game goes on, so the opponent can move.
+            let (_, evaluation) = self.negamax(
+                search,
+                board,
+                depth - 1,
+                color.other(),
+                -beta,
+                -alpha,
+                extended,
+            );
+            return (None, -evaluation);
+        }
+
+        self.hash_probes.fetch_add(1, Ordering::Relaxed);
+        if let Some(entry) = self.table.lock().unwrap().get(board, color) {
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.evaluation >= beta,
+                Bound::Upper => entry.evaluation <= alpha,
+            };
+            if entry.depth >= depth && usable {
+                self.hash_hits.fetch_add(1, Ordering::Relaxed);
+                search.record(|tree| tree.cut_off(Cutoff::Transposition));
+                return (entry.best_move, entry.evaluation);
+            }
+
+            // Even a shallower search is a good guess for the best move, which makes
+            // the most of the pruning when it is searched first.
+            if let Some(index) = moves
+                .iter()
+                .position(|&field| Some(field) == entry.best_move)
+            {
+                moves[..=index].rotate_right(1);
+            }
+        }
+
+        let window = (alpha, beta);
+        let mut best_choice = (None, -WIN_SCORE);
+
+        for field in moves {
+            let board = TrackedBoard::new(board.with_move(field, color).unwrap());
+
+            search.record(|tree| tree.enter(Some(field), Some(color)));
+            let (_, evaluation) = self.negamax(
+                search,
+                &board,
+                depth - 1,
+                color.other(),
+                -beta,
+                -alpha,
+                extended,
+            );
+            search.record(|tree| tree.leave(evaluation));
+            let evaluation = -evaluation;
+
+            if best_choice.0.is_none() || evaluation > best_choice.1 {
+                best_choice = (Some(field), evaluation);
+            }
+            alpha = alpha.max(evaluation);
+
+            if alpha >= beta {
+                search.record(|tree| tree.cut_off(Cutoff::AlphaBeta));
+                break;
+            }
+        }
+
+        // A search cut short by the node limit is not worth remembering.
+        if search.out_of_nodes() {
+            return best_choice;
+        }
+
+        let bound = if best_choice.1 <= window.0 {
+            Bound::Upper
+        } else if best_choice.1 >= window.1 {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.lock().unwrap().insert(
+            board,
+            color,
+            transposition_table::Entry {
+                depth,
+                evaluation: best_choice.1,
+                best_move: best_choice.0,
+                bound,
+            },
+        );
+
+        best_choice
+    }
+}
+
+impl Engine {
+    /// Run `best_move` and record the visited positions, keeping at most `limit` of them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::{Engine, SearchLimits}, Board, Color};
+    /// let engine = Engine::new();
+    /// let (_, tree) =
+    ///     engine.traced_best_move(&Board::new(), Color::White, SearchLimits::depth(2), 3);
+    ///
+    /// assert_eq!(tree.nodes.len(), 3);
+    /// assert!(tree.truncated > 0);
+    /// ```
+    pub fn traced_best_move(
+        &self,
+        board: &Board,
+        to_move: Color,
+        limits: SearchLimits,
+        limit: usize,
+    ) -> ((Option<Field>, i32), SearchTree) {
+        let mut tree = SearchTree::new(limit);
+        tree.enter(None, None);
+        let tree = RefCell::new(tree);
+
+        let result = self.search(board, to_move, limits, None, Some(&tree));
+
+        let mut tree = tree.into_inner();
+        tree.leave(result.1);
+        (result, tree)
+    }
+
+    /// Search like `Search::best_move`, stopping early at `deadline` and recording the visited
+    /// positions in `tree`, if any.
+    fn search(
+        &self,
+        board: &Board,
+        to_move: Color,
+        limits: SearchLimits,
+        deadline: Option<Instant>,
+        tree: Option<&RefCell<SearchTree>>,
+    ) -> (Option<Field>, i32) {
+        let strength = *self.strength.lock().unwrap();
+        let search = SearchContext::new(limits.nodes, deadline, strength, tree);
+        self.negamax(
+            &search,
+            &TrackedBoard::new(board.clone()),
+            limits.depth,
+            to_move,
+            -WIN_SCORE,
+            WIN_SCORE,
+            false,
+        )
+    }
+
+    /// Solve a position exactly by searching until the end of the game.
+    ///
+    /// # Returns
+    /// The best move for `color` (`None` if it has to pass) and the final disc difference
+    /// from the view of `color`, with empty fields counted for the winner.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::Engine, Board, Color, Field};
+    /// let mut board = Board::empty();
+    /// board.set(Field(0, 0), Some(Color::White));
+    /// board.set(Field(1, 0), Some(Color::Black));
+    ///
+    /// let engine = Engine::new();
+    /// assert_eq!(engine.solve(&board, Color::White), (Some(Field(2, 0)), 64));
+    /// ```
+    pub fn solve(&self, board: &Board, color: Color) -> (Option<Field>, i32) {
+        self.solve_window(
+            &SearchContext::new(None, None, None, None),
+            board,
+            color,
+            -65,
+            65,
+            false,
+        )
+    }
+
+    /// The final score of a finished game from the view of `color`.
+    fn final_score(board: &Board, color: Color) -> i32 {
+        let own = board.count_pieces(color) as i32;
+        let other = board.count_pieces(color.other()) as i32;
+        let empty = 64 - own - other;
+
+        match own.cmp(&other) {
+            std::cmp::Ordering::Greater => own - other + empty,
+            std::cmp::Ordering::Less => own - other - empty,
+            std::cmp::Ordering::Equal => 0,
+        }
+    }
+
+    /// Negamax with alpha-beta pruning until the end of the game.
+    fn solve_window(
+        &self,
+        search: &SearchContext,
+        board: &Board,
+        color: Color,
+        mut alpha: i32,
+        beta: i32,
+        passed: bool,
+    ) -> (Option<Field>, i32) {
+        self.count_node(search);
+
+        let cached = EndgameCache::covers(board);
+        if let Some(result) = cached
+            .then(|| self.endgame_cache().get(board, color))
+            .flatten()
+        {
+            return result;
+        }
+        let window = (alpha, beta);
+
+        let mut moves = board.valid_moves(color);
+        if moves.is_empty() {
+            if passed {
+                return (None, Self::final_score(board, color));
+            }
+            let (_, score) = self.solve_window(search, board, color.other(), -beta, -alpha, true);
+            return (None, -score);
+        }
+
+        // Moving into the odd regions first, and into small ones before large ones, tends to
+        // keep the last move of each region and finds the cutoffs earlier.
+        let regions = board.empty_regions();
+        let region_size = |field: Field| {
+            regions
+                .iter()
+                .find(|region| region.contains(&field))
+                .map_or(0, Vec::len)
+        };
+        moves.sort_by_key(|&field| {
+            let size = region_size(field);
+            (size % 2 == 0, size)
+        });
+
+        let mut best_choice = (None, -65);
+        for field in moves {
+            let board = board.with_move(field, color).unwrap();
+
+            let (_, score) = self.solve_window(search, &board, color.other(), -beta, -alpha, false);
+            let score = -score;
+
+            if score > best_choice.1 {
+                best_choice = (Some(field), score);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // Scores outside of the window are only bounds.
+        if cached && window.0 < best_choice.1 && best_choice.1 < window.1 {
+            self.endgame_cache().insert(board, color, best_choice);
+        }
+        best_choice
+    }
+}
+
+/// A negamax search with alpha-beta pruning and a transposition table.
+impl Search for Engine {
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::{Engine, Search, SearchLimits}, Board, Color};
+    /// let board = Board::new();
+    /// let engine = Engine::new();
+    ///
+    /// let (best_move, _) = engine.best_move(&board, Color::White, SearchLimits::depth(2));
+    /// assert!(board.valid_moves(Color::White).contains(&best_move.unwrap()));
+    /// ```
+    fn best_move(
+        &self,
+        board: &Board,
+        to_move: Color,
+        limits: SearchLimits,
+    ) -> (Option<Field>, i32) {
+        self.search(board, to_move, limits, None, None)
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
+}
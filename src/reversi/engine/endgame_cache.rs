@@ -0,0 +1,143 @@
+//! The exact results of endgame positions the solver has seen, so the same tails of different
+//! games aren't solved again, and the file format they are kept in between runs.
+//!
+//! An endgame file starts with its format version, followed by one position per line: the
+//! discs of White and Black as hexadecimal bitboards, the side to move, the best move (`--` for
+//! a pass) and the final score for the side to move.
+//!
+//! ```text
+//! version = 1
+//! 000000003fffffff 003fffffc0000000 O g2 12
+//! ```
+
+use super::{parse_move, parse_position, write_move, write_position};
+use crate::reversi::{Board, Color, Field};
+
+use std::{collections::HashMap, fmt, ops::RangeInclusive, str::FromStr};
+
+/// The solved positions, see the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndgameCache {
+    results: HashMap<(Board, Color), (Option<Field>, i32)>,
+}
+
+impl EndgameCache {
+    /// The version of the file format written by `Display`.
+    pub const VERSION: u32 = 1;
+    /// The numbers of empty fields of the cached positions. Fewer are solved faster than they
+    /// are looked up, more are hardly ever reached twice.
+    pub const EMPTIES: RangeInclusive<u32> = 6..=14;
+
+    pub fn new() -> Self {
+        EndgameCache::default()
+    }
+
+    /// The number of cached positions.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Whether positions like `board` are cached, see `EMPTIES`.
+    pub fn covers(board: &Board) -> bool {
+        let empties = (board.bitboard(Color::White) | board.bitboard(Color::Black)).count_zeros();
+        Self::EMPTIES.contains(&empties)
+    }
+
+    /// The best move and the final score of `color` to move on `board`, if it was solved.
+    pub fn get(&self, board: &Board, color: Color) -> Option<(Option<Field>, i32)> {
+        self.results.get(&(board.clone(), color)).copied()
+    }
+
+    /// Keep the exact result of a solved position, unless it has too few or too many empty
+    /// fields to be worth it, see `EMPTIES`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::EndgameCache, Board, Color};
+    /// let mut cache = EndgameCache::new();
+    /// cache.insert(&Board::new(), Color::White, (None, 0));
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn insert(&mut self, board: &Board, color: Color, result: (Option<Field>, i32)) {
+        if Self::covers(board) {
+            self.results.insert((board.clone(), color), result);
+        }
+    }
+
+    /// Add the positions of `other`, e.g. those solved by another engine.
+    pub fn merge(&mut self, other: EndgameCache) {
+        self.results.extend(other.results);
+    }
+}
+
+impl fmt::Display for EndgameCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "version = {}", Self::VERSION)?;
+        let mut results: Vec<_> = self.results.iter().collect();
+        // The same cache is always written the same way.
+        results.sort_by_key(|((board, color), _)| {
+            (
+                board.bitboard(Color::White),
+                board.bitboard(Color::Black),
+                *color,
+            )
+        });
+        for ((board, color), (best_move, score)) in results {
+            writeln!(
+                f,
+                "{} {} {score}",
+                write_position(board, *color),
+                write_move(*best_move)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads an endgame file, see the module documentation.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{engine::EndgameCache, Board, Color, Field};
+/// let mut board = Board::empty();
+/// for x in 0..7 {
+///     board.set(Field(x, 0), Some(Color::White));
+///     board.set(Field(x, 1), Some(Color::Black));
+/// }
+/// let mut cache = EndgameCache::new();
+/// cache.insert(&board, Color::White, (Some(Field(7, 2)), 62));
+/// assert_eq!(cache.to_string().parse(), Ok(cache));
+///
+/// assert!("version = 2".parse::<EndgameCache>().is_err());
+/// ```
+impl FromStr for EndgameCache {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty());
+        match lines.next() {
+            Some(line) if line == format!("version = {}", Self::VERSION) => {}
+            Some(line) => return Err(format!("Unsupported endgame file version `{line}`")),
+            None => return Ok(EndgameCache::new()),
+        }
+
+        let mut cache = EndgameCache::new();
+        for line in lines {
+            let invalid = || format!("Invalid endgame `{line}`");
+            let [white, black, color, best_move, score] = line
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| invalid())?;
+            let (board, color) = parse_position(white, black, color).ok_or_else(invalid)?;
+            let best_move = parse_move(best_move).ok_or_else(invalid)?;
+            let score = score.parse().map_err(|_| invalid())?;
+            cache.insert(&board, color, (best_move, score));
+        }
+        Ok(cache)
+    }
+}
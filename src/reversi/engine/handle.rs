@@ -0,0 +1,52 @@
+use super::{Engine, Search, SearchLimits};
+use crate::reversi::{Board, Color, Field};
+
+use std::{
+    ops::Deref,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+/// A shared handle to an engine, for searching without blocking the calling thread.
+///
+/// Clones refer to the same engine, so searches running at the same time share
+/// its transposition table and counters.
+#[derive(Debug, Clone, Default)]
+pub struct EngineHandle(Arc<Engine>);
+
+impl EngineHandle {
+    pub fn new(engine: Engine) -> Self {
+        EngineHandle(Arc::new(engine))
+    }
+
+    /// Search the best move of `to_move` on a background thread.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::{EngineHandle, SearchLimits}, Board, Color};
+    /// let handle = EngineHandle::default();
+    /// let search = handle.analyze_async(&Board::new(), Color::White, SearchLimits::depth(3));
+    ///
+    /// // ... handle input while the engine thinks ...
+    /// let (best_move, _) = search.join().unwrap();
+    /// assert!(Board::new().is_valid(best_move.unwrap(), Color::White));
+    /// ```
+    pub fn analyze_async(
+        &self,
+        board: &Board,
+        to_move: Color,
+        limits: SearchLimits,
+    ) -> JoinHandle<(Option<Field>, i32)> {
+        let engine = Arc::clone(&self.0);
+        let board = board.clone();
+        thread::spawn(move || engine.best_move(&board, to_move, limits))
+    }
+}
+
+impl Deref for EngineHandle {
+    type Target = Engine;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,162 @@
+use crate::reversi::{Color, Field};
+
+use std::fmt::Write;
+
+/// The reason a node was not searched any further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cutoff {
+    /// The result was taken from the transposition table.
+    Transposition,
+    /// The remaining moves were pruned because the opponent would avoid this position.
+    AlphaBeta,
+}
+
+impl Cutoff {
+    fn name(self) -> &'static str {
+        match self {
+            Cutoff::Transposition => "transposition",
+            Cutoff::AlphaBeta => "alpha-beta",
+        }
+    }
+}
+
+/// A searched position in a `SearchTree`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub parent: Option<usize>,
+    /// The move leading to this position, `None` for the root.
+    pub played: Option<Field>,
+    /// The color that played the move leading to this position.
+    pub color: Option<Color>,
+    /// The evaluation of the position from the view of the side to move,
+    /// once its search has finished.
+    pub score: Option<i32>,
+    pub cutoff: Option<Cutoff>,
+}
+
+/// A record of the positions visited by a single search.
+///
+/// Only the first `limit` positions are recorded, the rest are counted in `truncated`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTree {
+    pub nodes: Vec<TreeNode>,
+    pub truncated: usize,
+    limit: usize,
+    /// The path from the root to the node currently searched, `None` for unrecorded nodes.
+    path: Vec<Option<usize>>,
+}
+
+impl SearchTree {
+    /// Create an empty tree recording at most `limit` positions.
+    pub fn new(limit: usize) -> Self {
+        SearchTree {
+            nodes: Vec::new(),
+            truncated: 0,
+            limit,
+            path: Vec::new(),
+        }
+    }
+
+    /// Start searching a position reached by `played`.
+    pub(crate) fn enter(&mut self, played: Option<Field>, color: Option<Color>) {
+        let parent = self.path.last().copied().flatten();
+
+        if self.nodes.len() < self.limit && (self.path.is_empty() || parent.is_some()) {
+            self.nodes.push(TreeNode {
+                parent,
+                played,
+                color,
+                score: None,
+                cutoff: None,
+            });
+            self.path.push(Some(self.nodes.len() - 1));
+        } else {
+            self.truncated += 1;
+            self.path.push(None);
+        }
+    }
+
+    /// Finish searching the current position.
+    pub(crate) fn leave(&mut self, score: i32) {
+        if let Some(Some(index)) = self.path.pop() {
+            self.nodes[index].score = Some(score);
+        }
+    }
+
+    /// Mark the current position as cut off.
+    pub(crate) fn cut_off(&mut self, cutoff: Cutoff) {
+        if let Some(Some(index)) = self.path.last() {
+            self.nodes[*index].cutoff = Some(cutoff);
+        }
+    }
+
+    fn label(node: &TreeNode) -> String {
+        let played = match (node.played, node.color) {
+            (Some(field), Some(color)) => format!("{} {field}", char::from(color)),
+            _ => "root".to_owned(),
+        };
+        match node.score {
+            Some(score) => format!("{played}\\n{score:+}"),
+            None => played,
+        }
+    }
+
+    /// Render the tree in the Graphviz DOT language.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n    node [shape=box, fontname=monospace];\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let style = match node.cutoff {
+                Some(_) => ", style=dashed, color=gray",
+                None => "",
+            };
+            writeln!(
+                dot,
+                "    n{index} [label=\"{}\"{style}];",
+                Self::label(node)
+            )
+            .unwrap();
+            if let Some(parent) = node.parent {
+                writeln!(dot, "    n{parent} -> n{index};").unwrap();
+            }
+        }
+
+        if self.truncated > 0 {
+            writeln!(
+                dot,
+                "    truncated [label=\"{} more positions\", shape=plaintext];",
+                self.truncated
+            )
+            .unwrap();
+        }
+
+        dot + "}\n"
+    }
+
+    /// Render the tree as JSON: a flat list of nodes referring to their parent's index.
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "null".to_owned());
+
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                format!(
+                    "{{\"id\": {index}, \"parent\": {}, \"move\": {}, \"color\": {}, \"score\": {}, \"cutoff\": {}}}",
+                    optional(node.parent.map(|parent| parent.to_string())),
+                    optional(node.played.map(|field| format!("\"{field}\""))),
+                    optional(node.color.map(|color| format!("\"{}\"", char::from(color)))),
+                    optional(node.score.map(|score| score.to_string())),
+                    optional(node.cutoff.map(|cutoff| format!("\"{}\"", cutoff.name()))),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n    ");
+
+        format!(
+            "{{\n  \"truncated\": {},\n  \"nodes\": [\n    {nodes}\n  ]\n}}\n",
+            self.truncated
+        )
+    }
+}
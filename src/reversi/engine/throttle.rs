@@ -0,0 +1,66 @@
+//! A low-power mode for searches in the background, e.g. long self-play or analysis batches on
+//! a laptop. Each search thread pauses as long as it worked, so it uses about half of a core,
+//! and batches of work are split across at most half of the cores.
+
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Whether the low-power mode is on, see `set_low_power`.
+static LOW_POWER: AtomicBool = AtomicBool::new(false);
+
+/// How long a search thread works before it pauses as long in the low-power mode.
+const SLICE: Duration = Duration::from_millis(10);
+
+thread_local! {
+    /// When the thread last started working after a pause.
+    static WORKING_SINCE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Whether searches run in the low-power mode.
+pub fn low_power() -> bool {
+    LOW_POWER.load(Ordering::Relaxed)
+}
+
+/// Turn the low-power mode on or off for all searches of the program.
+pub fn set_low_power(enabled: bool) {
+    LOW_POWER.store(enabled, Ordering::Relaxed);
+}
+
+/// The number of threads a batch of work may use: one per core, or half as many in the
+/// low-power mode.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::engine::throttle;
+/// assert!(throttle::threads() >= 1);
+/// ```
+pub fn threads() -> usize {
+    let cores = thread::available_parallelism().map_or(1, usize::from);
+    if low_power() {
+        cores.div_ceil(2)
+    } else {
+        cores
+    }
+}
+
+/// Called by the search now and then: in the low-power mode, sleep for a `SLICE` once the
+/// thread has worked that long since its last pause.
+pub(crate) fn pause() {
+    if !low_power() {
+        return;
+    }
+    let now = Instant::now();
+    match WORKING_SINCE.get() {
+        // A search that starts after a while of idling pauses once and then works a slice.
+        Some(since) if now - since >= SLICE => {
+            thread::sleep(SLICE);
+            WORKING_SINCE.set(Some(Instant::now()));
+        }
+        Some(_) => {}
+        None => WORKING_SINCE.set(Some(now)),
+    }
+}
@@ -0,0 +1,125 @@
+//! Sharing the thinking time of a game between its moves by how much they matter.
+
+use crate::reversi::{Board, Color};
+
+use std::{ops::RangeInclusive, time::Duration};
+
+/// Decides how long to think about each move, aiming for `per_move` on average.
+///
+/// Instead of the same time for every move, a move gets more where mistakes are likely and
+/// costly and less where there is little to decide: nothing for the only valid move, more
+/// with only a few valid moves left, after the evaluation swung, in volatile positions and
+/// just before the endgame. The time saved on easy moves goes to the critical ones.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{engine::TimeManager, Board, Color};
+/// # use std::time::Duration;
+/// let mut time = TimeManager::new(Duration::from_secs(2));
+/// let board = Board::new();
+/// assert_eq!(time.budget(&board, Color::White), Duration::from_secs(2));
+///
+/// // The bot moved at once, so it can afford more on a critical move later.
+/// time.record(Duration::ZERO, 0);
+/// assert_eq!(time.saved(), Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeManager {
+    per_move: Duration,
+    /// The time saved on earlier moves in seconds, negative if they took longer.
+    saved: f64,
+    /// The evaluations of the last two moves, the last one first.
+    evaluations: [Option<i32>; 2],
+}
+
+impl TimeManager {
+    /// The most time a move gets, as a multiple of `per_move`.
+    const MAX_WEIGHT: f64 = 3.0;
+    /// The most time saved for later moves, as a multiple of `per_move`.
+    const MAX_SAVED: f64 = 4.0;
+    /// A change of the evaluation by this many discs between two moves makes the position
+    /// unstable.
+    const SWING: i32 = 6;
+    /// Positions where the next move can flip this many discs are volatile,
+    /// see `Board::volatility`.
+    const VOLATILE: usize = 10;
+    /// The numbers of empty fields just before the endgame, where the engine starts to see the
+    /// end of the game and the choices decide it.
+    const PRE_ENDGAME: RangeInclusive<u32> = 15..=24;
+
+    pub fn new(per_move: Duration) -> Self {
+        TimeManager {
+            per_move,
+            saved: 0.0,
+            evaluations: [None; 2],
+        }
+    }
+
+    /// The average time per move aimed for.
+    pub fn per_move(&self) -> Duration {
+        self.per_move
+    }
+
+    /// The time saved on earlier moves, to be spent on critical ones.
+    pub fn saved(&self) -> Duration {
+        Duration::from_secs_f64(self.saved.max(0.0))
+    }
+
+    /// How much time the move of `color` on `board` deserves compared to an average move.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::TimeManager, Board, Color, Field};
+    /// # use std::time::Duration;
+    /// let time = TimeManager::new(Duration::from_secs(1));
+    /// let mut forced = Board::empty();
+    /// forced.set(Field(0, 0), Some(Color::White));
+    /// forced.set(Field(1, 0), Some(Color::Black));
+    /// assert_eq!(time.weight(&forced, Color::White), 0.0);
+    /// assert_eq!(time.weight(&Board::new(), Color::White), 1.0);
+    /// ```
+    pub fn weight(&self, board: &Board, color: Color) -> f64 {
+        let mobility = board.mobility(color);
+        if mobility <= 1 {
+            return 0.0;
+        }
+
+        let mut weight: f64 = 1.0;
+        if mobility <= 3 {
+            weight *= 1.5;
+        }
+        if let [Some(last), Some(before)] = self.evaluations {
+            if (last - before).abs() >= Self::SWING {
+                weight *= 1.5;
+            }
+        }
+        if board.volatility(color) >= Self::VOLATILE {
+            weight *= 1.25;
+        }
+        let empties = 64 - board.count_pieces(Color::White) - board.count_pieces(Color::Black);
+        if u32::try_from(empties).is_ok_and(|empties| Self::PRE_ENDGAME.contains(&empties)) {
+            weight *= 1.5;
+        }
+        weight.min(Self::MAX_WEIGHT)
+    }
+
+    /// The time to think about the move of `color` on `board`: its share by `weight`, and on
+    /// critical moves half of the time saved before.
+    pub fn budget(&self, board: &Board, color: Color) -> Duration {
+        let weight = self.weight(board, color);
+        let per_move = self.per_move.as_secs_f64();
+        let mut seconds = per_move * weight;
+        if weight > 1.0 {
+            seconds += self.saved.max(0.0) / 2.0;
+        }
+        Duration::from_secs_f64(seconds.min(per_move * (Self::MAX_WEIGHT + 1.0)))
+    }
+
+    /// Take note of a move that took `used` and was evaluated as `evaluation` by the player.
+    pub fn record(&mut self, used: Duration, evaluation: i32) {
+        let per_move = self.per_move.as_secs_f64();
+        self.saved = (self.saved + per_move - used.as_secs_f64())
+            .clamp(-per_move, per_move * Self::MAX_SAVED);
+        self.evaluations = [Some(evaluation), self.evaluations[0]];
+    }
+}
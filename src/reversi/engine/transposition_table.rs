@@ -0,0 +1,180 @@
+use super::{parse_move, parse_position, write_move, write_position};
+use crate::reversi::{Board, Color, Field};
+
+use std::{collections::HashMap, fmt, mem};
+
+/// How an `Entry`'s evaluation relates to the real evaluation of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The evaluation is exact.
+    Exact,
+    /// The real evaluation is at least as high.
+    Lower,
+    /// The real evaluation is at most as high.
+    Upper,
+}
+
+/// A cached search result for a position.
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    /// The remaining depth the position was searched with.
+    pub depth: u8,
+    /// The evaluation of the position from the view of the side to move.
+    pub evaluation: i32,
+    /// The best move found in the position, if any.
+    pub best_move: Option<Field>,
+    /// Whether the search was cut short, leaving only a bound of the evaluation.
+    pub bound: Bound,
+}
+
+/// A size-limited cache of already searched positions.
+///
+/// The table never grows beyond the amount of memory it was created with;
+/// once it is full, new positions are simply not stored anymore.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    entries: HashMap<(Board, Color), Entry>,
+    max_entries: usize,
+}
+
+impl TranspositionTable {
+    /// The approximate amount of bytes a single stored position occupies,
+    /// including the control byte of the hash map.
+    const ENTRY_SIZE: usize = mem::size_of::<((Board, Color), Entry)>() + 1;
+
+    /// Create a new table that uses at most `megabytes` MB of memory.
+    pub fn new(megabytes: usize) -> Self {
+        TranspositionTable {
+            entries: HashMap::new(),
+            max_entries: Self::capacity_for(megabytes),
+        }
+    }
+
+    fn capacity_for(megabytes: usize) -> usize {
+        megabytes * 1024 * 1024 / Self::ENTRY_SIZE
+    }
+
+    /// Change the memory limit of the table.
+    /// If the table shrinks, all stored positions are discarded.
+    pub fn resize(&mut self, megabytes: usize) {
+        let max_entries = Self::capacity_for(megabytes);
+        if max_entries < self.entries.len() {
+            self.entries = HashMap::new();
+        }
+        self.max_entries = max_entries;
+    }
+
+    /// Look up a position searched for the given color to move.
+    pub fn get(&self, board: &Board, color: Color) -> Option<Entry> {
+        self.entries.get(&(board.clone(), color)).copied()
+    }
+
+    /// Store a search result, unless the table is full.
+    /// Positions already in the table are always updated.
+    pub fn insert(&mut self, board: &Board, color: Color, entry: Entry) {
+        let key = (board.clone(), color);
+        if let Some(stored) = self.entries.get_mut(&key) {
+            *stored = entry;
+        } else if self.entries.len() < self.max_entries {
+            self.entries.insert(key, entry);
+        }
+    }
+
+    /// Remove all positions with less than `discs` discs.
+    ///
+    /// Discs are never removed during a game, so such positions can't be reached anymore
+    /// once a position with `discs` discs is on the board.
+    pub fn prune(&mut self, discs: usize) {
+        self.entries.retain(|(board, _), _| {
+            board.count_pieces(Color::White) + board.count_pieces(Color::Black) >= discs
+        });
+    }
+
+    /// Remove all stored positions, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The amount of bytes occupied by the stored positions.
+    pub fn memory_usage(&self) -> usize {
+        self.entries.len() * Self::ENTRY_SIZE
+    }
+
+    /// The maximum amount of bytes the table may use.
+    pub fn memory_limit(&self) -> usize {
+        self.max_entries * Self::ENTRY_SIZE
+    }
+
+    /// Store the positions written by `Display`, as many as fit.
+    /// Returns the number of positions read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::{Engine, Search, SearchLimits, TranspositionTable}, Board, Color};
+    /// let engine = Engine::new();
+    /// engine.best_move(&Board::new(), Color::White, SearchLimits::depth(3));
+    ///
+    /// let mut table = TranspositionTable::new(1);
+    /// let saved = engine.export_hash();
+    /// assert_eq!(table.read(&saved), Ok(saved.lines().count()));
+    /// assert_eq!(table.to_string(), saved);
+    /// ```
+    pub fn read(&mut self, s: &str) -> Result<usize, String> {
+        let mut count = 0;
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let invalid = || format!("Invalid position `{line}`");
+            let [white, black, color, depth, evaluation, best_move, bound] = line
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| invalid())?;
+            let (board, color) = parse_position(white, black, color).ok_or_else(invalid)?;
+            let entry = Entry {
+                depth: depth.parse().map_err(|_| invalid())?,
+                evaluation: evaluation.parse().map_err(|_| invalid())?,
+                best_move: parse_move(best_move).ok_or_else(invalid)?,
+                bound: match bound {
+                    "exact" => Bound::Exact,
+                    "lower" => Bound::Lower,
+                    "upper" => Bound::Upper,
+                    _ => return Err(invalid()),
+                },
+            };
+            self.insert(&board, color, entry);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Writes one position per line: the position, the depth, the evaluation, the best move and
+/// the bound, e.g. `0000000810000000 0000001008000000 O 3 1 d3 exact`.
+impl fmt::Display for TranspositionTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        // The same table is always written the same way.
+        entries.sort_by_key(|((board, color), _)| {
+            (
+                board.bitboard(Color::White),
+                board.bitboard(Color::Black),
+                *color,
+            )
+        });
+        for ((board, color), entry) in entries {
+            let bound = match entry.bound {
+                Bound::Exact => "exact",
+                Bound::Lower => "lower",
+                Bound::Upper => "upper",
+            };
+            writeln!(
+                f,
+                "{} {} {} {} {bound}",
+                write_position(board, *color),
+                entry.depth,
+                entry.evaluation,
+                write_move(entry.best_move)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+//! The parameters of the evaluation function and the file format they are shared in.
+//!
+//! A weights file has one `key = value` line per weight and starts with its format version:
+//!
+//! ```text
+//! # Prefers corners and mobility over discs.
+//! version = 1
+//! discs = 1
+//! mobility = 2
+//! corners = 10
+//! x_squares = -5
+//! edges = 1
+//! parity = 0
+//! ```
+//!
+//! Missing weights keep their default value.
+
+use crate::reversi::{Board, Color, Field};
+
+use std::{fmt, str::FromStr};
+
+/// The weights of the features of a position, each counted for one side minus for the other.
+///
+/// The default weights only count discs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weights {
+    pub discs: i32,
+    /// The number of valid moves.
+    pub mobility: i32,
+    pub corners: i32,
+    /// The fields diagonally next to an empty corner, which give the corner away.
+    pub x_squares: i32,
+    /// The fields on the edges, except for the corners.
+    pub edges: i32,
+    /// The empty regions with an odd number of fields that a side can move into,
+    /// see `Board::empty_regions`.
+    pub parity: i32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            discs: 1,
+            mobility: 0,
+            corners: 0,
+            x_squares: 0,
+            edges: 0,
+            parity: 0,
+        }
+    }
+}
+
+impl Weights {
+    /// The version of the file format written by `Display`.
+    pub const VERSION: u32 = 1;
+
+    const CORNERS: [(Field, Field); 4] = [
+        (Field(0, 0), Field(1, 1)),
+        (Field(7, 0), Field(6, 1)),
+        (Field(0, 7), Field(1, 6)),
+        (Field(7, 7), Field(6, 6)),
+    ];
+
+    /// The weighted features of a position that is still in progress, from the view of `color`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{engine::Weights, Board, Color};
+    /// let mut board = Board::new();
+    /// board.add_piece("d3".parse().unwrap(), Color::White).unwrap();
+    /// assert_eq!(Weights::default().evaluate(&board, Color::White), 3);
+    /// assert_eq!(Weights::default().evaluate(&board, Color::Black), -3);
+    ///
+    /// let mobility = Weights { discs: 0, mobility: 1, ..Default::default() };
+    /// assert_eq!(
+    ///     mobility.evaluate(&board, Color::White),
+    ///     board.mobility(Color::White) as i32 - board.mobility(Color::Black) as i32
+    /// );
+    /// ```
+    pub fn evaluate(&self, board: &Board, color: Color) -> i32 {
+        let difference =
+            |count: &dyn Fn(Color) -> usize| count(color) as i32 - count(color.other()) as i32;
+        let owned = |color, fields: &mut dyn Iterator<Item = Field>| {
+            fields.filter(|&field| board[field] == Some(color)).count()
+        };
+
+        let mut evaluation = self.discs * difference(&|color| board.count_pieces(color));
+        if self.mobility != 0 {
+            evaluation += self.mobility * difference(&|color| board.mobility(color));
+        }
+        if self.corners != 0 {
+            evaluation += self.corners
+                * difference(&|color| {
+                    owned(color, &mut Self::CORNERS.iter().map(|&(corner, _)| corner))
+                });
+        }
+        if self.x_squares != 0 {
+            evaluation += self.x_squares
+                * difference(&|color| {
+                    owned(
+                        color,
+                        &mut Self::CORNERS
+                            .iter()
+                            .filter(|&&(corner, _)| board[corner].is_none())
+                            .map(|&(_, x_square)| x_square),
+                    )
+                });
+        }
+        if self.edges != 0 {
+            evaluation += self.edges
+                * difference(&|color| {
+                    owned(
+                        color,
+                        &mut Field::all().filter(|field| {
+                            let on_edge = |coordinate| coordinate == 0 || coordinate == 7;
+                            on_edge(field.0) != on_edge(field.1)
+                        }),
+                    )
+                });
+        }
+        if self.parity != 0 {
+            let regions = board.empty_regions();
+            evaluation += self.parity
+                * difference(&|color| {
+                    regions
+                        .iter()
+                        .filter(|region| {
+                            region.len() % 2 == 1
+                                && region.iter().any(|&field| board.is_valid(field, color))
+                        })
+                        .count()
+                });
+        }
+        evaluation
+    }
+}
+
+/// Writes the weights in the file format.
+impl fmt::Display for Weights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "version = {}", Self::VERSION)?;
+        writeln!(f, "discs = {}", self.discs)?;
+        writeln!(f, "mobility = {}", self.mobility)?;
+        writeln!(f, "corners = {}", self.corners)?;
+        writeln!(f, "x_squares = {}", self.x_squares)?;
+        writeln!(f, "edges = {}", self.edges)?;
+        writeln!(f, "parity = {}", self.parity)
+    }
+}
+
+/// Reads a weights file, see the module documentation.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::engine::Weights;
+/// let weights = Weights { corners: 10, ..Default::default() };
+/// assert_eq!(weights.to_string().parse(), Ok(weights));
+///
+/// assert!("discs = 1".parse::<Weights>().is_err());
+/// assert!("version = 2".parse::<Weights>().is_err());
+/// ```
+impl FromStr for Weights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights::default();
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.trim(), value.trim()))
+                    .ok_or_else(|| format!("Expected `key = value`, got `{line}`"))
+            });
+
+        match lines.next().transpose()? {
+            Some(("version", version)) if version == Self::VERSION.to_string() => {}
+            Some(("version", version)) => {
+                return Err(format!("Unsupported weights version `{version}`"))
+            }
+            _ => return Err("The weights must start with their `version`".to_owned()),
+        }
+
+        for line in lines {
+            let (key, value) = line?;
+            let value = value
+                .parse()
+                .map_err(|_| format!("Invalid weight `{value}` for `{key}`"))?;
+            match key {
+                "discs" => weights.discs = value,
+                "mobility" => weights.mobility = value,
+                "corners" => weights.corners = value,
+                "x_squares" => weights.x_squares = value,
+                "edges" => weights.edges = value,
+                "parity" => weights.parity = value,
+                other => return Err(format!("Unknown weight `{other}`")),
+            }
+        }
+
+        Ok(weights)
+    }
+}
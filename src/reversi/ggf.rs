@@ -0,0 +1,308 @@
+//! Reading and writing games in the Generic Game Format of GGS and `NBoard`.
+//!
+//! A game is written in `(;` and `;)` as properties like `PB[name]`: the players `PB` and
+//! `PW`, the date `DT`, the result `RE`, the type of game `TY`, the start position `BO` and the
+//! moves `B[f5]` and `W[d6]`, optionally followed by an evaluation and the time it took, as in
+//! `B[f5/0.50/1.2]`. A pass is written as `PA`.
+//!
+//! ```text
+//! (;GM[Othello]PC[NBoard]DT[2014-02-21]PB[Ann]PW[Bo]RE[+2.000]TI[0:00//0:00]TY[8]
+//! BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[f5]W[d6];)
+//! ```
+//!
+//! In Othello Black moves first, so GGF's black player is White here and its white player
+//! Black. The fields are named the same, but the board of `BO` lists rank 1 first.
+
+use crate::reversi::{
+    archive,
+    rules::{self, Position, Variant},
+    Board, Color, Field, GameStatus, Move, Notation,
+};
+
+use std::{fmt::Write, time::Duration};
+
+/// The character of `color` on a `BO` board and for the side to move.
+fn disc(color: Color) -> char {
+    match color {
+        Color::White => '*',
+        Color::Black => 'O',
+    }
+}
+
+/// The properties of one game, as pairs of names and unescaped values.
+fn properties(game: &str) -> Result<Vec<(String, String)>, String> {
+    let mut properties = Vec::new();
+    let mut chars = game.chars();
+    let mut name = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err(format!("The value of {name} is not closed")),
+                    }
+                }
+                properties.push((std::mem::take(&mut name), value));
+            }
+            c if c.is_ascii_alphabetic() => name.push(c),
+            c if c.is_whitespace() => {}
+            c => return Err(format!("Unexpected `{c}`")),
+        }
+    }
+    Ok(properties)
+}
+
+/// The position of a `BO` property, like `8 <64 fields> *`.
+fn parse_board(value: &str) -> Result<Position, String> {
+    let chars: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let (Some(('8', rest)), Some(&to_move)) = (chars.split_first(), chars.last()) else {
+        return Err("Only 8x8 boards are supported".to_owned());
+    };
+    if rest.len() != 65 {
+        return Err("A board needs 64 fields and the side to move".to_owned());
+    }
+    let color = |c| match c {
+        '*' | 'X' | 'x' | '#' => Ok(Some(Color::White)),
+        'O' | 'o' => Ok(Some(Color::Black)),
+        '-' | '.' => Ok(None),
+        c => Err(format!("Invalid field `{c}`")),
+    };
+    let mut board = Board::empty();
+    for (index, &c) in rest[..64].iter().enumerate() {
+        board.set(Field(index % 8, 7 - index / 8), color(c)?);
+    }
+    let to_move = color(to_move)?.ok_or("The board needs the side to move")?;
+    Ok(Position::from_board(board, to_move))
+}
+
+/// The `BO` property of `position`.
+fn write_board(position: &Position) -> String {
+    let rows: Vec<String> = (0..8)
+        .rev()
+        .map(|y| {
+            (0..8)
+                .map(|x| position.board()[Field(x, y)].map_or('-', disc))
+                .collect()
+        })
+        .collect();
+    format!("8 {} {}", rows.join(" "), disc(position.to_move()))
+}
+
+/// A move like `f5/0.50/1.2`, with the time it took if it was given.
+fn parse_move(value: &str) -> Result<(Move, Option<Duration>), String> {
+    let mut parts = value.split('/');
+    let played = match parts
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "pa" | "pass" => Move::Pass,
+        field => Move::Place(
+            Notation::Standard
+                .parse(field)
+                .map_err(|error| format!("Invalid move `{value}`: {error}"))?,
+        ),
+    };
+    let time = parts
+        .nth(1)
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+    Ok((played, time))
+}
+
+/// The game between `(;` and `;)`.
+fn parse_game(text: &str) -> Result<archive::Game, String> {
+    let mut saved = archive::Game::new();
+    let mut game = rules::Game::new();
+    let mut times = Vec::new();
+    let mut termination = None;
+    for (name, value) in properties(text)? {
+        match name.as_str() {
+            "GM" if !value.eq_ignore_ascii_case("othello") => {
+                return Err(format!("{value} is not Othello"));
+            }
+            "TY" if value.contains('a') => {
+                return Err("Anti-Othello is not supported".to_owned());
+            }
+            "PB" => saved.set_header("White", value),
+            "PW" => saved.set_header("Black", value),
+            "PC" => saved.set_header("Site", value),
+            "DT" => {
+                let date: String = value.chars().take(10).collect();
+                let parts: Vec<&str> = date.split(['-', '.']).collect();
+                if let [year, month, day] = parts[..] {
+                    saved.set_header("Date", format!("{year}.{month}.{day}"));
+                }
+            }
+            "RE" => {
+                termination = match value.rsplit_once(':').map(|(_, reason)| reason) {
+                    Some("r") => Some("resignation"),
+                    Some("t") => Some("time forfeit"),
+                    _ => None,
+                };
+            }
+            "BO" => {
+                if !game.history().is_empty() {
+                    return Err("The board must come before the moves".to_owned());
+                }
+                let start = parse_board(&value)?;
+                saved.set_start(&start);
+                game = rules::Game::from_position(start);
+            }
+            "B" | "W" => {
+                let color = if name == "B" {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                if color != game.current_player() && game.position().must_pass() {
+                    game.pass().unwrap();
+                    times.push(Some(Duration::ZERO));
+                }
+                let number = game.history().len() + 1;
+                if color != game.current_player() {
+                    return Err(format!("Move {number}: It's not {color}'s turn"));
+                }
+                let (played, time) = parse_move(&value)?;
+                game.play_move(played.field())
+                    .map_err(|error| format!("Move {number} ({value}): {error}"))?;
+                times.push(time);
+            }
+            _ => {}
+        }
+    }
+
+    saved.set_header("Result", archive::Game::result_of(game.board()));
+    if let Some(termination) = termination {
+        saved.set_header("Termination", termination);
+    }
+    if let Some(times) = times.into_iter().collect::<Option<Vec<_>>>() {
+        if !times.is_empty() {
+            saved.set_move_times(&times);
+        }
+    }
+    saved.add_tag("ggf");
+    saved.moves = game.history().to_vec();
+    Ok(saved)
+}
+
+/// The games of a GGF file, each in `(;` and `;)`.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{ggf, Field};
+/// let games = ggf::parse("(;GM[Othello]PB[Ann]PW[Bo]TY[8]B[F5]W[d6];)").unwrap();
+/// assert_eq!(games[0].header("White"), Some("Ann"));
+/// assert_eq!(games[0].moves, [Some(Field(5, 3)), Some(Field(3, 2))]);
+///
+/// let error = ggf::parse("(;GM[Othello]TY[8]B[F5//1.5]W[A1];)").unwrap_err();
+/// assert!(error.starts_with("Game 1: Move 2 (A1)"));
+/// ```
+pub fn parse(text: &str) -> Result<Vec<archive::Game>, String> {
+    let mut games = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("(;") {
+        let end = rest[start..]
+            .find(";)")
+            .ok_or_else(|| format!("Game {} is not closed with `;)`", games.len() + 1))?;
+        let game = parse_game(&rest[start + 2..start + end])
+            .map_err(|error| format!("Game {}: {error}", games.len() + 1))?;
+        games.push(game);
+        rest = &rest[start + end + 2..];
+    }
+    Ok(games)
+}
+
+/// `value` with the characters that end a value escaped.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// The game as GGF, on one line. Games of the classic variant can't be written, as GGF
+/// doesn't know its placement phase.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{archive, ggf, Field};
+/// let mut game = archive::Game::new();
+/// game.set_header("White", "Ann");
+/// game.moves = vec![Some(Field(5, 3))];
+/// let written = ggf::write_game(&game).unwrap();
+/// assert!(written.starts_with("(;GM[Othello]PC[reversi]DT[?]PB[Ann]PW[?]RE[?]"));
+/// assert!(written.ends_with("---O*--- ---*O--- -------- -------- -------- *]B[F5];)"));
+/// assert_eq!(ggf::parse(&written).unwrap()[0].moves, game.moves);
+/// ```
+pub fn write_game(saved: &archive::Game) -> Result<String, String> {
+    let start = saved.start()?;
+    if Variant::of(&start) == Variant::Classic {
+        return Err("GGF can't record the placement of the classic variant".to_owned());
+    }
+    let mut game = rules::Game::from_position(start.clone());
+    for (index, &played) in saved.moves.iter().enumerate() {
+        game.replay_move(played)
+            .map_err(|error| format!("Move {}: {error}", index + 1))?;
+    }
+
+    let header = |name| escape(saved.header(name).unwrap_or("?"));
+    let board = game.board();
+    let result = match (game.status(), saved.header("Termination")) {
+        (GameStatus::InProgress, Some(reason @ ("resignation" | "time forfeit"))) => {
+            let sign = match game.current_player() {
+                Color::White => '-',
+                Color::Black => '+',
+            };
+            format!("{sign}64.000:{}", &reason[..1])
+        }
+        (GameStatus::InProgress, _) => "?".to_owned(),
+        _ => {
+            let difference =
+                board.count_pieces(Color::White) as i64 - board.count_pieces(Color::Black) as i64;
+            format!("{difference:+}.000").replace("+0.", "0.")
+        }
+    };
+    let mut written = format!(
+        "(;GM[Othello]PC[{}]DT[{}]PB[{}]PW[{}]RE[{result}]TI[0:00//0:00]TY[8]BO[{}]",
+        saved.header("Site").map_or("reversi".to_owned(), escape),
+        header("Date").replace('.', "-"),
+        header("White"),
+        header("Black"),
+        write_board(&start),
+    );
+    let times = saved.move_times();
+    for (index, &played) in saved.moves.iter().enumerate() {
+        let color = saved.color_of(index + 1);
+        let field = played.map_or("PA".to_owned(), |field| {
+            Notation::Standard.format(field).to_uppercase()
+        });
+        let time = times
+            .get(index)
+            .map_or(String::new(), |time| format!("//{:.1}", time.as_secs_f64()));
+        write!(
+            written,
+            "{}[{field}{time}]",
+            if color == Color::White { 'B' } else { 'W' }
+        )
+        .unwrap();
+    }
+    written.push_str(";)");
+    Ok(written)
+}
+
+/// The games as GGF, one per line, see `write_game`.
+pub fn write(games: &[archive::Game]) -> Result<String, String> {
+    games
+        .iter()
+        .enumerate()
+        .map(|(index, game)| {
+            write_game(game)
+                .map(|line| line + "\n")
+                .map_err(|error| format!("Game {}: {error}", index + 1))
+        })
+        .collect()
+}
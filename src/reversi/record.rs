@@ -0,0 +1,253 @@
+//! A game with everything known about it besides the moves: the players, where and when it
+//! was played, the time control, the result and the time and comment of each move.
+//!
+//! Unlike `rules::Game`, which only follows the rules, a record is meant to be kept and
+//! exported. It is written and read as a game of an archive, see `archive`.
+//!
+//! ```
+//! # use reversi_game::{record::GameRecord, rules::Position, Field};
+//! # use std::time::Duration;
+//! let mut record = GameRecord::new("Ann", "Bo", Position::new());
+//! record.event = Some("Club night".to_owned());
+//! record.push(Some(Field(3, 5)), Duration::from_secs(2));
+//! record.set_comment(1, "the usual");
+//!
+//! let written = record.to_string();
+//! assert!(written.contains("[Event \"Club night\"]"));
+//! assert!(written.contains("1. d3 {the usual} *"));
+//! assert_eq!(written.parse::<GameRecord>(), Ok(record));
+//! ```
+
+use crate::reversi::{
+    archive,
+    rules::{self, Position},
+    Field,
+};
+
+use std::{fmt, str::FromStr, time::Duration};
+
+/// A move of a record with its annotations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMove {
+    /// The field played on, or `None` for a pass.
+    pub played: Option<Field>,
+    /// How long the player took, if it is known.
+    pub time: Option<Duration>,
+    pub comment: Option<String>,
+}
+
+/// A game with its metadata and annotated moves, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub white: String,
+    pub black: String,
+    /// The date like `2026.10.16`, with `??` for unknown parts.
+    pub date: Option<String>,
+    pub event: Option<String>,
+    /// The time control like `300+5`, or `-` for none.
+    pub time_control: Option<String>,
+    /// The final disc counts `White-Black`, or `*` while the game is not finished.
+    pub result: String,
+    /// Why the game ended early, e.g. `resignation`.
+    pub termination: Option<String>,
+    /// The position the moves were played from.
+    pub start: Position,
+    pub moves: Vec<RecordedMove>,
+    /// The comment on the whole game.
+    pub comment: Option<String>,
+    /// The other headers of the archive, e.g. `Site` or `Tags`.
+    pub headers: Vec<(String, String)>,
+}
+
+/// The headers kept in fields of a record instead of `GameRecord::headers`.
+const RECORD_HEADERS: [&str; 9] = [
+    "White",
+    "Black",
+    "Date",
+    "Event",
+    "TimeControl",
+    "Result",
+    "Termination",
+    "Position",
+    "MoveTimes",
+];
+
+impl GameRecord {
+    /// An unfinished game between `white` and `black` from `start`, without moves.
+    pub fn new(white: impl Into<String>, black: impl Into<String>, start: Position) -> Self {
+        GameRecord {
+            white: white.into(),
+            black: black.into(),
+            date: None,
+            event: None,
+            time_control: None,
+            result: "*".to_owned(),
+            termination: None,
+            start,
+            moves: Vec::new(),
+            comment: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Record the next move, which took `time`.
+    pub fn push(&mut self, played: Option<Field>, time: Duration) {
+        self.moves.push(RecordedMove {
+            played,
+            time: Some(time),
+            comment: None,
+        });
+    }
+
+    /// Keep only the first `count` moves, e.g. after some were taken back.
+    pub fn truncate(&mut self, count: usize) {
+        self.moves.truncate(count);
+    }
+
+    /// Set the comment on the `number`th move, starting at 1, or on the whole game for 0.
+    /// An empty comment removes it.
+    ///
+    /// # Panics
+    /// If there is no `number`th move yet.
+    pub fn set_comment(&mut self, number: usize, comment: &str) {
+        let comment = (!comment.trim().is_empty()).then(|| comment.trim().to_owned());
+        match number {
+            0 => self.comment = comment,
+            number => self.moves[number - 1].comment = comment,
+        }
+    }
+
+    /// The value of one of the other headers.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set one of the other headers, replacing its previous value.
+    pub fn set_header(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.headers.iter_mut().find(|(key, _)| key == name) {
+            Some((_, previous)) => *previous = value,
+            None => self.headers.push((name.to_owned(), value)),
+        }
+    }
+
+    /// Remove one of the other headers.
+    pub fn remove_header(&mut self, name: &str) {
+        self.headers.retain(|(key, _)| key != name);
+    }
+
+    /// The game after the moves, or an error for the first invalid one.
+    pub fn replay(&self) -> Result<rules::Game, String> {
+        let mut game = rules::Game::from_position(self.start.clone());
+        for (index, recorded) in self.moves.iter().enumerate() {
+            game.replay_move(recorded.played)
+                .map_err(|error| format!("Move {}: {error}", index + 1))?;
+        }
+        Ok(game)
+    }
+
+    /// The record as a game of an archive. The times are written in the `MoveTimes` header
+    /// if all moves have one.
+    pub fn to_archive(&self) -> archive::Game {
+        let mut game = archive::Game::new();
+        game.set_header("White", self.white.as_str());
+        game.set_header("Black", self.black.as_str());
+        let optional = [
+            ("Date", &self.date),
+            ("Event", &self.event),
+            ("TimeControl", &self.time_control),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                game.set_header(name, value.as_str());
+            }
+        }
+        game.set_header("Result", self.result.as_str());
+        if let Some(termination) = &self.termination {
+            game.set_header("Termination", termination.as_str());
+        }
+        game.set_start(&self.start);
+        for (name, value) in &self.headers {
+            game.set_header(name, value.as_str());
+        }
+
+        let times: Option<Vec<Duration>> =
+            self.moves.iter().map(|recorded| recorded.time).collect();
+        if let Some(times) = times.filter(|times| !times.is_empty()) {
+            game.set_move_times(&times);
+        }
+        game.moves = self.moves.iter().map(|recorded| recorded.played).collect();
+        if let Some(comment) = &self.comment {
+            game.set_comment(0, comment);
+        }
+        for (index, recorded) in self.moves.iter().enumerate() {
+            if let Some(comment) = &recorded.comment {
+                game.set_comment(index + 1, comment);
+            }
+        }
+        game
+    }
+
+    /// The record of a game of an archive, or an error if its start position is invalid or a
+    /// comment comes after the last move.
+    pub fn from_archive(game: &archive::Game) -> Result<Self, String> {
+        let text = |name| game.header(name).map(str::to_owned);
+        let times = game.move_times();
+        let mut record = GameRecord {
+            white: text("White").unwrap_or_else(|| "?".to_owned()),
+            black: text("Black").unwrap_or_else(|| "?".to_owned()),
+            date: text("Date"),
+            event: text("Event"),
+            time_control: text("TimeControl"),
+            result: text("Result").unwrap_or_else(|| "*".to_owned()),
+            termination: text("Termination"),
+            start: game.start()?,
+            moves: game
+                .moves
+                .iter()
+                .enumerate()
+                .map(|(index, &played)| RecordedMove {
+                    played,
+                    time: (times.len() == game.moves.len()).then(|| times[index]),
+                    comment: None,
+                })
+                .collect(),
+            comment: None,
+            headers: game
+                .headers
+                .iter()
+                .filter(|(name, _)| !RECORD_HEADERS.contains(&name.as_str()))
+                .cloned()
+                .collect(),
+        };
+        for (&number, comment) in &game.comments {
+            if number > record.moves.len() {
+                return Err(format!("There is no move {number} to comment on"));
+            }
+            record.set_comment(number, comment);
+        }
+        Ok(record)
+    }
+}
+
+/// Writes the record as a game of an archive.
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_archive())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    /// Parse the only game of an archive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &archive::parse(s)?[..] {
+            [game] => GameRecord::from_archive(game),
+            games => Err(format!("Expected one game, found {}", games.len())),
+        }
+    }
+}
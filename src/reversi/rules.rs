@@ -0,0 +1,479 @@
+//! The order of the turns: whose turn it is after a move or a pass.
+//!
+//! The colors of the discs on a board don't tell who moves next once a player has passed,
+//! so a `Position` keeps track of the side to move itself.
+
+use crate::reversi::{board, Board, Color, Field, GameStatus, Notation, PlaceError, TrackedBoard};
+
+use std::{fmt, str::FromStr};
+
+/// The rules for the start of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    /// Othello: the game starts with two discs of each color crossed in the center.
+    #[default]
+    Standard,
+    /// The original Reversi: the game starts empty, and the players place the first four discs
+    /// on the center fields as they like, see `Board::in_placement_phase`.
+    Classic,
+}
+
+impl Variant {
+    pub const NAMES: [&'static str; 2] = ["standard", "classic"];
+
+    /// The position a game of this variant starts from, with White to move.
+    pub fn start(self) -> Position {
+        match self {
+            Variant::Standard => Position::new(),
+            Variant::Classic => Position::from_board(Board::empty(), Color::White),
+        }
+    }
+
+    /// The variant of a game that started from `start`.
+    pub fn of(start: &Position) -> Variant {
+        if start.board().in_placement_phase() {
+            Variant::Classic
+        } else {
+            Variant::Standard
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(Variant::NAMES[*self as usize])
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Variant::Standard),
+            "classic" => Ok(Variant::Classic),
+            other => Err(format!("Unknown variant `{other}`")),
+        }
+    }
+}
+
+/// A board and the color to move on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// The board with the valid moves, which the game asks for after every move.
+    board: TrackedBoard,
+    to_move: Color,
+}
+
+impl Position {
+    /// The initial position, with White to move.
+    pub fn new() -> Self {
+        Self::from_board(Board::new(), Color::White)
+    }
+
+    /// A position with `to_move` to move on `board`.
+    pub fn from_board(board: Board, to_move: Color) -> Self {
+        Position {
+            board: TrackedBoard::new(board),
+            to_move,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        self.board.board()
+    }
+
+    /// The board with the valid moves of both colors, e.g. to count them without searching.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{rules::Position, Color};
+    /// assert_eq!(Position::new().tracked_board().mobility(Color::Black), 4);
+    /// ```
+    pub fn tracked_board(&self) -> &TrackedBoard {
+        &self.board
+    }
+
+    /// The valid moves of the side to move.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{rules::Position, Field};
+    /// let moves = Position::new().valid_moves();
+    /// assert_eq!(moves.len(), 4);
+    /// assert!(moves.contains(&Field(2, 4)));
+    /// ```
+    pub fn valid_moves(&self) -> Vec<Field> {
+        self.board.valid_moves(self.to_move)
+    }
+
+    /// The color whose turn it is.
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    /// A 64-bit hash of the position like `Board::position_hash`, which also tells whose turn
+    /// it is: for Black to move, the hash of the board is combined by XOR with the next output
+    /// of the same `SplitMix64` sequence, the 129th. The initial position hashes like its board.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{rules::Position, Board, Color};
+    /// let position = Position::new();
+    /// assert_eq!(position.position_hash(), Board::new().position_hash());
+    ///
+    /// // The same discs after a pass.
+    /// let passed = Position::from_board(Board::new(), Color::Black);
+    /// assert_ne!(passed.position_hash(), position.position_hash());
+    /// ```
+    pub fn position_hash(&self) -> u64 {
+        match self.to_move {
+            Color::White => self.board().position_hash(),
+            Color::Black => self.board().position_hash() ^ board::BLACK_TO_MOVE_KEY,
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.board.status()
+    }
+
+    /// Whether the side to move has no valid moves in a game that goes on.
+    pub fn must_pass(&self) -> bool {
+        self.status() == GameStatus::InProgress && self.board.mobility(self.to_move) == 0
+    }
+
+    /// Play a move for the side to move, or pass with `None`, and hand the turn to the other
+    /// color. Passing is only allowed without a valid move, see `must_pass`; a turn lost
+    /// otherwise, e.g. because the time ran out, is given up with `forfeit_turn`.
+    ///
+    /// # Returns
+    /// The fields captured by the move.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{rules::Position, Color, Field, PlaceError};
+    /// let mut position = Position::new();
+    /// assert_eq!(position.play(Some(Field(2, 4))).map(|captured| captured.len()), Ok(1));
+    /// assert_eq!(position.to_move(), Color::Black);
+    ///
+    /// assert_eq!(position.play(Some(Field(0, 0))), Err(PlaceError::CapturesNone));
+    /// assert_eq!(position.play(None), Err(PlaceError::CannotPass));
+    /// assert_eq!(position.to_move(), Color::Black);
+    /// ```
+    pub fn play(&mut self, played: Option<Field>) -> Result<Vec<Field>, PlaceError> {
+        if self.status() != GameStatus::InProgress {
+            return Err(PlaceError::GameOver);
+        }
+
+        let captured = match played {
+            Some(field) => self.board.add_piece(field, self.to_move)?,
+            None if self.must_pass() => Vec::new(),
+            None => return Err(PlaceError::CannotPass),
+        };
+        self.to_move = self.to_move.other();
+        Ok(captured)
+    }
+
+    /// Hand the turn to the other color without a move, even if the side to move has valid
+    /// moves, e.g. because its time ran out.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{rules::Position, Color};
+    /// let mut position = Position::new();
+    /// position.forfeit_turn().unwrap();
+    /// assert_eq!(position.to_move(), Color::Black);
+    /// ```
+    pub fn forfeit_turn(&mut self) -> Result<(), PlaceError> {
+        if self.status() != GameStatus::InProgress {
+            return Err(PlaceError::GameOver);
+        }
+        self.to_move = self.to_move.other();
+        Ok(())
+    }
+}
+
+/// Writes the board like `Board::to_ascii`, followed by e.g. `X to move`.
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = match self.to_move {
+            Color::Black => 'X',
+            Color::White => 'O',
+        };
+        write!(f, "{}\n{side} to move", self.board.to_ascii())
+    }
+}
+
+/// Reads a board drawn like in `Board::from_ascii`, with an optional line saying who is to
+/// move (`X to move`, `black to move`, ...), which is White otherwise.
+/// Rows may also be separated by `/` to fit on one line.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{rules::Position, Board, Color};
+/// let position: Position = Position::new().to_string().parse().unwrap();
+/// assert_eq!(position, Position::new());
+///
+/// let one_line = Board::new().to_ascii().replace('\n', "/") + "/Black to move";
+/// let position: Position = one_line.parse().unwrap();
+/// assert_eq!(position.to_move(), Color::Black);
+/// ```
+impl FromStr for Position {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace('/', "\n");
+        let (side, rows): (Vec<&str>, Vec<&str>) = s
+            .lines()
+            .partition(|line| line.to_lowercase().contains("to move"));
+
+        let to_move = match side.as_slice() {
+            [] => Color::White,
+            [line] => match line
+                .split_whitespace()
+                .next()
+                .map(str::to_lowercase)
+                .as_deref()
+            {
+                Some("x" | "b" | "black" | "*") => Color::Black,
+                Some("o" | "w" | "white") => Color::White,
+                _ => return Err(format!("Unknown side to move in `{line}`")),
+            },
+            _ => return Err("The side to move is given more than once".to_owned()),
+        };
+        Ok(Position::from_board(
+            Board::from_ascii(&rows.join("\n"))?,
+            to_move,
+        ))
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new()
+    }
+}
+
+/// A game from a start position: the position reached and every move played to get there,
+/// including the passes and the forfeited turns, which are kept as passes.
+///
+/// # Examples
+/// ```
+/// # use reversi_game::{Color, Field, Game};
+/// let mut game = Game::new();
+/// game.play(Field(2, 4)).unwrap();
+/// game.forfeit_turn().unwrap();
+/// assert_eq!(game.current_player(), Color::White);
+/// assert_eq!(game.history(), [Some(Field(2, 4)), None]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Game {
+    start: Position,
+    position: Position,
+    history: Vec<Option<Field>>,
+    /// The position before each move of `history`, to restore on `undo`.
+    previous: Vec<Position>,
+    /// The moves taken back by `undo`, the last one first to `redo`.
+    undone: Vec<Option<Field>>,
+}
+
+impl Game {
+    /// A game from the initial position.
+    pub fn new() -> Self {
+        Self::from_position(Position::new())
+    }
+
+    /// A game from `start`.
+    pub fn from_position(start: Position) -> Self {
+        Game {
+            position: start.clone(),
+            start,
+            history: Vec::new(),
+            previous: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// The position the game started from.
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+
+    /// The position after all moves of `history`.
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn board(&self) -> &Board {
+        self.position.board()
+    }
+
+    /// The board with the valid moves of both colors, see `Position::tracked_board`.
+    pub fn tracked_board(&self) -> &TrackedBoard {
+        self.position.tracked_board()
+    }
+
+    /// The color whose turn it is.
+    pub fn current_player(&self) -> Color {
+        self.position.to_move()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.position.status()
+    }
+
+    /// The valid moves of the current player, see `Position::valid_moves`.
+    pub fn valid_moves(&self) -> Vec<Field> {
+        self.position.valid_moves()
+    }
+
+    /// The moves from the start position, `None` for a pass.
+    pub fn history(&self) -> &[Option<Field>] {
+        &self.history
+    }
+
+    /// The moves as an Othello transcript like `F5D6C3`, the way games are exchanged on the
+    /// internet: the fields in standard notation, without the passes, which the rules force.
+    ///
+    /// Othello lets Black move first, but with the colors swapped the games are the same, so
+    /// the fields keep their names. Transcripts always start from the initial position, see
+    /// `from_transcript`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Field, Game};
+    /// let mut game = Game::new();
+    /// game.play(Field(5, 3)).unwrap();
+    /// game.play(Field(3, 2)).unwrap();
+    /// assert_eq!(game.to_transcript(), "F5D6");
+    /// ```
+    pub fn to_transcript(&self) -> String {
+        self.history
+            .iter()
+            .flatten()
+            .map(|&field| Notation::Standard.format(field).to_uppercase())
+            .collect()
+    }
+
+    /// A game from the initial position with the moves of an Othello transcript, see
+    /// `to_transcript`. The passes are made where the rules force them; whitespace and
+    /// lowercase fields are allowed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Color, Field, Game};
+    /// let game = Game::from_transcript("F5D6 c3").unwrap();
+    /// assert_eq!(game.history().len(), 3);
+    /// assert_eq!(game.current_player(), Color::Black);
+    /// assert_eq!(game.to_transcript(), "F5D6C3");
+    ///
+    /// assert!(Game::from_transcript("F5F5").is_err());
+    /// assert!(Game::from_transcript("F5D").is_err());
+    /// ```
+    pub fn from_transcript(transcript: &str) -> Result<Self, String> {
+        let letters: Vec<char> = transcript
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        let mut game = Game::new();
+        for (index, field) in letters.chunks(2).enumerate() {
+            let field: String = field.iter().collect();
+            let invalid = |error| format!("Move {} ({field}): {error}", index + 1);
+            let parsed = Notation::Standard.parse(&field).map_err(invalid)?;
+            if game.position.must_pass() {
+                game.pass().unwrap();
+            }
+            game.play(parsed).map_err(invalid)?;
+        }
+        Ok(game)
+    }
+
+    /// Play `field` for the current player, returning the captured fields.
+    pub fn play(&mut self, field: Field) -> Result<Vec<Field>, PlaceError> {
+        self.play_move(Some(field))
+    }
+
+    /// Let the current player pass, see `Position::play`.
+    pub fn pass(&mut self) -> Result<(), PlaceError> {
+        self.play_move(None).map(drop)
+    }
+
+    /// Give up the turn of the current player, which is kept as a pass in the history,
+    /// see `Position::forfeit_turn`.
+    pub fn forfeit_turn(&mut self) -> Result<(), PlaceError> {
+        self.push(None, true)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Play a move or pass with `None`, keeping it in the history if it is valid.
+    /// The moves taken back before can't be redone after that.
+    pub fn play_move(&mut self, played: Option<Field>) -> Result<Vec<Field>, PlaceError> {
+        let captured = self.push(played, false)?;
+        self.undone.clear();
+        Ok(captured)
+    }
+
+    /// Play a move of a recorded game like `play_move`, where a pass with valid moves is a
+    /// forfeited turn, see `forfeit_turn`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Field, Game, PlaceError};
+    /// let mut game = Game::new();
+    /// assert_eq!(game.play_move(None), Err(PlaceError::CannotPass));
+    /// game.replay_move(None).unwrap();
+    /// assert_eq!(game.history(), [None]);
+    /// ```
+    pub fn replay_move(&mut self, played: Option<Field>) -> Result<Vec<Field>, PlaceError> {
+        let captured = self.push(played, true)?;
+        self.undone.clear();
+        Ok(captured)
+    }
+
+    /// Play `played`, where `None` forfeits the turn if `forfeit` is set and passes otherwise.
+    fn push(&mut self, played: Option<Field>, forfeit: bool) -> Result<Vec<Field>, PlaceError> {
+        let before = self.position.clone();
+        let captured = match played {
+            None if forfeit => self.position.forfeit_turn().map(|()| Vec::new())?,
+            _ => self.position.play(played)?,
+        };
+        self.previous.push(before);
+        self.history.push(played);
+        Ok(captured)
+    }
+
+    /// Take back the last move, restoring the position before it with the flipped discs.
+    /// Returns the move, or `None` at the start of the game.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{Board, Field, Game};
+    /// let mut game = Game::new();
+    /// game.play(Field(2, 4)).unwrap();
+    /// let after = game.board().clone();
+    ///
+    /// assert_eq!(game.undo(), Some(Some(Field(2, 4))));
+    /// assert_eq!(game.board(), &Board::new());
+    /// assert_eq!(game.redo(), Some(Some(Field(2, 4))));
+    /// assert_eq!(game.board(), &after);
+    /// assert_eq!(game.redo(), None);
+    /// ```
+    pub fn undo(&mut self) -> Option<Option<Field>> {
+        let played = self.history.pop()?;
+        self.position = self.previous.pop().unwrap();
+        self.undone.push(played);
+        Some(played)
+    }
+
+    /// Play the last move taken back by `undo` again. Returns the move, or `None` if there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> Option<Option<Field>> {
+        let played = self.undone.pop()?;
+        // A pass that was undone may have been a forfeited turn.
+        self.push(played, true)
+            .expect("an undone move is valid in the position it was played in");
+        Some(played)
+    }
+}
@@ -0,0 +1,213 @@
+//! Reading the game databases of the Fédération Française d'Othello in the WTHOR format.
+//!
+//! A database comes in three kinds of binary files, each starting with a header of 16 bytes:
+//! the games of one year (`WTH_1990.wtb`), the names of the players (`WTHOR.JOU`) and the
+//! names of the tournaments (`WTHOR.TRN`). The games refer to their players and tournament by
+//! their index in the lists of names.
+//!
+//! In Othello Black moves first, so WTHOR's black player is White here and its white player
+//! Black. The fields are the same, e.g. the first move `f5` is `Field(5, 3)`.
+
+use crate::reversi::{archive, rules, Field};
+
+/// The size of the header of every file.
+const HEADER_SIZE: usize = 16;
+/// The size of a game with its 60 moves.
+const GAME_SIZE: usize = 68;
+/// The size of a name in `WTHOR.JOU`, including the terminating zeros.
+const PLAYER_SIZE: usize = 20;
+/// The size of a name in `WTHOR.TRN`, including the terminating zeros.
+const TOURNAMENT_SIZE: usize = 26;
+
+/// The header of a WTHOR file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The day the file was written, as year, month and day.
+    pub created: (u16, u8, u8),
+    /// The number of games in a games file.
+    pub games: u32,
+    /// The number of names in a players or tournaments file.
+    pub names: u16,
+    /// The year the games were played.
+    pub year: u16,
+    /// The number of empty fields from which on `Game::theoretical_score` assumes perfect play.
+    pub depth: u8,
+}
+
+impl Header {
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let header = bytes
+            .get(..HEADER_SIZE)
+            .ok_or("The file is too short for a WTHOR header")?;
+        let u16_at = |index: usize| u16::from_le_bytes([header[index], header[index + 1]]);
+        if !matches!(header[12], 0 | 8) {
+            return Err(format!(
+                "Only games on an 8x8 board are supported, not {0}x{0}",
+                header[12]
+            ));
+        }
+        Ok(Header {
+            created: (
+                u16::from(header[0]) * 100 + u16::from(header[1]),
+                header[2],
+                header[3],
+            ),
+            games: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+            names: u16_at(8),
+            year: u16_at(10),
+            depth: header[14],
+        })
+    }
+}
+
+/// A game of a WTHOR database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    /// The index of the tournament in the tournaments file.
+    pub tournament: u16,
+    /// The index of White's player in the players file.
+    pub white: u16,
+    /// The index of Black's player in the players file.
+    pub black: u16,
+    /// White's discs at the end, with the empty fields counted for the winner.
+    pub score: u8,
+    /// White's discs at the end with perfect play from `Header::depth` empty fields on.
+    pub theoretical_score: u8,
+    /// The moves without the passes, which the rules force anyway.
+    pub moves: Vec<Field>,
+}
+
+impl Game {
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let u16_at = |index: usize| u16::from_le_bytes([bytes[index], bytes[index + 1]]);
+        let moves = bytes[8..]
+            .iter()
+            .take_while(|&&code| code != 0)
+            .map(|&code| {
+                let (rank, file) = (usize::from(code / 10), usize::from(code % 10));
+                if (1..=8).contains(&rank) && (1..=8).contains(&file) {
+                    Ok(Field(file - 1, 8 - rank))
+                } else {
+                    Err(format!("Invalid move {code}"))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Game {
+            tournament: u16_at(0),
+            white: u16_at(2),
+            black: u16_at(4),
+            score: bytes[6],
+            theoretical_score: bytes[7],
+            moves,
+        })
+    }
+
+    /// The game played from the initial position, with the passes the rules force.
+    ///
+    /// # Examples
+    /// ```
+    /// # use reversi_game::{wthor, Field};
+    /// let game = wthor::Game {
+    ///     tournament: 0,
+    ///     white: 0,
+    ///     black: 1,
+    ///     score: 32,
+    ///     theoretical_score: 32,
+    ///     moves: vec![Field(5, 3), Field(3, 2)],
+    /// };
+    /// assert_eq!(game.replay().unwrap().to_transcript(), "F5D6");
+    /// ```
+    pub fn replay(&self) -> Result<rules::Game, String> {
+        let mut game = rules::Game::new();
+        for (index, &field) in self.moves.iter().enumerate() {
+            if game.position().must_pass() {
+                game.pass().unwrap();
+            }
+            game.play(field)
+                .map_err(|error| format!("Move {} ({field}): {error}", index + 1))?;
+        }
+        Ok(game)
+    }
+
+    /// The game for an archive, with the names of the players and the tournament from
+    /// `players` and `tournaments`, see `parse_players` and `parse_tournaments`.
+    pub fn to_archive(
+        &self,
+        header: &Header,
+        players: &[String],
+        tournaments: &[String],
+    ) -> Result<archive::Game, String> {
+        let name = |names: &[String], index: u16| {
+            names
+                .get(usize::from(index))
+                .cloned()
+                .ok_or_else(|| format!("Unknown name number {index}"))
+        };
+        let game = self.replay()?;
+        let mut saved = archive::Game::new();
+        saved.set_header("White", name(players, self.white)?);
+        saved.set_header("Black", name(players, self.black)?);
+        saved.set_header("Date", format!("{}.??.??", header.year));
+        saved.set_header("Result", archive::Game::result_of(game.board()));
+        saved.set_header("Event", name(tournaments, self.tournament)?);
+        saved.add_tag("wthor");
+        saved.moves = game.history().to_vec();
+        Ok(saved)
+    }
+}
+
+/// The header and the games of a games file (`.wtb`).
+pub fn parse_games(bytes: &[u8]) -> Result<(Header, Vec<Game>), String> {
+    let header = Header::parse(bytes)?;
+    let records = &bytes[HEADER_SIZE..];
+    if records.len() != header.games as usize * GAME_SIZE {
+        return Err(format!(
+            "The file should have {} games of {GAME_SIZE} bytes, but has {} bytes of games",
+            header.games,
+            records.len()
+        ));
+    }
+    let games = records
+        .chunks(GAME_SIZE)
+        .enumerate()
+        .map(|(index, record)| {
+            Game::parse(record).map_err(|error| format!("Game {}: {error}", index + 1))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((header, games))
+}
+
+/// The names of the players of a players file (`WTHOR.JOU`), by their index.
+pub fn parse_players(bytes: &[u8]) -> Result<Vec<String>, String> {
+    parse_names(bytes, PLAYER_SIZE)
+}
+
+/// The names of the tournaments of a tournaments file (`WTHOR.TRN`), by their index.
+pub fn parse_tournaments(bytes: &[u8]) -> Result<Vec<String>, String> {
+    parse_names(bytes, TOURNAMENT_SIZE)
+}
+
+/// The names of `size` bytes each, terminated by zeros and in Latin-1 like all WTHOR text.
+fn parse_names(bytes: &[u8], size: usize) -> Result<Vec<String>, String> {
+    let header = Header::parse(bytes)?;
+    let records = &bytes[HEADER_SIZE..];
+    if records.len() != usize::from(header.names) * size {
+        return Err(format!(
+            "The file should have {} names of {size} bytes, but has {} bytes of names",
+            header.names,
+            records.len()
+        ));
+    }
+    Ok(records
+        .chunks(size)
+        .map(|record| {
+            record
+                .iter()
+                .take_while(|&&byte| byte != 0)
+                .map(|&byte| char::from(byte))
+                .collect::<String>()
+                .trim_end()
+                .to_owned()
+        })
+        .collect())
+}
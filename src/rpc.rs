@@ -0,0 +1,260 @@
+//! A JSON-RPC 2.0 interface on the standard input and output for graphical front ends,
+//! with one request or response per line. See the README for the methods.
+
+pub mod json;
+
+use json::Json;
+use reversi_game::{
+    engine::{Engine, Search, SearchLimits},
+    reversi::*,
+    rules::{Position, Variant},
+};
+
+use std::io::{self, BufRead, Write};
+
+/// The depth of `analyze` if none is given.
+const DEFAULT_DEPTH: u8 = 4;
+/// The deepest search `analyze` allows, to keep front ends responsive.
+const MAX_DEPTH: u8 = 10;
+
+// The error codes of the JSON-RPC specification.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// A valid request that the game doesn't allow, e.g. an invalid move.
+const GAME_ERROR: i32 = 1;
+
+/// An error response: the code and the message.
+type RpcError = (i32, String);
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+/// The memory used by the transposition table of `engine` and its limit in bytes.
+fn hash_usage(engine: &Engine) -> Json {
+    let (used, limit) = engine.hash_usage();
+    Json::object([("used", used.into()), ("limit", limit.into())])
+}
+
+/// The board of `game`, whose turn it is, the status, the discs and the moves so far.
+pub fn state(game: &Game) -> Json {
+    let board = game.board();
+    let status = match game.status() {
+        GameStatus::InProgress => "in_progress",
+        GameStatus::Win(Color::White) => "white_won",
+        GameStatus::Win(Color::Black) => "black_won",
+        GameStatus::Draw => "draw",
+    };
+    Json::object([
+        (
+            "board",
+            board
+                .to_ascii()
+                .lines()
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        ("to_move", color_name(game.current_player()).into()),
+        ("status", status.into()),
+        (
+            "discs",
+            Json::object([
+                ("white", board.count_pieces(Color::White).into()),
+                ("black", board.count_pieces(Color::Black).into()),
+            ]),
+        ),
+        (
+            "moves",
+            game.history()
+                .iter()
+                .map(|played| played.map(|field| Notation::Standard.format(field)))
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+    ])
+}
+
+/// The game played over the interface.
+struct Session {
+    game: Game,
+    engine: Engine,
+}
+
+impl Session {
+    fn new() -> Self {
+        Session {
+            game: Game::new(),
+            engine: Engine::new(),
+        }
+    }
+
+    fn state(&self) -> Json {
+        state(&self.game)
+    }
+
+    /// Call `method` with `params`, returning the result.
+    fn call(&mut self, method: &str, params: &Json) -> Result<Json, RpcError> {
+        let invalid = |message: String| (INVALID_PARAMS, message);
+
+        match method {
+            "new_game" => {
+                self.game = Game::from_position(match params.get("position") {
+                    Some(Json::String(position)) => position.parse().map_err(invalid)?,
+                    None | Some(Json::Null) => match params.get("variant") {
+                        Some(Json::String(variant)) => {
+                            variant.parse::<Variant>().map_err(invalid)?.start()
+                        }
+                        None | Some(Json::Null) => Position::new(),
+                        Some(_) => return Err(invalid("`variant` must be a string".to_owned())),
+                    },
+                    Some(_) => return Err(invalid("`position` must be a string".to_owned())),
+                });
+                self.engine.clear_hash();
+                Ok(self.state())
+            }
+            "play" => {
+                let played = match params.get("move") {
+                    Some(Json::String(field)) => {
+                        Some(Notation::Standard.parse(field).map_err(|error| {
+                            invalid(format!("Invalid field `{field}`: {error}"))
+                        })?)
+                    }
+                    Some(Json::Null) => None,
+                    _ => return Err(invalid("`move` must be a field or null to pass".to_owned())),
+                };
+                if played.is_none()
+                    && self.game.status() == GameStatus::InProgress
+                    && !self.game.position().must_pass()
+                {
+                    return Err((GAME_ERROR, "You have valid moves".to_owned()));
+                }
+                let captured = self
+                    .game
+                    .play_move(played)
+                    .map_err(|error| (GAME_ERROR, error.to_string()))?;
+                Ok(Json::object([
+                    (
+                        "captured",
+                        captured
+                            .iter()
+                            .map(|&field| Notation::Standard.format(field))
+                            .collect::<Vec<_>>()
+                            .into(),
+                    ),
+                    ("state", self.state()),
+                ]))
+            }
+            "legal_moves" => Ok(self
+                .game
+                .valid_moves()
+                .iter()
+                .map(|&field| Notation::Standard.format(field))
+                .collect::<Vec<_>>()
+                .into()),
+            "analyze" => {
+                let depth = match params.get("depth") {
+                    None | Some(Json::Null) => DEFAULT_DEPTH,
+                    Some(depth) => depth
+                        .as_u64()
+                        .and_then(|depth| u8::try_from(depth).ok())
+                        .filter(|depth| (1..=MAX_DEPTH).contains(depth))
+                        .ok_or_else(|| {
+                            invalid(format!("`depth` must be a number from 1 to {MAX_DEPTH}"))
+                        })?,
+                };
+                self.engine.reset_counters();
+                let (best_move, score) = self.engine.best_move(
+                    self.game.board(),
+                    self.game.current_player(),
+                    SearchLimits::depth(depth),
+                );
+                Ok(Json::object([
+                    (
+                        "best_move",
+                        best_move
+                            .map(|field| Notation::Standard.format(field))
+                            .into(),
+                    ),
+                    ("score", score.into()),
+                    ("depth", usize::from(depth).into()),
+                    ("nodes", self.engine.nodes().into()),
+                    ("hash", hash_usage(&self.engine)),
+                ]))
+            }
+            "board_state" => Ok(self.state()),
+            other => Err((METHOD_NOT_FOUND, format!("Unknown method `{other}`"))),
+        }
+    }
+
+    /// Handle a request line, returning the response line unless it was a notification.
+    fn handle(&mut self, line: &str) -> Option<Json> {
+        let request: Json = match line.parse() {
+            Ok(request) => request,
+            Err(error) => return Some(response(Json::Null, Err((PARSE_ERROR, error)))),
+        };
+        let id = request.get("id").cloned();
+
+        let result = match (request.get("jsonrpc"), request.get("method")) {
+            (Some(Json::String(version)), Some(Json::String(method))) if version == "2.0" => {
+                let params = request
+                    .get("params")
+                    .cloned()
+                    .unwrap_or(Json::Object(Vec::new()));
+                if matches!(params, Json::Object(_)) {
+                    self.call(method, &params)
+                } else {
+                    Err((INVALID_PARAMS, "`params` must be an object".to_owned()))
+                }
+            }
+            _ => Err((
+                INVALID_REQUEST,
+                "Expected a JSON-RPC 2.0 request with a `method`".to_owned(),
+            )),
+        };
+
+        match (id, &result) {
+            (Some(id), _) => Some(response(id, result)),
+            // Invalid requests are answered even without an id.
+            (None, Err((INVALID_REQUEST, _))) => Some(response(Json::Null, result)),
+            (None, _) => None,
+        }
+    }
+}
+
+fn response(id: Json, result: Result<Json, RpcError>) -> Json {
+    let outcome = match result {
+        Ok(result) => ("result", result),
+        Err((code, message)) => (
+            "error",
+            Json::object([("code", code.into()), ("message", message.into())]),
+        ),
+    };
+    Json::object([("jsonrpc", "2.0".into()), outcome, ("id", id)])
+}
+
+/// Answer requests from the standard input until it ends.
+pub fn run() {
+    let mut session = Session::new();
+    let mut stdout = io::stdout().lock();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle(&line) {
+            if writeln!(stdout, "{response}")
+                .and_then(|()| stdout.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Simultaneous exhibitions: one human plays several bots at once, moving from board to board
+//! while the bots think about their replies.
+
+use crate::{games, play::input};
+use reversi_game::{
+    archive,
+    engine::{Engine, Search, SearchLimits},
+    reversi::*,
+    rules::{Game, Variant},
+};
+
+use std::{
+    sync::{mpsc::RecvTimeoutError, Arc},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use clap::ArgMatches;
+use reversi_game::board::display::style::Colorize;
+
+/// The human plays White on every board, so they move first everywhere.
+const HUMAN: Color = Color::White;
+
+/// One of the games of the exhibition.
+struct Table {
+    game: Game,
+    engine: Arc<Engine>,
+    /// The bot's reply, searched while the human plays on the other boards.
+    reply: Option<JoinHandle<Option<Field>>>,
+    /// The time the human spent on this board.
+    clock: Duration,
+    /// How the game ended if not by the rules, e.g. `resignation`.
+    termination: Option<&'static str>,
+}
+
+impl Table {
+    fn new() -> Self {
+        Table {
+            game: Game::new(),
+            engine: Arc::new(Engine::new()),
+            reply: None,
+            clock: Duration::ZERO,
+            termination: None,
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.termination.is_some() || self.game.status() != GameStatus::InProgress
+    }
+
+    /// Play the bot's finished replies and the human's forced passes until it's the human's
+    /// turn or the bot is still thinking, waiting for the bot if `wait` is set.
+    fn update(&mut self, depth: u8, wait: bool) {
+        while !self.is_over() {
+            if self.game.current_player() == HUMAN {
+                if !self.game.valid_moves().is_empty() {
+                    return;
+                }
+                self.game.pass().unwrap();
+                continue;
+            }
+            match self.reply.take() {
+                Some(reply) if wait || reply.is_finished() => {
+                    let field = reply.join().unwrap();
+                    self.game.play_move(field).unwrap();
+                }
+                Some(reply) => {
+                    self.reply = Some(reply);
+                    return;
+                }
+                None => {
+                    let engine = Arc::clone(&self.engine);
+                    let board = self.game.board().clone();
+                    let color = self.game.current_player();
+                    self.reply = Some(thread::spawn(move || {
+                        engine
+                            .best_move(&board, color, SearchLimits::depth(depth))
+                            .0
+                    }));
+                }
+            }
+        }
+    }
+
+    /// The state of the game in a few words.
+    fn summary(&self) -> String {
+        let outcome = match (self.termination, self.game.status()) {
+            (Some("resignation"), _) => "you resigned",
+            (Some("time forfeit"), _) => "lost on time",
+            (Some(termination), _) => termination,
+            (None, GameStatus::Win(HUMAN)) => "you won",
+            (None, GameStatus::Win(_)) => "you lost",
+            (None, GameStatus::Draw) => "draw",
+            (None, GameStatus::InProgress) if self.reply.is_some() => "the bot thinks",
+            (None, GameStatus::InProgress) => "your move",
+        };
+        let board = self.game.board();
+        format!(
+            "{} {:2} {} {:2}  {:5}  {outcome}",
+            Color::White,
+            board.count_pieces(Color::White),
+            Color::Black,
+            board.count_pieces(Color::Black),
+            format_clock(self.clock),
+        )
+    }
+
+    /// The game for the games directory.
+    fn record(&self, name: &str, bot: &str) -> archive::Game {
+        let mut saved = archive::Game::new();
+        saved.set_header("White", name);
+        saved.set_header("Black", bot);
+        saved.set_header("Date", games::today());
+        saved.set_header("Result", archive::Game::result_of(self.game.board()));
+        saved.set_header("TimeControl", "-");
+        saved.set_header("Variant", Variant::of(self.game.start()).to_string());
+        if let Some(termination) = self.termination {
+            saved.set_header("Termination", termination);
+        }
+        saved.add_tag("simul");
+        saved.moves = self.game.history().to_vec();
+        saved
+    }
+}
+
+/// A clock like `4:05`.
+fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// The next board after `current` that isn't over, `current` itself if it is the only one.
+fn next_table(tables: &[Table], current: usize) -> Option<usize> {
+    (1..=tables.len())
+        .map(|offset| (current + offset) % tables.len())
+        .find(|&index| !tables[index].is_over())
+}
+
+pub fn run(matches: &ArgMatches) {
+    let opponents = usize::from(*matches.get_one::<u8>("opponents").unwrap());
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    let time = matches
+        .get_one::<u64>("time")
+        .map(|&minutes| Duration::from_secs(minutes * 60));
+    let name = matches.get_one::<String>("name").unwrap();
+    let bot = format!("Minimax Bot (depth {depth})");
+
+    let mut tables: Vec<Table> = (0..opponents).map(|_| Table::new()).collect();
+    let mut current = 0;
+    let mut message = None;
+
+    loop {
+        for table in &mut tables {
+            table.update(depth, false);
+        }
+        if tables[current].is_over() {
+            let Some(next) = next_table(&tables, current) else {
+                break;
+            };
+            current = next;
+        }
+        let table = &mut tables[current];
+        table.update(depth, true);
+        if table.is_over() {
+            message = Some(format!("Board {} is over.", current + 1));
+            continue;
+        }
+
+        let overview: Vec<String> = tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| {
+                let marker = if index == current { '>' } else { ' ' };
+                format!("{marker} {}: {}", index + 1, table.summary())
+            })
+            .collect();
+        let table = &mut tables[current];
+        redraw_board(
+            table.game.board(),
+            &DisplayOptions {
+                title: Some(format!(
+                    "Board {} of {opponents} against {bot}",
+                    current + 1
+                )),
+                color: Some(HUMAN),
+                footer: Some(
+                    overview
+                        .into_iter()
+                        .chain(message.take())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+                ..Default::default()
+            },
+        );
+
+        let completions: Vec<String> = table
+            .game
+            .valid_moves()
+            .iter()
+            .map(ToString::to_string)
+            .chain(["next", "resign", "quit", "help"].map(str::to_owned))
+            .collect();
+        let started = Instant::now();
+        let deadline = time.map(|time| started + time.saturating_sub(table.clock));
+        let input = input::edit_line_with_tab(
+            &format!("Board {}, enter a field: ", current + 1),
+            &completions,
+            deadline,
+            Some("next"),
+        );
+        table.clock += started.elapsed();
+
+        let input = match input {
+            Ok(input) => input.trim().to_lowercase(),
+            Err(RecvTimeoutError::Timeout) => {
+                table.termination = Some("time forfeit");
+                message = Some(format!("Time is up on board {}!", current + 1));
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        match input.as_str() {
+            "" | "next" => current = next_table(&tables, current).unwrap_or(current),
+            "resign" => {
+                if input::confirm(&format!("Really resign on board {}?", current + 1)) {
+                    table.termination = Some("resignation");
+                }
+            }
+            "quit" => {
+                if input::confirm("Really leave all unfinished games?") {
+                    break;
+                }
+            }
+            "help" => {
+                message = Some(format!(
+                    "Enter a field like {}, the number of a board to go there, next (or <Tab>) for the next board, resign or quit.",
+                    Field(3, 5)
+                ));
+            }
+            input => match input.parse::<usize>() {
+                Ok(number @ 1..) if number <= opponents => current = number - 1,
+                Ok(_) => message = Some(format!("There are boards 1 to {opponents}.")),
+                Err(_) => match input.parse::<Field>() {
+                    Ok(field) => match table.game.play(field) {
+                        Ok(_) => {
+                            table.update(depth, false);
+                            // Walk on to the next board, as in an exhibition.
+                            current = next_table(&tables, current).unwrap_or(current);
+                        }
+                        Err(error) => message = Some(format!("Invalid move {field}: {error}")),
+                    },
+                    Err(error) => message = Some(format!("Invalid input: {error}")),
+                },
+            },
+        }
+    }
+
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+    println!();
+    for (index, table) in tables.iter_mut().enumerate() {
+        // Unfinished games are left as they are.
+        if table.termination.is_none() && table.game.status() == GameStatus::InProgress {
+            table.termination = Some("abandoned");
+        }
+        match (table.termination, table.game.status()) {
+            (Some("abandoned"), _) => {}
+            (None, GameStatus::Win(HUMAN)) => wins += 1,
+            (None, GameStatus::Draw) => draws += 1,
+            _ => losses += 1,
+        }
+        println!("Board {}: {}", index + 1, table.summary());
+
+        if matches.get_flag("save") {
+            if let Err(error) = games::save(&table.record(name, &bot)) {
+                eprintln!("Failed to save board {}: {error}", index + 1);
+            }
+        }
+    }
+    println!(
+        "\n{}",
+        format!("You won {wins}, drew {draws} and lost {losses} of {opponents} games.").bold()
+    );
+}
@@ -0,0 +1,478 @@
+//! Complete games through the `reversi` binary. The moves are typed into its standard input,
+//! and as its output isn't a terminal, it logs the game line by line (see `play::log`), which
+//! is checked against the same game played with the library.
+
+use reversi_game::{archive, rules, Color, Field, GameStatus, Notation};
+
+use std::{
+    env, fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+/// Runs of the binary with their own games directory and settings file.
+struct Session {
+    directory: PathBuf,
+}
+
+impl Session {
+    fn new(name: &str) -> Self {
+        let directory = env::temp_dir().join(format!("reversi-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        Session { directory }
+    }
+
+    /// The output of `reversi` with `args`, typing the lines of `input`.
+    fn run(&self, args: &[&str], input: &[&str]) -> String {
+        let output = self.output(args, input);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            output.status.success(),
+            "reversi {args:?} failed: {}\n{stdout}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        stdout
+    }
+
+    /// The error message of `reversi` with `args`, which has to fail.
+    fn fail(&self, args: &[&str], input: &[&str]) -> String {
+        let output = self.output(args, input);
+        assert!(
+            !output.status.success(),
+            "reversi {args:?} succeeded: {}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        String::from_utf8(output.stderr).unwrap()
+    }
+
+    fn output(&self, args: &[&str], input: &[&str]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_reversi"))
+            .args(args)
+            .env("REVERSI_GAMES", self.directory.join("games"))
+            .env("REVERSI_CONFIG", self.directory.join("settings"))
+            .env_remove("REVERSI_NOTATION")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        for line in input {
+            writeln!(stdin, "{line}").unwrap();
+        }
+        drop(stdin);
+        child.wait_with_output().unwrap()
+    }
+
+    /// The games saved during the session.
+    fn saved(&self) -> Vec<archive::Game> {
+        let Ok(entries) = fs::read_dir(self.directory.join("games")) else {
+            return Vec::new();
+        };
+        entries
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .flat_map(|contents| archive::parse(&contents).unwrap())
+            .collect()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+/// A move in the log: the ply, the color, the field or `pass` and the discs after it.
+#[derive(Debug, PartialEq, Eq)]
+struct Logged {
+    ply: usize,
+    color: String,
+    played: String,
+    discs: String,
+}
+
+/// The logged moves of `output`. A prompt may come first on the line, as the input isn't echoed.
+fn logged_moves(output: &str) -> Vec<Logged> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let index = words.iter().position(|&word| word == "discs")?;
+            Some(Logged {
+                ply: words[index - 3].parse().unwrap(),
+                color: words[index - 2].to_owned(),
+                played: words[index - 1].to_owned(),
+                discs: words[index + 1].to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// The logged result of `output`, like `36-28 White wins`.
+fn logged_result(output: &str) -> &str {
+    output
+        .lines()
+        .find_map(|line| line.split_once(" result ").map(|(_, result)| result))
+        .unwrap_or_else(|| panic!("No result in the output:\n{output}"))
+}
+
+/// How `game` should have been logged.
+fn expected_moves(game: &rules::Game) -> Vec<Logged> {
+    let mut replayed = rules::Game::from_position(game.start().clone());
+    game.history()
+        .iter()
+        .map(|&played| {
+            let color = format!("{:?}", replayed.current_player());
+            replayed.play_move(played).unwrap();
+            let board = replayed.board();
+            Logged {
+                ply: replayed.history().len(),
+                color,
+                played: played.map_or("pass".to_owned(), |field| Notation::Standard.format(field)),
+                discs: format!(
+                    "{}-{}",
+                    board.count_pieces(Color::White),
+                    board.count_pieces(Color::Black)
+                ),
+            }
+        })
+        .collect()
+}
+
+/// A finished game in which a player has to pass, always playing the `step`th next valid move.
+fn game_with_a_pass() -> rules::Game {
+    (1..)
+        .map(|step| {
+            let mut game = rules::Game::new();
+            while game.status() == GameStatus::InProgress {
+                let moves = game.valid_moves();
+                if moves.is_empty() {
+                    game.pass().unwrap();
+                } else {
+                    let index = (game.history().len() * step + step) % moves.len();
+                    game.play(moves[index]).unwrap();
+                }
+            }
+            game
+        })
+        .find(|game| game.history().contains(&None))
+        .unwrap()
+}
+
+#[test]
+fn full_game_with_passes() {
+    let game = game_with_a_pass();
+    // An empty line passes.
+    let input: Vec<String> = game
+        .history()
+        .iter()
+        .map(|played| played.map_or(String::new(), |field| Notation::Standard.format(field)))
+        .collect();
+    let input: Vec<&str> = input.iter().map(String::as_str).collect();
+
+    let session = Session::new("passes");
+    let output = session.run(&["--player", "--no-confirm"], &input);
+    assert_eq!(logged_moves(&output), expected_moves(&game));
+
+    let board = game.board();
+    let (white, black) = (
+        board.count_pieces(Color::White),
+        board.count_pieces(Color::Black),
+    );
+    let outcome = match game.status() {
+        GameStatus::Win(color) => format!("{color:?} wins"),
+        _ => "draw".to_owned(),
+    };
+    assert_eq!(logged_result(&output), format!("{white}-{black} {outcome}"));
+    assert!(session.saved().is_empty());
+}
+
+#[test]
+fn invalid_input_is_recovered() {
+    let session = Session::new("invalid");
+    let output = session.run(&["--player", "--no-confirm"], &["z9", "a1", "d3", "quit"]);
+    assert!(output.contains("Invalid input"));
+    assert!(output.contains("Invalid move"));
+
+    let mut game = rules::Game::new();
+    game.play(Field(3, 5)).unwrap();
+    assert_eq!(logged_moves(&output), expected_moves(&game));
+    // Nothing is typed when asked whether to save the game.
+    assert_eq!(logged_result(&output), "4-1 unfinished by abandonment");
+    assert!(session.saved().is_empty());
+}
+
+#[test]
+fn resignation_is_reported_and_saved() {
+    let session = Session::new("resign");
+    let output = session.run(&["--player", "--no-confirm", "--save"], &["d3", "resign"]);
+    assert_eq!(logged_result(&output), "4-1 White wins by resignation");
+
+    let saved = session.saved();
+    assert_eq!(saved.len(), 1);
+    assert_eq!(saved[0].header("Termination"), Some("resignation"));
+    assert_eq!(saved[0].moves, [Some(Field(3, 5))]);
+}
+
+#[test]
+fn bots_play_a_full_game() {
+    let session = Session::new("watch");
+    let output = session.run(
+        &["--watch", "--depth", "1", "--watch-delay", "100", "--save"],
+        &[],
+    );
+
+    // Replay the logged moves to check that each is valid and the discs add up.
+    let mut game = rules::Game::new();
+    for logged in logged_moves(&output) {
+        let played = match logged.played.as_str() {
+            "pass" => None,
+            field => Some(Notation::Standard.parse(field).unwrap()),
+        };
+        game.play_move(played).unwrap();
+    }
+    assert_ne!(game.status(), GameStatus::InProgress);
+    assert_eq!(logged_moves(&output), expected_moves(&game));
+    assert!(logged_result(&output).starts_with(&format!(
+        "{}-{}",
+        game.board().count_pieces(Color::White),
+        game.board().count_pieces(Color::Black)
+    )));
+
+    // The search statistics of both bots are kept with the saved game.
+    let saved = session.saved();
+    assert_eq!(saved.len(), 1);
+    for name in ["WhiteStats", "BlackStats"] {
+        assert!(saved[0]
+            .header(name)
+            .is_some_and(|stats| stats.contains("nodes")));
+    }
+}
+
+#[test]
+fn game_is_saved_and_loaded_as_json() {
+    let session = Session::new("json");
+    let file = session.directory.join("game.json");
+    let file = file.to_str().unwrap();
+    session.run(
+        &["--player", "--no-confirm", "--save", file],
+        &["d3", "c3", "quit"],
+    );
+    let saved = fs::read_to_string(file).unwrap();
+    assert!(saved.contains(r#""moves":["d3","c3"]"#));
+    assert!(saved.contains(r#""to_move":"white""#));
+
+    let output = session.run(
+        &["--player", "--no-confirm", "--load", file],
+        &["c4", "quit"],
+    );
+    let mut game = rules::Game::new();
+    for field in [Field(3, 5), Field(2, 5), Field(2, 4)] {
+        game.play(field).unwrap();
+    }
+    assert_eq!(logged_moves(&output), expected_moves(&game)[2..]);
+    assert!(fs::read_to_string(file)
+        .unwrap()
+        .contains(r#""moves":["d3","c3","c4"]"#));
+    assert!(session.saved().is_empty());
+}
+
+#[test]
+fn result_is_posted_to_the_webhook() {
+    use std::{
+        io::{BufRead, BufReader, Read},
+        net::TcpListener,
+        thread,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/results", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request = String::new();
+        reader.read_line(&mut request).unwrap();
+        let mut length = 0;
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            line.clear();
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        write!(&stream, "HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+        (request, String::from_utf8(body).unwrap())
+    });
+
+    let session = Session::new("webhook");
+    let output = session.run(
+        &["--player", "--no-confirm", "--webhook", &url],
+        &["d3", "resign"],
+    );
+    assert_eq!(logged_result(&output), "4-1 White wins by resignation");
+
+    let (request, body) = server.join().unwrap();
+    assert!(request.starts_with("POST /results HTTP/1.1"));
+    for expected in [
+        r#""white":"Player 1","black":"Player 2""#,
+        r#""score":[4,1],"winner":"white","termination":"resignation""#,
+        r#""transcript":"D3","moves":["d3"]"#,
+    ] {
+        assert!(body.contains(expected), "{expected} not in {body}");
+    }
+}
+
+#[test]
+fn quiz_asks_for_better_moves_and_keeps_the_scores() {
+    let mut game = archive::Game::new();
+    game.set_header("White", "Ann");
+    game.set_header("Black", "Bo");
+    let mut played = rules::Game::new();
+    for _ in 0..4 {
+        let field = played.valid_moves()[0];
+        played.play(field).unwrap();
+    }
+    // Annotate White's fifth move like `reversi analyze-batch` with another valid move.
+    let moves = played.valid_moves();
+    let (mistake, better) = (moves[0], moves[1]);
+    played.play(mistake).unwrap();
+    game.moves = played.history().to_vec();
+    game.set_analysis(&[(5, (better, 6))].into());
+    // The comments are for people and may be edited, the quiz only reads the analysis.
+    game.set_comment(5, "d3 was 60 discs better");
+    let better = Notation::Standard.format(better);
+
+    let session = Session::new("quiz");
+    let games = session.directory.join("games");
+    fs::create_dir_all(&games).unwrap();
+    fs::write(games.join("game.rgn"), archive::write(&[game])).unwrap();
+
+    let output = session.run(&["quiz", "--name", "Ann"], &[&better, ""]);
+    assert!(output.contains("Quiz 1/1: game 1, move 5"));
+    assert!(output.contains("Correct!"));
+    assert!(output.contains("This was your first quiz"));
+
+    let output = session.run(&["quiz", "--name", "Ann"], &["skip", ""]);
+    assert!(output.contains(&format!("{better} was 6 discs better")));
+    assert!(output.contains("Earlier quizzes: 1/1"));
+    assert!(output.contains("You found 0% of the better moves, down from 100% before."));
+    let scores = fs::read_to_string(games.join("quizzes")).unwrap();
+    let scores: Vec<&str> = scores
+        .lines()
+        .map(|line| &line[line.find(' ').unwrap()..])
+        .collect();
+    assert_eq!(scores, [" 1 1 1", " 1 0 1"]);
+}
+
+#[test]
+fn boards_are_mirrored_right_to_left() {
+    let session = Session::new("direction");
+    let position = "......../......../......../...OX.../...XO.../......../......../........";
+    let output = session.run(&["diff", position, position, "--direction", "rtl"], &[]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "\u{200E}h g f e d c b a");
+    assert_eq!(lines[4], "\u{200E}. . . X O . . . 5");
+    assert_eq!(lines[5], "\u{200E}. . . O X . . . 4");
+}
+
+#[test]
+fn failed_subcommands_exit_with_an_error() {
+    let session = Session::new("failure");
+    let error = session.fail(&["games", "import", "/nonexistent/game.txt"], &[]);
+    assert!(error.contains("/nonexistent/game.txt"), "{error}");
+    session.fail(&["games", "replay", "1"], &[]);
+    session.fail(&["analyze-batch", "/nonexistent"], &[]);
+    session.fail(&["quiz", "--name", "Nobody"], &[]);
+}
+
+#[test]
+fn deeply_nested_json_is_a_parse_error() {
+    let session = Session::new("json");
+    let nested = "[".repeat(200_000);
+    let output = session.run(
+        &["--json-rpc"],
+        &[
+            &nested,
+            r#"{"jsonrpc":"2.0","id":1,"method":"board_state"}"#,
+        ],
+    );
+    let mut lines = output.lines();
+    assert!(
+        lines.next().unwrap().contains(r#""code":-32700"#),
+        "{output}"
+    );
+    assert!(lines.next().unwrap().contains(r#""id":1"#), "{output}");
+}
+
+#[test]
+fn passing_with_valid_moves_is_a_game_error() {
+    let session = Session::new("json-pass");
+    let output = session.run(
+        &["--json-rpc"],
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"play","params":{"move":null}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"board_state"}"#,
+        ],
+    );
+    let mut lines = output.lines();
+    let error = lines.next().unwrap();
+    assert!(error.contains(r#""code":1"#), "{output}");
+    assert!(error.contains("You have valid moves"), "{output}");
+    assert!(lines.next().unwrap().contains(r#""moves":[]"#), "{output}");
+}
+
+#[test]
+fn analysis_reports_the_memory_of_the_hash() {
+    let session = Session::new("json-hash");
+    let output = session.run(
+        &["--json-rpc"],
+        &[r#"{"jsonrpc":"2.0","id":1,"method":"analyze","params":{"depth":3}}"#],
+    );
+    assert!(output.contains(r#""hash":{"used":"#), "{output}");
+    assert!(output.contains(r#","limit":"#), "{output}");
+}
+
+#[test]
+fn analysis_searches_at_least_one_ply_and_at_most_to_the_end() {
+    let session = Session::new("analyze-depth");
+    let board = session.directory.join("end.txt");
+    fs::write(&board, format!("{}XOXO....\n", "XOXOXOXO\n".repeat(7))).unwrap();
+    let load = format!("load {}", board.display());
+    let output = session.run(
+        &["analyze"],
+        &["go 0", "deeper 0", &load, "go 255", "deeper", "quit"],
+    );
+    assert!(output.contains("Invalid depth `0`"), "{output}");
+    assert!(output.contains("Invalid number of plies `0`"), "{output}");
+    assert!(output.contains("Depth 4: best move"), "{output}");
+    assert!(
+        output.contains("Depth 4 already reaches the end of the game"),
+        "{output}"
+    );
+}
+
+#[test]
+fn completions_and_man_page_include_nested_subcommands() {
+    let session = Session::new("generate");
+    let bash = session.run(&["completions", "bash"], &[]);
+    assert!(bash.contains("\"games,export\")"), "{bash}");
+    assert!(bash.contains("--csv"), "{bash}");
+    let zsh = session.run(&["completions", "zsh"], &[]);
+    assert!(zsh.contains("_reversi_games_export() {"), "{zsh}");
+    assert!(zsh.contains("--csv"), "{zsh}");
+    let fish = session.run(&["completions", "fish"], &[]);
+    assert!(
+        fish.contains(
+            "__fish_seen_subcommand_from games; and __fish_seen_subcommand_from export' -l csv"
+        ),
+        "{fish}"
+    );
+
+    let manpage = session.run(&["manpage"], &[]);
+    assert!(manpage.contains(".SS reversi games export"), "{manpage}");
+    assert!(manpage.contains("\\-\\-csv"), "{manpage}");
+}